@@ -0,0 +1,47 @@
+(() => {
+  const params = new URLSearchParams(location.search);
+  const charsPerSecond = Number(params.get("chars_per_second") || 40);
+  const hold = Number(params.get("hold") || 8) * 1000;
+  document.documentElement.style.setProperty("--accent", params.get("accent") || "#00f5d4");
+
+  const box = document.getElementById("box");
+  const who = document.getElementById("who");
+  const text = document.getElementById("text");
+  const queue = [];
+  let busy = false;
+
+  function next() {
+    const alert = queue.shift();
+    if (!alert) {
+      busy = false;
+      return;
+    }
+    busy = true;
+    who.textContent = alert.user;
+    text.textContent = "";
+    box.classList.remove("hidden");
+    let i = 0;
+    const timer = setInterval(() => {
+      i += 1;
+      text.textContent = alert.text.slice(0, i);
+      if (i >= alert.text.length) {
+        clearInterval(timer);
+        setTimeout(() => {
+          box.classList.add("hidden");
+          setTimeout(next, 400);
+        }, hold);
+      }
+    }, 1000 / charsPerSecond);
+  }
+
+  window.addEventListener("twitchdesk:event", (e) => {
+    if (e.detail.type !== "ai_alert") return;
+    queue.push({ user: e.detail.user, text: e.detail.text });
+    if (!busy) next();
+  });
+
+  if (params.get("mock") === "true") {
+    queue.push({ user: "mock_viewer", text: "mock_viewer just summoned the hype train!" });
+    next();
+  }
+})();
@@ -0,0 +1,53 @@
+(() => {
+  const params = new URLSearchParams(location.search);
+  const accent = params.get("accent") || "#7c5cff";
+  const duration = Number(params.get("duration") || 5) * 1000;
+  document.documentElement.style.setProperty("--accent", accent);
+  document.body.style.fontFamily = params.get("font") || "system-ui";
+
+  const box = document.getElementById("alert");
+  const title = document.getElementById("alert-title");
+  const message = document.getElementById("alert-message");
+  const queue = [];
+  let showing = false;
+
+  function describe(e) {
+    switch (e.type) {
+      case "follow": return [`${e.user} followed!`, ""];
+      case "sub": return [`${e.user} subscribed!`, ""];
+      case "resub": return [`${e.user} resubscribed for ${e.months} months!`, e.message || ""];
+      case "cheer": return [`${e.user} cheered ${e.bits} bits!`, e.message || ""];
+      case "raid": return [`${e.user} is raiding with ${e.viewers} viewers!`, ""];
+      default: return null;
+    }
+  }
+
+  function next() {
+    const item = queue.shift();
+    if (!item) {
+      showing = false;
+      return;
+    }
+    showing = true;
+    [title.textContent, message.textContent] = item;
+    box.classList.remove("hidden");
+    setTimeout(() => {
+      box.classList.add("hidden");
+      setTimeout(next, 400);
+    }, duration);
+  }
+
+  function push(e) {
+    const item = describe(e);
+    if (!item) return;
+    queue.push(item);
+    if (!showing) next();
+  }
+
+  window.addEventListener("twitchdesk:event", (e) => push(e.detail));
+
+  if (params.get("mock") === "true") {
+    push({ type: "follow", user: "mock_viewer" });
+    push({ type: "cheer", user: "mock_viewer", bits: 500, message: "Great stream!" });
+  }
+})();
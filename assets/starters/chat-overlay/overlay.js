@@ -0,0 +1,30 @@
+(() => {
+  const params = new URLSearchParams(location.search);
+  const maxMessages = Number(params.get("max_messages") || 8);
+  document.documentElement.style.setProperty("--name-color", params.get("name_color") || "#00f5d4");
+
+  const list = document.getElementById("chat");
+  list.classList.toggle("fade", params.get("fade") !== "false");
+
+  function add(user, text) {
+    const item = document.createElement("li");
+    const name = document.createElement("span");
+    name.className = "name";
+    name.textContent = user;
+    item.append(name, document.createTextNode(text));
+    list.append(item);
+    while (list.children.length > maxMessages) {
+      list.firstElementChild.remove();
+    }
+  }
+
+  window.addEventListener("twitchdesk:event", (e) => {
+    if (e.detail.type === "chat") add(e.detail.user, e.detail.message);
+  });
+
+  if (params.get("mock") === "true") {
+    add("mock_viewer", "Hello chat!");
+    add("another_viewer", "That play was clean");
+    add("mock_viewer", "GG");
+  }
+})();
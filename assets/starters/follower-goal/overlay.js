@@ -0,0 +1,27 @@
+(() => {
+  const params = new URLSearchParams(location.search);
+  const goal = Math.max(1, Number(params.get("goal") || 100));
+  let current = Number(params.get("current") || 0);
+  document.documentElement.style.setProperty("--bar-color", params.get("bar_color") || "#7c5cff");
+  document.getElementById("goal-title").textContent = params.get("title") || "Follower goal";
+
+  const count = document.getElementById("goal-count");
+  const bar = document.getElementById("goal-bar");
+
+  function render() {
+    count.textContent = `${current} / ${goal}`;
+    bar.style.width = `${Math.min(100, (current / goal) * 100)}%`;
+  }
+
+  window.addEventListener("twitchdesk:event", (e) => {
+    if (e.detail.type === "follow") {
+      current += 1;
+      render();
+    }
+  });
+
+  if (params.get("mock") === "true" && current === 0) {
+    current = Math.round(goal * 0.6);
+  }
+  render();
+})();
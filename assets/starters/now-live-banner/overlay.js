@@ -0,0 +1,15 @@
+(() => {
+  const params = new URLSearchParams(location.search);
+  document.documentElement.style.setProperty("--accent", params.get("accent") || "#e91916");
+  document.getElementById("channel").textContent = params.get("channel") || "yourchannel";
+  document.getElementById("subtitle").textContent = params.get("subtitle") || "Stream starting now";
+
+  // A raid replaces the subtitle for a moment.
+  const subtitle = document.getElementById("subtitle");
+  window.addEventListener("twitchdesk:event", (e) => {
+    if (e.detail.type !== "raid") return;
+    const previous = subtitle.textContent;
+    subtitle.textContent = `Welcome raiders from ${e.detail.user}!`;
+    setTimeout(() => (subtitle.textContent = previous), 8000);
+  });
+})();
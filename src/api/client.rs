@@ -0,0 +1,458 @@
+use std::time::Duration;
+
+use reqwest::{Method, Request, RequestBuilder};
+use serde::de::DeserializeOwned;
+use tracing::warn;
+
+use crate::models::{
+    AiAlertCreateRequest, AiAlertDetailResponse, AiAlertFireRequest, AiAlertFireResponse,
+    AiAlertPublicStatusResponse, AiAlertUpdateRequest, AiAlertsListResponse, AiTokenStatusResponse,
+    AiTokenUpsertRequest, AuthLoginRequest, AuthLoginResponse, AuthRegisterRequest,
+    AuthRegisterResponse, ChannelAddRequest, ChannelStatus, ChannelsResponse, MeResponse,
+    TemplateAsset, TemplateAssetsResponse, TemplateCreateRequest, TemplateDetailResponse,
+    TemplateDuplicateRequest, TemplateRenameRequest, TemplateTagsRequest, TemplateVersionCreateRequest, TemplateVersionResponse, TemplateVersionUpdateRequest,
+    TemplatesListResponse, TwitchOAuthStartResponse, TwitchValidateResponse, UserConfig,
+};
+
+use super::{is_transient, note_retry, ApiError, RetryPolicy};
+
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Cheap-to-clone handle to the TwitchDesk API.
+///
+/// Clones share the underlying `reqwest::Client` (and therefore its connection pool).
+#[derive(Clone)]
+pub(crate) struct ApiClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    timeout: Duration,
+    retry: RetryPolicy,
+}
+
+impl ApiClient {
+    pub(crate) fn new(http: reqwest::Client, base_url: &str) -> Result<Self, ApiError> {
+        let base_url = base_url.trim().trim_end_matches('/').to_string();
+        if base_url.is_empty() {
+            return Err(ApiError::Config("Missing API base URL".to_string()));
+        }
+        Ok(Self {
+            http,
+            base_url,
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    /// Per-attempt timeout (each retry gets the full timeout again).
+    pub(crate) fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub(crate) fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Attach a bearer token. Fails if the token is missing/blank.
+    pub(crate) fn with_token(mut self, token: Option<&str>) -> Result<Self, ApiError> {
+        let token = token
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| ApiError::Config("Missing access token. Login first.".to_string()))?;
+        self.token = Some(token.to_string());
+        Ok(self)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let req = self.http.request(method, self.url(path));
+        match self.token.as_deref() {
+            Some(token) => req.header("Authorization", format!("Bearer {}", token)),
+            None => req,
+        }
+    }
+
+    /// Send a request and return the raw body of a successful response.
+    ///
+    /// Idempotent, unconditional requests are retried on transient failures according to the
+    /// retry policy.
+    async fn send(&self, req: RequestBuilder) -> Result<String, ApiError> {
+        let body = self.send_bytes(req).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Like [`Self::send`], for binary responses.
+    async fn send_bytes(&self, req: RequestBuilder) -> Result<Vec<u8>, ApiError> {
+        let mut request = req.timeout(self.timeout).build()?;
+        let retryable = self.retry.allows(&request);
+
+        let mut attempt = 0;
+        loop {
+            let next = if retryable && attempt < self.retry.max_retries {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            match (self.execute(request).await, next) {
+                (Err(e), Some(next)) if is_transient(&e) => {
+                    let delay = self.retry.delay(attempt);
+                    warn!(error = %e, attempt = attempt + 1, ?delay, "api request failed; retrying");
+                    tokio::time::sleep(delay).await;
+                    note_retry();
+                    attempt += 1;
+                    request = next;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+
+    async fn execute(&self, request: Request) -> Result<Vec<u8>, ApiError> {
+        let resp = self.http.execute(request).await?;
+        let status = resp.status();
+        let body = resp.bytes().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(ApiError::from_response(status, &String::from_utf8_lossy(&body)));
+        }
+        Ok(body.to_vec())
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T, ApiError> {
+        let body = self.send(req).await?;
+        Ok(serde_json::from_str::<T>(&body)?)
+    }
+
+    async fn send_empty(&self, req: RequestBuilder) -> Result<(), ApiError> {
+        self.send(req).await.map(|_| ())
+    }
+
+    // -------------------------------
+    // Health
+    // -------------------------------
+
+    /// Single-shot probe (no retries); callers apply their own short timeout.
+    pub(crate) async fn health(&self) -> Result<(), ApiError> {
+        let request = self.request(Method::GET, "/health").build()?;
+        self.execute(request).await.map(|_| ())
+    }
+
+    // -------------------------------
+    // Auth / user
+    // -------------------------------
+
+    pub(crate) async fn register(
+        &self,
+        req: &AuthRegisterRequest,
+    ) -> Result<AuthRegisterResponse, ApiError> {
+        self.send_json(self.request(Method::POST, "/v1/auth/register").json(req))
+            .await
+    }
+
+    pub(crate) async fn login(&self, req: &AuthLoginRequest) -> Result<AuthLoginResponse, ApiError> {
+        self.send_json(self.request(Method::POST, "/v1/auth/login").json(req))
+            .await
+    }
+
+    pub(crate) async fn me(&self) -> Result<MeResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/users/me")).await
+    }
+
+    pub(crate) async fn update_me(&self, cfg: &UserConfig) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::PUT, "/v1/users/me").json(cfg))
+            .await
+    }
+
+    // -------------------------------
+    // Twitch
+    // -------------------------------
+
+    pub(crate) async fn twitch_validate(&self) -> Result<TwitchValidateResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/twitch/validate"))
+            .await
+    }
+
+    pub(crate) async fn twitch_oauth_start(&self) -> Result<TwitchOAuthStartResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/twitch/oauth/start"))
+            .await
+    }
+
+    /// Raw Twitch user lookup; the body is returned as-is for display.
+    pub(crate) async fn twitch_users(&self, login: &str) -> Result<String, ApiError> {
+        let path = format!("/v1/twitch/users?login={}", urlencoding::encode(login.trim()));
+        self.send(self.request(Method::GET, &path)).await
+    }
+
+    // -------------------------------
+    // Channels
+    // -------------------------------
+
+    pub(crate) async fn channel_statuses(&self) -> Result<Vec<ChannelStatus>, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/channels/status"))
+            .await
+    }
+
+    pub(crate) async fn add_channel(&self, req: &ChannelAddRequest) -> Result<ChannelsResponse, ApiError> {
+        self.send_json(self.request(Method::POST, "/v1/channels").json(req))
+            .await
+    }
+
+    pub(crate) async fn remove_channel(&self, login: &str) -> Result<ChannelsResponse, ApiError> {
+        let path = format!("/v1/channels/{}", urlencoding::encode(login.trim()));
+        self.send_json(self.request(Method::DELETE, &path)).await
+    }
+
+    // -------------------------------
+    // AI Alerts
+    // -------------------------------
+
+    pub(crate) async fn ai_token_status(&self) -> Result<AiTokenStatusResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/ai/token")).await
+    }
+
+    pub(crate) async fn ai_token_upsert(&self, req: &AiTokenUpsertRequest) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::PUT, "/v1/ai/token").json(req))
+            .await
+    }
+
+    pub(crate) async fn ai_token_delete(&self) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::DELETE, "/v1/ai/token")).await
+    }
+
+    pub(crate) async fn ai_alerts(&self) -> Result<AiAlertsListResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/v1/ai/alerts")).await
+    }
+
+    pub(crate) async fn ai_alert(&self, alert_id: &str) -> Result<AiAlertDetailResponse, ApiError> {
+        self.send_json(self.request(Method::GET, &ai_alert_path(alert_id)))
+            .await
+    }
+
+    pub(crate) async fn ai_alert_create(
+        &self,
+        req: &AiAlertCreateRequest,
+    ) -> Result<AiAlertDetailResponse, ApiError> {
+        self.send_json(self.request(Method::POST, "/v1/ai/alerts").json(req))
+            .await
+    }
+
+    pub(crate) async fn ai_alert_update(
+        &self,
+        alert_id: &str,
+        req: &AiAlertUpdateRequest,
+    ) -> Result<AiAlertDetailResponse, ApiError> {
+        self.send_json(self.request(Method::PUT, &ai_alert_path(alert_id)).json(req))
+            .await
+    }
+
+    pub(crate) async fn ai_alert_delete(&self, alert_id: &str) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::DELETE, &ai_alert_path(alert_id)))
+            .await
+    }
+
+    pub(crate) async fn ai_alert_public_status(
+        &self,
+        alert_id: &str,
+    ) -> Result<AiAlertPublicStatusResponse, ApiError> {
+        let path = format!("{}/public", ai_alert_path(alert_id));
+        self.send_json(self.request(Method::GET, &path)).await
+    }
+
+    pub(crate) async fn ai_alert_public_enable(
+        &self,
+        alert_id: &str,
+    ) -> Result<AiAlertPublicStatusResponse, ApiError> {
+        let path = format!("{}/public", ai_alert_path(alert_id));
+        self.send_json(self.request(Method::POST, &path)).await
+    }
+
+    pub(crate) async fn ai_alert_public_disable(
+        &self,
+        alert_id: &str,
+    ) -> Result<AiAlertPublicStatusResponse, ApiError> {
+        let path = format!("{}/public", ai_alert_path(alert_id));
+        self.send_json(self.request(Method::DELETE, &path)).await
+    }
+
+    /// Fire a public alert trigger. `public_url` is absolute and unauthenticated.
+    pub(crate) async fn ai_alert_fire(
+        &self,
+        public_url: &str,
+        req: &AiAlertFireRequest,
+    ) -> Result<AiAlertFireResponse, ApiError> {
+        self.send_json(self.http.post(public_url).json(req)).await
+    }
+
+    // -------------------------------
+    // Templates
+    // -------------------------------
+
+    pub(crate) async fn templates(&self) -> Result<TemplatesListResponse, ApiError> {
+        self.send_json(self.request(Method::GET, "/api/templates")).await
+    }
+
+    pub(crate) async fn template(&self, template_id: &str) -> Result<TemplateDetailResponse, ApiError> {
+        self.send_json(self.request(Method::GET, &template_path(template_id)))
+            .await
+    }
+
+    pub(crate) async fn template_create(
+        &self,
+        req: &TemplateCreateRequest,
+    ) -> Result<TemplateDetailResponse, ApiError> {
+        self.send_json(self.request(Method::POST, "/api/templates").json(req))
+            .await
+    }
+
+    pub(crate) async fn template_rename(
+        &self,
+        template_id: &str,
+        req: &TemplateRenameRequest,
+    ) -> Result<TemplateDetailResponse, ApiError> {
+        self.send_json(self.request(Method::PATCH, &template_path(template_id)).json(req))
+            .await
+    }
+
+    /// Replace the tags of a template (same endpoint as a rename).
+    pub(crate) async fn template_set_tags(
+        &self,
+        template_id: &str,
+        req: &TemplateTagsRequest,
+    ) -> Result<TemplateDetailResponse, ApiError> {
+        self.send_json(self.request(Method::PATCH, &template_path(template_id)).json(req))
+            .await
+    }
+
+    pub(crate) async fn template_delete(&self, template_id: &str) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::DELETE, &template_path(template_id)))
+            .await
+    }
+
+    pub(crate) async fn template_duplicate(
+        &self,
+        template_id: &str,
+        req: &TemplateDuplicateRequest,
+    ) -> Result<TemplateDetailResponse, ApiError> {
+        let path = format!("{}/duplicate", template_path(template_id));
+        self.send_json(self.request(Method::POST, &path).json(req)).await
+    }
+
+    pub(crate) async fn template_version(
+        &self,
+        template_id: &str,
+        version: &str,
+    ) -> Result<TemplateVersionResponse, ApiError> {
+        self.send_json(self.request(Method::GET, &version_path(template_id, version)))
+            .await
+    }
+
+    pub(crate) async fn template_version_create(
+        &self,
+        template_id: &str,
+        req: &TemplateVersionCreateRequest,
+    ) -> Result<TemplateVersionResponse, ApiError> {
+        let path = format!("{}/versions", template_path(template_id));
+        self.send_json(self.request(Method::POST, &path).json(req)).await
+    }
+
+    /// `PUT` a version. With `if_match` (the `updated_at` the edits started from) the server
+    /// rejects the save with 409/412 if the version was saved by someone else in between.
+    pub(crate) async fn template_version_update(
+        &self,
+        template_id: &str,
+        version: &str,
+        req: &TemplateVersionUpdateRequest,
+        if_match: Option<&str>,
+    ) -> Result<TemplateVersionResponse, ApiError> {
+        let path = version_path(template_id, version);
+        let mut builder = self.request(Method::PUT, &path).json(req);
+        if let Some(updated_at) = if_match.filter(|v| !v.is_empty()) {
+            builder = builder.header("If-Match", format!("\"{updated_at}\""));
+        }
+        self.send_json(builder).await
+    }
+
+    pub(crate) async fn template_version_publish(
+        &self,
+        template_id: &str,
+        version: &str,
+    ) -> Result<TemplateVersionResponse, ApiError> {
+        let path = format!("{}/publish", version_path(template_id, version));
+        self.send_json(self.request(Method::POST, &path)).await
+    }
+
+    pub(crate) async fn template_version_unpublish(
+        &self,
+        template_id: &str,
+        version: &str,
+    ) -> Result<TemplateVersionResponse, ApiError> {
+        let path = format!("{}/publish", version_path(template_id, version));
+        self.send_json(self.request(Method::DELETE, &path)).await
+    }
+
+    pub(crate) async fn template_version_delete(&self, template_id: &str, version: &str) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::DELETE, &version_path(template_id, version)))
+            .await
+    }
+
+    pub(crate) async fn template_assets(
+        &self,
+        template_id: &str,
+        version: &str,
+    ) -> Result<TemplateAssetsResponse, ApiError> {
+        let path = format!("{}/assets", version_path(template_id, version));
+        self.send_json(self.request(Method::GET, &path)).await
+    }
+
+    pub(crate) async fn template_asset(&self, template_id: &str, version: &str, name: &str) -> Result<Vec<u8>, ApiError> {
+        self.send_bytes(self.request(Method::GET, &asset_path(template_id, version, name)))
+            .await
+    }
+
+    /// Upload (or replace) the asset `name` of a version.
+    pub(crate) async fn template_asset_upload(
+        &self,
+        template_id: &str,
+        version: &str,
+        name: &str,
+        mime_type: &str,
+        bytes: Vec<u8>,
+    ) -> Result<TemplateAsset, ApiError> {
+        let builder = self
+            .request(Method::PUT, &asset_path(template_id, version, name))
+            .header("Content-Type", mime_type)
+            .body(bytes);
+        self.send_json(builder).await
+    }
+
+    pub(crate) async fn template_asset_delete(&self, template_id: &str, version: &str, name: &str) -> Result<(), ApiError> {
+        self.send_empty(self.request(Method::DELETE, &asset_path(template_id, version, name)))
+            .await
+    }
+}
+
+fn ai_alert_path(alert_id: &str) -> String {
+    format!("/v1/ai/alerts/{}", urlencoding::encode(alert_id.trim()))
+}
+
+fn template_path(template_id: &str) -> String {
+    format!("/api/templates/{}", urlencoding::encode(template_id.trim()))
+}
+
+fn version_path(template_id: &str, version: &str) -> String {
+    format!(
+        "{}/versions/{}",
+        template_path(template_id),
+        urlencoding::encode(version.trim())
+    )
+}
+
+fn asset_path(template_id: &str, version: &str, name: &str) -> String {
+    format!("{}/assets/{}", version_path(template_id, version), urlencoding::encode(name))
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// Error returned by every [`super::ApiClient`] call.
+#[derive(Debug)]
+pub(crate) enum ApiError {
+    /// The client is not usable yet (missing base URL or access token).
+    Config(String),
+    /// The request never produced an HTTP response (DNS, TLS, connection reset, ...).
+    Network(reqwest::Error),
+    /// The request did not finish in time.
+    Timeout,
+    /// 401: the bearer token is missing, expired or revoked (or the login was wrong).
+    Unauthorized(String),
+    /// 403: authenticated, but not allowed to touch this resource.
+    Forbidden(String),
+    /// 404
+    NotFound(String),
+    /// 409: the resource changed on the server since it was loaded.
+    Conflict(String),
+    /// 400/422 with optional per-field messages (keyed by request field name).
+    Validation {
+        message: String,
+        fields: BTreeMap<String, String>,
+    },
+    /// 5xx
+    Server { status: StatusCode, message: String },
+    /// Any other non-success status.
+    Http { status: StatusCode, message: String },
+    /// The API answered with a body we could not parse.
+    Decode(serde_json::Error),
+}
+
+/// Error bodies we know how to read. The backend is not fully consistent, so
+/// accept `error` or `message`, and `fields`/`errors` as a map or a list.
+#[derive(Debug, Default, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    fields: Option<serde_json::Value>,
+    #[serde(default)]
+    errors: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    /// Build an error from a non-success response.
+    pub(crate) fn from_response(status: StatusCode, body: &str) -> Self {
+        let parsed = serde_json::from_str::<ErrorBody>(body).unwrap_or_default();
+        let message = parsed
+            .message
+            .clone()
+            .or_else(|| parsed.error.clone())
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or_else(|| body.trim().to_string());
+
+        match status {
+            StatusCode::UNAUTHORIZED => ApiError::Unauthorized(message),
+            StatusCode::FORBIDDEN => ApiError::Forbidden(message),
+            StatusCode::NOT_FOUND => ApiError::NotFound(message),
+            StatusCode::CONFLICT | StatusCode::PRECONDITION_FAILED => ApiError::Conflict(message),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                let mut fields = BTreeMap::new();
+                for v in [parsed.fields, parsed.errors].into_iter().flatten() {
+                    collect_field_errors(&v, &mut fields);
+                }
+                ApiError::Validation { message, fields }
+            }
+            s if s.is_server_error() => ApiError::Server { status, message },
+            _ => ApiError::Http { status, message },
+        }
+    }
+
+    /// Per-field messages for validation errors (`None` otherwise).
+    pub(crate) fn field_errors(&self) -> Option<&BTreeMap<String, String>> {
+        match self {
+            ApiError::Validation { fields, .. } if !fields.is_empty() => Some(fields),
+            _ => None,
+        }
+    }
+}
+
+fn collect_field_errors(v: &serde_json::Value, out: &mut BTreeMap<String, String>) {
+    match v {
+        // {"name": "is required"} or {"name": ["is required", "too short"]}
+        serde_json::Value::Object(map) => {
+            for (field, msg) in map {
+                let msg = match msg {
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Array(items) => items
+                        .iter()
+                        .filter_map(|i| i.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    other => other.to_string(),
+                };
+                out.insert(field.clone(), msg);
+            }
+        }
+        // [{"field": "name", "message": "is required"}]
+        serde_json::Value::Array(items) => {
+            for item in items {
+                let field = item.get("field").and_then(|f| f.as_str());
+                let msg = item.get("message").and_then(|m| m.as_str());
+                if let (Some(field), Some(msg)) = (field, msg) {
+                    out.insert(field.to_string(), msg.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn write_with_detail(f: &mut fmt::Formatter<'_>, what: &str, detail: &str) -> fmt::Result {
+    if detail.trim().is_empty() {
+        write!(f, "{what}")
+    } else {
+        write!(f, "{what}: {detail}")
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Config(msg) => write!(f, "{msg}"),
+            ApiError::Network(e) => write!(f, "network error: {e}"),
+            ApiError::Timeout => write!(f, "request timed out"),
+            ApiError::Unauthorized(msg) => write_with_detail(f, "Unauthorized", msg),
+            ApiError::Forbidden(msg) => write_with_detail(f, "Forbidden", msg),
+            ApiError::NotFound(msg) => write_with_detail(f, "Not found", msg),
+            ApiError::Conflict(msg) => write_with_detail(f, "Conflict", msg),
+            ApiError::Validation { message, fields } => {
+                write_with_detail(f, "Invalid input", message)?;
+                for (field, msg) in fields {
+                    write!(f, "\n  {field}: {msg}")?;
+                }
+                Ok(())
+            }
+            ApiError::Server { status, message } => {
+                write_with_detail(f, &format!("Server error ({status})"), message)
+            }
+            ApiError::Http { status, message } => write_with_detail(f, &format!("HTTP {status}"), message),
+            ApiError::Decode(e) => write!(f, "invalid response: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::Network(e) => Some(e),
+            ApiError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ApiError::Timeout
+        } else {
+            ApiError::Network(e)
+        }
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(e: serde_json::Error) -> Self {
+        ApiError::Decode(e)
+    }
+}
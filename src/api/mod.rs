@@ -0,0 +1,12 @@
+//! Typed client for the TwitchDesk cloud API.
+//!
+//! The UI (and anything else in this crate) should go through [`ApiClient`]
+//! instead of building `reqwest` requests by hand.
+
+mod client;
+mod error;
+mod retry;
+
+pub(crate) use client::*;
+pub(crate) use error::*;
+pub(crate) use retry::*;
@@ -0,0 +1,96 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::{header, Method, Request, StatusCode};
+
+use super::ApiError;
+
+/// Exponential backoff (with jitter) for idempotent requests.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    /// Extra attempts after the first one. `0` disables retries.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(300),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Only requests that are safe to repeat are retried: POST may create duplicates, and a
+    /// conditional request whose first attempt did reach the server would be rejected as a
+    /// conflict with its own write.
+    pub(crate) fn allows(&self, request: &Request) -> bool {
+        let conditional = [header::IF_MATCH, header::IF_UNMODIFIED_SINCE]
+            .iter()
+            .any(|h| request.headers().contains_key(h));
+        self.max_retries > 0
+            && !conditional
+            && matches!(*request.method(), Method::GET | Method::PUT | Method::DELETE)
+    }
+
+    /// Delay before retry number `attempt` (0-based): full backoff capped at
+    /// `max_delay`, then a random point in its upper half.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        exp.mul_f64(0.5 + 0.5 * jitter_fraction())
+    }
+}
+
+/// Errors that are worth another attempt (the request may not have reached the server,
+/// or the server/proxy was temporarily unavailable).
+pub(crate) fn is_transient(e: &ApiError) -> bool {
+    match e {
+        ApiError::Network(_) | ApiError::Timeout => true,
+        ApiError::Server { status, .. } => matches!(
+            *status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        ),
+        ApiError::Http { status, .. } => *status == StatusCode::TOO_MANY_REQUESTS,
+        _ => false,
+    }
+}
+
+/// Uniform-ish value in `[0, 1)`; good enough to spread out retries without pulling in `rand`.
+fn jitter_fraction() -> f64 {
+    let mut h = std::collections::hash_map::RandomState::new().build_hasher();
+    h.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    (h.finish() % 10_000) as f64 / 10_000.0
+}
+
+tokio::task_local! {
+    static RETRIES: Cell<u32>;
+}
+
+/// Record a retry against the surrounding [`count_retries`] scope (if any).
+pub(crate) fn note_retry() {
+    let _ = RETRIES.try_with(|c| c.set(c.get() + 1));
+}
+
+/// Run `fut` and report how many retries its API calls needed in total.
+pub(crate) async fn count_retries<F: Future>(fut: F) -> (F::Output, u32) {
+    RETRIES
+        .scope(Cell::new(0), async move {
+            let out = fut.await;
+            (out, RETRIES.with(Cell::get))
+        })
+        .await
+}
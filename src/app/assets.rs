@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use tracing::warn;
+
+use crate::api::{ApiClient, ApiError};
+use crate::preview_server::PreviewAsset;
+use crate::templates::{asset_mime_type, asset_name, format_size, MAX_ASSET_BYTES};
+
+use super::{jobs::JobKind, state::TwitchDeskApp, trash::TemplateConfirm};
+
+/// Copy the assets of one version to another (a duplicate). Assets the target already
+/// has with the same size are skipped, so servers that copy them on their own are fine.
+pub(crate) async fn copy_template_assets(
+    api: &ApiClient,
+    from: (&str, &str),
+    to: (&str, &str),
+) -> Result<usize, ApiError> {
+    let source = api.template_assets(from.0, from.1).await?.assets;
+    if source.is_empty() {
+        return Ok(0);
+    }
+    let existing = api.template_assets(to.0, to.1).await?.assets;
+    let mut copied = 0;
+    for asset in source {
+        if existing.iter().any(|e| e.name == asset.name && e.size == asset.size) {
+            continue;
+        }
+        let bytes = api.template_asset(from.0, from.1, &asset.name).await?;
+        api.template_asset_upload(to.0, to.1, &asset.name, &asset.mime_type, bytes)
+            .await?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+impl TwitchDeskApp {
+    fn templates_selected_pair(&self) -> Option<(String, String)> {
+        Some((
+            self.account.templates_selected_template_id.clone()?,
+            self.account.templates_selected_version.clone()?,
+        ))
+    }
+
+    /// Reload the asset list of the selected version (and the local preview's copies).
+    pub(crate) fn templates_refresh_assets(&mut self) {
+        let Some((template_id, version)) = self.templates_selected_pair() else {
+            self.account.templates_assets.clear();
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            {
+                let (template_id, version) = (template_id.clone(), version.clone());
+                async move { api.template_assets(&template_id, &version).await }
+            },
+            move |app, result| {
+                // Another version was opened in the meantime.
+                if app.templates_selected_pair() != Some((template_id, version)) {
+                    return;
+                }
+                match result {
+                    Ok(list) => {
+                        app.account.templates_assets = list.assets;
+                        app.templates_sync_preview_assets();
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "template assets failed");
+                        app.account.templates_status = format!("Load assets failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    /// Download the selected version's assets into the local preview server, if it runs.
+    pub(crate) fn templates_sync_preview_assets(&mut self) {
+        if self.preview_server.is_none() {
+            return;
+        }
+        let Some((template_id, version)) = self.templates_selected_pair() else {
+            if let Some(server) = &self.preview_server {
+                server.set_assets(BTreeMap::new());
+            }
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+        let assets = self.account.templates_assets.clone();
+
+        self.spawn_job(
+            JobKind::Templates,
+            {
+                let (template_id, version) = (template_id.clone(), version.clone());
+                async move {
+                    let mut files = BTreeMap::new();
+                    for asset in assets {
+                        let bytes = api.template_asset(&template_id, &version, &asset.name).await?;
+                        files.insert(
+                            asset.name,
+                            PreviewAsset {
+                                mime_type: asset.mime_type,
+                                bytes: bytes.into(),
+                            },
+                        );
+                    }
+                    Ok(files)
+                }
+            },
+            move |app, result| {
+                if app.templates_selected_pair() != Some((template_id, version)) {
+                    return;
+                }
+                match result {
+                    Ok(files) => {
+                        if let Some(server) = &app.preview_server {
+                            server.set_assets(files);
+                        }
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "preview assets failed");
+                        app.account.templates_status = format!("Local preview assets failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    /// Upload the file named in the asset path field to the selected version. A file with
+    /// the same name replaces the existing asset.
+    pub(crate) fn templates_upload_asset(&mut self) {
+        let Some((template_id, version)) = self.templates_selected_pair() else {
+            self.account.templates_status = "Select a version first".to_string();
+            return;
+        };
+        let path = PathBuf::from(self.account.templates_asset_path.trim());
+        if path.as_os_str().is_empty() {
+            self.account.templates_status = "Enter the path of a file to upload".to_string();
+            return;
+        }
+        let name = match asset_name(&path) {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = format!("Upload failed: {e:#}");
+                return;
+            }
+        };
+        let mime_type = asset_mime_type(&name).unwrap_or("application/octet-stream");
+        let size = match std::fs::metadata(&path) {
+            Ok(meta) => meta.len(),
+            Err(e) => {
+                self.account.templates_status = format!("Upload failed: read {}: {e}", path.display());
+                return;
+            }
+        };
+        if size > MAX_ASSET_BYTES {
+            self.account.templates_status = format!(
+                "Upload failed: {name} is {} (the limit is {})",
+                format_size(size),
+                format_size(MAX_ASSET_BYTES)
+            );
+            return;
+        }
+        let bytes = match std::fs::read(&path) {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = format!("Upload failed: read {}: {e}", path.display());
+                return;
+            }
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+        let replaces = self.account.templates_assets.iter().any(|a| a.name == name);
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                api.template_asset_upload(&template_id, &version, &name, mime_type, bytes)
+                    .await
+            },
+            move |app, result| match result {
+                Ok(asset) => {
+                    app.account.templates_asset_path.clear();
+                    let verb = if replaces { "Replaced" } else { "Uploaded" };
+                    app.account.templates_status = format!("{verb} asset {} ({}).", asset.name, format_size(asset.size));
+                    app.templates_refresh_assets();
+                }
+                Err(e) => {
+                    warn!(error = ?e, "asset upload failed");
+                    app.account.templates_status = format!("Upload failed: {e:#}");
+                }
+            },
+        );
+    }
+
+    pub(crate) fn templates_confirm_delete_asset(&mut self, name: &str) {
+        let Some((template_id, version)) = self.templates_selected_pair() else {
+            return;
+        };
+        self.account.templates_confirm = Some(TemplateConfirm::DeleteAsset {
+            template_id,
+            version,
+            name: name.to_string(),
+        });
+    }
+
+    pub(crate) fn templates_delete_asset(&mut self, template_id: String, version: String, name: String) {
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                api.template_asset_delete(&template_id, &version, &name).await?;
+                Ok(name)
+            },
+            |app, result| match result {
+                Ok(name) => {
+                    app.account.templates_status = format!("Deleted asset {name}.");
+                    app.templates_refresh_assets();
+                }
+                Err(e) => {
+                    warn!(error = ?e, "asset delete failed");
+                    app.account.templates_status = format!("Delete asset failed: {e:#}");
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use directories::UserDirs;
+use std::path::PathBuf;
+use tracing::warn;
+
+use crate::models::{TemplateCreateRequest, TemplateVersionCreateRequest};
+use crate::templates::{
+    read_template_bundle, safe_dir_name, unique_template_name, write_template_bundle, BundleVersion,
+    TemplateBundle, TemplateSources, BUNDLE_EXTENSION,
+};
+
+use super::{jobs::JobKind, state::TwitchDeskApp, unsaved::GuardedAction};
+
+impl TwitchDeskApp {
+    /// Where to write an export of `template_name`: the path field if it names a file,
+    /// otherwise `<folder>/<template>.twitchdesk.zip` in that folder, the workspace folder,
+    /// Downloads or home.
+    fn bundle_export_path(&self, template_name: &str) -> Option<PathBuf> {
+        let typed = self.templates_bundle_path.trim();
+        if !typed.is_empty() && !PathBuf::from(typed).is_dir() {
+            return Some(PathBuf::from(typed));
+        }
+        let dir = if !typed.is_empty() {
+            PathBuf::from(typed)
+        } else if !self.local.profile().workspace_dir.trim().is_empty() {
+            PathBuf::from(self.local.profile().workspace_dir.trim())
+        } else {
+            let dirs = UserDirs::new()?;
+            dirs.download_dir()
+                .map(|d| d.to_path_buf())
+                .unwrap_or_else(|| dirs.home_dir().to_path_buf())
+        };
+        Some(dir.join(format!("{}.{BUNDLE_EXTENSION}", safe_dir_name(template_name))))
+    }
+
+    /// Download every version of the selected template and write them to a zip bundle.
+    pub(crate) fn templates_export_bundle(&mut self) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                let detail = api.template(&template_id).await?;
+                let mut versions = Vec::with_capacity(detail.versions.len());
+                for summary in &detail.versions {
+                    let v = api.template_version(&detail.id, &summary.version).await?;
+                    versions.push(BundleVersion {
+                        version: v.version.clone(),
+                        is_published: summary.is_published,
+                        updated_at: v.updated_at.clone(),
+                        sources: TemplateSources::from(&v),
+                    });
+                }
+                Ok(TemplateBundle {
+                    name: detail.name,
+                    versions,
+                })
+            },
+            |app, result| {
+                let bundle = match result {
+                    Ok(v) => v,
+                    Err(e) => {
+                        warn!(error = ?e, "template export failed");
+                        app.account.templates_status = format!("Export failed: {e:#}");
+                        return;
+                    }
+                };
+                let Some(path) = app.bundle_export_path(&bundle.name) else {
+                    app.account.templates_status = "Export failed: enter a file path".to_string();
+                    return;
+                };
+                match write_template_bundle(&path, &bundle) {
+                    Ok(()) => {
+                        app.templates_bundle_path = path.display().to_string();
+                        app.account.templates_status = format!(
+                            "Exported {} ({} version(s)) to {}",
+                            bundle.name,
+                            bundle.versions.len(),
+                            path.display()
+                        );
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "write template bundle failed");
+                        app.account.templates_status = format!("Export failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    /// Recreate a template from a zip bundle. The name gets a " (2)" style suffix if a
+    /// template with that name already exists.
+    pub(crate) fn templates_import_bundle(&mut self) {
+        let path = PathBuf::from(self.templates_bundle_path.trim());
+        if path.as_os_str().is_empty() {
+            self.account.templates_status = "Enter the path of a bundle to import".to_string();
+            return;
+        }
+        let bundle = match read_template_bundle(&path) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error = ?e, "read template bundle failed");
+                self.account.templates_status = format!("Import failed: {e:#}");
+                return;
+            }
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                // Fresh list, so names created elsewhere since the last refresh count too.
+                let taken: Vec<String> = api.templates().await?.templates.into_iter().map(|t| t.name).collect();
+                let name = unique_template_name(&bundle.name, &taken);
+                let created = api.template_create(&TemplateCreateRequest { name: name.clone() }).await?;
+
+                let filled = async {
+                    for v in &bundle.versions {
+                        if !created.versions.iter().any(|s| s.version == v.version) {
+                            api.template_version_create(
+                                &created.id,
+                                &TemplateVersionCreateRequest {
+                                    new_version: v.version.clone(),
+                                    source_version: None,
+                                },
+                            )
+                            .await?;
+                        }
+                        let req = v.sources.to_update_request();
+                        api.template_version_update(&created.id, &v.version, &req, None).await?;
+                    }
+                    // The version a new template starts with, unless the bundle has one by
+                    // that label.
+                    for default in &created.versions {
+                        if !bundle.versions.iter().any(|v| v.version == default.version) {
+                            api.template_version_delete(&created.id, &default.version).await?;
+                        }
+                    }
+                    if let Some(published) = bundle.versions.iter().find(|v| v.is_published) {
+                        api.template_version_publish(&created.id, &published.version).await?;
+                    }
+                    Ok(())
+                }
+                .await;
+                if let Err(e) = filled {
+                    // Don't leave a half-imported template behind.
+                    if let Err(cleanup) = api.template_delete(&created.id).await {
+                        warn!(error = ?cleanup, "remove partly imported template failed");
+                    }
+                    return Err(e);
+                }
+                Ok((created.id, name, bundle.versions.len()))
+            },
+            |app, result| match result {
+                Ok((id, name, count)) => {
+                    app.account.templates_status = format!("Imported {name} ({count} version(s)).");
+                    app.templates_refresh_list();
+                    app.templates_guard(GuardedAction::SelectTemplate(id));
+                }
+                Err(e) => {
+                    warn!(error = ?e, "template import failed");
+                    app.account.templates_status = format!("Import failed: {e:#}");
+                }
+            },
+        );
+    }
+}
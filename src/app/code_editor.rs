@@ -0,0 +1,150 @@
+use std::ops::Range;
+
+use crate::templates::{
+    byte_to_char, char_offset, find_matches, line_char_range, replace_all, replace_match, FindQuery, TemplateFile,
+};
+
+use super::state::{TemplatesEditorTab, TwitchDeskApp};
+
+/// Find/replace and go-to-line state of the template code editor.
+#[derive(Default)]
+pub(crate) struct CodeEditorState {
+    pub(crate) find_open: bool,
+    pub(crate) replace_open: bool,
+    /// Focus the find field on the next frame.
+    pub(crate) focus_find: bool,
+    pub(crate) query: FindQuery,
+    pub(crate) replacement: String,
+    /// Index of the selected match.
+    pub(crate) current: usize,
+    pub(crate) goto_open: bool,
+    pub(crate) goto_line: String,
+    pub(crate) focus_goto: bool,
+    pub(crate) pending: Option<PendingSelection>,
+}
+
+/// Selection to put into a file's editor and scroll to on the next frame.
+pub(crate) struct PendingSelection {
+    pub(crate) file: TemplateFile,
+    /// Char range.
+    pub(crate) range: Range<usize>,
+    /// Move keyboard focus to the editor (find steps keep it in the find field).
+    pub(crate) focus: bool,
+}
+
+impl TwitchDeskApp {
+    pub(crate) fn templates_text(&self, file: TemplateFile) -> &str {
+        match file {
+            TemplateFile::Html => &self.account.templates_index_html,
+            TemplateFile::Css => &self.account.templates_style_css,
+            TemplateFile::Js => &self.account.templates_overlay_js,
+        }
+    }
+
+    pub(crate) fn templates_text_mut(&mut self, file: TemplateFile) -> &mut String {
+        match file {
+            TemplateFile::Html => &mut self.account.templates_index_html,
+            TemplateFile::Css => &mut self.account.templates_style_css,
+            TemplateFile::Js => &mut self.account.templates_overlay_js,
+        }
+    }
+
+    /// Open `file` and select `line` (1-based), placing the cursor at `column` if given.
+    pub(crate) fn templates_jump_to(&mut self, file: TemplateFile, line: usize, column: Option<usize>) {
+        self.account.templates_editor_tab = TemplatesEditorTab::for_file(file);
+        let text = self.templates_text(file);
+        let range = match column {
+            Some(column) => {
+                let at = char_offset(text, line, column);
+                at..at
+            }
+            None => line_char_range(text, line),
+        };
+        self.code_editor.pending = Some(PendingSelection { file, range, focus: true });
+    }
+
+    /// Byte ranges of the find matches in the open file.
+    pub(crate) fn code_editor_matches(&self) -> Vec<Range<usize>> {
+        match self.code_editor.query.compile() {
+            Ok(Some(re)) => find_matches(&re, self.templates_text(self.account.templates_editor_tab.file())),
+            _ => vec![],
+        }
+    }
+
+    /// Select the next (or previous) match.
+    pub(crate) fn code_editor_find_step(&mut self, forward: bool) {
+        let matches = self.code_editor_matches();
+        if matches.is_empty() {
+            return;
+        }
+        let n = matches.len();
+        let state = &mut self.code_editor;
+        state.current = if state.current >= n {
+            0
+        } else if forward {
+            (state.current + 1) % n
+        } else {
+            (state.current + n - 1) % n
+        };
+        self.code_editor_select_match(&matches);
+    }
+
+    pub(crate) fn code_editor_select_match(&mut self, matches: &[Range<usize>]) {
+        let file = self.account.templates_editor_tab.file();
+        let Some(m) = matches.get(self.code_editor.current) else {
+            return;
+        };
+        let text = self.templates_text(file);
+        let range = byte_to_char(text, m.start)..byte_to_char(text, m.end);
+        self.code_editor.pending = Some(PendingSelection { file, range, focus: false });
+    }
+
+    pub(crate) fn code_editor_replace_current(&mut self) {
+        let Ok(Some(re)) = self.code_editor.query.compile() else {
+            return;
+        };
+        let file = self.account.templates_editor_tab.file();
+        let matches = find_matches(&re, self.templates_text(file));
+        let Some(range) = matches.get(self.code_editor.current).cloned() else {
+            return;
+        };
+        let query = self.code_editor.query.clone();
+        let replacement = self.code_editor.replacement.clone();
+        replace_match(&query, &re, self.templates_text_mut(file), range, &replacement);
+
+        // The next match now has the replaced one's index.
+        let matches = find_matches(&re, self.templates_text(file));
+        if self.code_editor.current >= matches.len() {
+            self.code_editor.current = 0;
+        }
+        self.code_editor_select_match(&matches);
+    }
+
+    pub(crate) fn code_editor_replace_all(&mut self) {
+        let Ok(Some(re)) = self.code_editor.query.compile() else {
+            return;
+        };
+        let file = self.account.templates_editor_tab.file();
+        let query = self.code_editor.query.clone();
+        let replacement = self.code_editor.replacement.clone();
+        let count = replace_all(&query, &re, self.templates_text_mut(file), &replacement);
+        self.code_editor.current = 0;
+        self.account.templates_status = format!("Replaced {count} match(es) in {}.", file.file_name());
+    }
+
+    pub(crate) fn code_editor_goto(&mut self) {
+        let file = self.account.templates_editor_tab.file();
+        let input = self.code_editor.goto_line.trim().to_string();
+        let (line, column) = match input.split_once(':') {
+            Some((l, c)) => (l.trim().parse::<usize>(), c.trim().parse::<usize>().ok()),
+            None => (input.parse::<usize>(), None),
+        };
+        match line {
+            Ok(line) if line >= 1 => {
+                self.code_editor.goto_open = false;
+                self.templates_jump_to(file, line, column);
+            }
+            _ => self.account.templates_status = format!("Not a line number: {input}"),
+        }
+    }
+}
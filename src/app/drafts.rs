@@ -0,0 +1,414 @@
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::models::{Draft, DraftContent};
+use crate::storage;
+use crate::templates::{sort_versions, TemplateSources};
+
+use super::{jobs::JobKind, state::{TemplatesEditorTab, TwitchDeskApp}, types::View};
+
+/// How often dirty editor buffers are written to the drafts folder.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Bookkeeping for draft autosave.
+pub(crate) struct DraftAutosave {
+    last_run: Instant,
+    /// Drafts this session has on disk, keyed by (profile, draft key).
+    written: HashMap<(String, String), DraftContent>,
+    /// Profiles whose leftover drafts were already offered for recovery.
+    checked_profiles: HashSet<String>,
+    /// Profiles whose offered drafts were already compared with the server.
+    server_checked_profiles: HashSet<String>,
+}
+
+impl Default for DraftAutosave {
+    fn default() -> Self {
+        Self {
+            last_run: Instant::now(),
+            written: HashMap::new(),
+            checked_profiles: HashSet::new(),
+            server_checked_profiles: HashSet::new(),
+        }
+    }
+}
+
+/// How a leftover draft relates to the current server copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DraftServerState {
+    /// The server copy is still the one the draft started from.
+    Unchanged,
+    /// Someone saved the item since the draft started.
+    Changed,
+    /// The server could not be asked (offline, deleted, ...).
+    Unknown,
+}
+
+pub(crate) struct RecoverableDraft {
+    pub(crate) draft: Draft,
+    pub(crate) server: DraftServerState,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl TwitchDeskApp {
+    pub(crate) fn ai_alerts_is_dirty(&self) -> bool {
+        self.account.ai_alerts_loaded
+            .as_ref()
+            .is_some_and(|(name, prompt)| *name != self.account.ai_alerts_name || *prompt != self.account.ai_alerts_prompt)
+    }
+
+    /// Drafts for the buffers that currently have unsaved edits.
+    fn current_drafts(&self) -> Vec<(String, DraftContent)> {
+        let mut drafts = Vec::new();
+        if let (true, Some(template_id), Some(version)) = (
+            self.templates_is_dirty(),
+            self.account.templates_selected_template_id.clone(),
+            self.account.templates_selected_version.clone(),
+        ) {
+            drafts.push((
+                self.account.templates_loaded_updated_at.clone(),
+                DraftContent::Template {
+                    template_name: self
+                        .account
+                        .templates_selected_template_name
+                        .clone()
+                        .unwrap_or_else(|| template_id.clone()),
+                    template_id,
+                    version,
+                    index_html: self.account.templates_index_html.clone(),
+                    style_css: self.account.templates_style_css.clone(),
+                    overlay_js: self.account.templates_overlay_js.clone(),
+                },
+            ));
+        }
+        let new_alert_empty = self.account.ai_alerts_selected_id.is_none() && self.account.ai_alerts_prompt.trim().is_empty();
+        if self.ai_alerts_is_dirty() && !new_alert_empty {
+            drafts.push((
+                self.account.ai_alerts_loaded_updated_at.clone(),
+                DraftContent::AiAlert {
+                    alert_id: self.account.ai_alerts_selected_id.clone(),
+                    name: self.account.ai_alerts_name.clone(),
+                    prompt: self.account.ai_alerts_prompt.clone(),
+                },
+            ));
+        }
+        drafts
+    }
+
+    /// Called every frame: write dirty buffers to disk and remove drafts that were saved or discarded.
+    pub(crate) fn tick_drafts(&mut self, ctx: &egui::Context) {
+        if self.local_state_recovery.is_some() {
+            return;
+        }
+        let profile = self.local.active_profile.clone();
+        // Leftover drafts must not be overwritten before the user decided about them; other
+        // buffers keep autosaving meanwhile.
+        let offered: HashSet<(String, String)> = self
+            .drafts_recovery
+            .iter()
+            .flatten()
+            .map(|item| (item.draft.profile.clone(), item.draft.content.key()))
+            .collect();
+        let current: Vec<_> = self
+            .current_drafts()
+            .into_iter()
+            .filter(|(_, content)| !offered.contains(&(profile.clone(), content.key())))
+            .collect();
+        if !current.is_empty() {
+            ctx.request_repaint_after(AUTOSAVE_INTERVAL);
+        }
+        if self.drafts.last_run.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.drafts.last_run = Instant::now();
+
+        let mut keep = offered;
+        for (base_updated_at, content) in current {
+            let id = (profile.clone(), content.key());
+            keep.insert(id.clone());
+            if self.drafts.written.get(&id) == Some(&content) {
+                continue;
+            }
+            let draft = Draft {
+                profile: profile.clone(),
+                base_updated_at,
+                saved_at: unix_now(),
+                content: content.clone(),
+            };
+            match storage::save_draft(&draft) {
+                Ok(()) => {
+                    self.drafts.written.insert(id, content);
+                }
+                Err(e) => warn!(error = ?e, "draft autosave failed"),
+            }
+        }
+
+        let stale: Vec<_> = self
+            .drafts
+            .written
+            .keys()
+            .filter(|id| id.0 == profile && !keep.contains(*id))
+            .cloned()
+            .collect();
+        for id in stale {
+            if let Err(e) = storage::delete_draft(&id.0, &id.1) {
+                warn!(error = ?e, "draft cleanup failed");
+            }
+            self.drafts.written.remove(&id);
+        }
+    }
+
+    /// Offer drafts left by a previous run of the active profile for recovery. They are read
+    /// from disk once per profile and session, without waiting for the API; the comparison
+    /// with the server copies follows as soon as there is a session to ask with.
+    pub(crate) fn drafts_check_recovery(&mut self) {
+        let profile = self.local.active_profile.clone();
+        if self.drafts.checked_profiles.insert(profile.clone()) {
+            match storage::load_drafts(&profile) {
+                Ok(drafts) if !drafts.is_empty() => {
+                    let items = drafts.into_iter().map(|draft| RecoverableDraft {
+                        draft,
+                        server: DraftServerState::Unknown,
+                    });
+                    self.drafts_recovery.get_or_insert_with(Vec::new).extend(items);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = ?e, "load drafts failed"),
+            }
+        }
+        self.drafts_check_server();
+    }
+
+    /// Compare the active profile's offered drafts with the server. Runs once per profile
+    /// and session; without a session the drafts stay `Unknown` until the next call.
+    fn drafts_check_server(&mut self) {
+        let profile = self.local.active_profile.clone();
+        let drafts: Vec<Draft> = self
+            .drafts_recovery
+            .iter()
+            .flatten()
+            .filter(|item| item.draft.profile == profile)
+            .map(|item| item.draft.clone())
+            .collect();
+        if drafts.is_empty() || self.drafts.server_checked_profiles.contains(&profile) {
+            return;
+        }
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error = ?e, "draft server check skipped");
+                return;
+            }
+        };
+        self.drafts.server_checked_profiles.insert(profile);
+
+        self.spawn_job(
+            JobKind::Drafts,
+            async move {
+                let mut checked = Vec::new();
+                for draft in drafts {
+                    // (server updated_at, draft content equals the server copy)
+                    let server = match &draft.content {
+                        DraftContent::Template {
+                            template_id,
+                            version,
+                            index_html,
+                            style_css,
+                            overlay_js,
+                            ..
+                        } => api.template_version(template_id, version).await.ok().map(|v| {
+                            let same = v.index_html == *index_html
+                                && v.style_css == *style_css
+                                && v.overlay_js == *overlay_js;
+                            (v.updated_at, same)
+                        }),
+                        DraftContent::AiAlert {
+                            alert_id: Some(alert_id),
+                            name,
+                            prompt,
+                        } => api
+                            .ai_alert(alert_id)
+                            .await
+                            .ok()
+                            .map(|a| (a.updated_at, a.name == *name && a.prompt == *prompt)),
+                        DraftContent::AiAlert { alert_id: None, .. } => None,
+                    };
+                    checked.push((draft, server));
+                }
+                Ok(checked)
+            },
+            |app, result| {
+                let Ok(checked) = result else {
+                    return;
+                };
+                let Some(items) = app.drafts_recovery.as_mut() else {
+                    return;
+                };
+                for (draft, server) in checked {
+                    // The user may have restored or discarded it meanwhile.
+                    let Some(i) = items.iter().position(|item| item.draft == draft) else {
+                        continue;
+                    };
+                    items[i].server = match server {
+                        // Nothing to recover.
+                        Some((_, true)) => {
+                            let _ = storage::delete_draft(&draft.profile, &draft.content.key());
+                            items.remove(i);
+                            continue;
+                        }
+                        Some((updated_at, false)) if updated_at == draft.base_updated_at => {
+                            DraftServerState::Unchanged
+                        }
+                        Some(_) => DraftServerState::Changed,
+                        None => DraftServerState::Unknown,
+                    };
+                }
+                if items.is_empty() {
+                    app.drafts_recovery = None;
+                }
+            },
+        );
+    }
+
+    /// Drop a leftover draft from disk and from the recovery list.
+    pub(crate) fn drafts_discard(&mut self, index: usize) {
+        let Some(items) = self.drafts_recovery.as_mut() else {
+            return;
+        };
+        if index < items.len() {
+            let item = items.remove(index);
+            if let Err(e) = storage::delete_draft(&item.draft.profile, &item.draft.content.key()) {
+                warn!(error = ?e, "delete draft failed");
+            }
+        }
+        if items.is_empty() {
+            self.drafts_recovery = None;
+        }
+    }
+
+    /// Open the draft's item from the server and put the draft on top as unsaved edits.
+    pub(crate) fn drafts_restore(&mut self, index: usize) {
+        let Some(item) = self.drafts_recovery.as_ref().and_then(|items| items.get(index)) else {
+            return;
+        };
+        let busy_editor = match item.draft.content {
+            DraftContent::Template { .. } => self.templates_is_dirty(),
+            DraftContent::AiAlert { .. } => self.ai_alerts_is_dirty(),
+        };
+        if busy_editor {
+            self.status = "Save or discard the open edits before restoring this draft.".to_string();
+            return;
+        }
+        // Drafts are offered before sign-in; keep them listed until they can be opened.
+        let needs_server = !matches!(item.draft.content, DraftContent::AiAlert { alert_id: None, .. });
+        if needs_server && !self.has_access_token() {
+            self.status = "Sign in to restore this draft.".to_string();
+            return;
+        }
+        let Some(items) = self.drafts_recovery.as_mut() else {
+            return;
+        };
+        let draft = items.remove(index).draft;
+        if items.is_empty() {
+            self.drafts_recovery = None;
+        }
+
+        match draft.content {
+            DraftContent::Template {
+                template_id,
+                template_name,
+                version,
+                index_html,
+                style_css,
+                overlay_js,
+            } => {
+                let api = match self.api() {
+                    Ok(v) => v,
+                    Err(e) => {
+                        self.status = e.to_string();
+                        return;
+                    }
+                };
+                let (id, ver) = (template_id.clone(), version.clone());
+                self.spawn_job(
+                    JobKind::Templates,
+                    async move {
+                        let detail = api.template(&id).await?;
+                        let server = api.template_version(&id, &ver).await?;
+                        Ok((detail, server))
+                    },
+                    move |app, result| match result {
+                        Ok((detail, server)) => {
+                            app.account.templates_selected_template_id = Some(detail.id.clone());
+                            app.account.templates_selected_template_name = Some(detail.name.clone());
+                            app.account.templates_versions = detail.versions.clone();
+                            sort_versions(&mut app.account.templates_versions);
+                            app.account.templates_selected_version = Some(server.version.clone());
+                            app.account.templates_loaded = Some(TemplateSources::from(&server));
+                            app.account.templates_loaded_updated_at = server.updated_at.clone();
+                            app.set_editor_sources(TemplateSources {
+                                index_html,
+                                style_css,
+                                overlay_js,
+                            });
+                            app.account.templates_editor_tab = TemplatesEditorTab::Html;
+                            app.account.active_view = View::Templates;
+                            app.account.templates_status = format!(
+                                "Restored draft of {template_name} version {version} (not saved yet)."
+                            );
+                        }
+                        Err(e) => {
+                            warn!(error = ?e, "restore template draft failed");
+                            app.account.templates_status = format!("Restore draft failed: {e:#}");
+                        }
+                    },
+                );
+            }
+            DraftContent::AiAlert { alert_id, name, prompt } => {
+                self.account.active_view = View::AiAlerts;
+                match alert_id {
+                    None => {
+                        self.ai_alerts_clear_editor();
+                        self.account.ai_alerts_name = name;
+                        self.account.ai_alerts_prompt = prompt;
+                        self.account.ai_status = "Restored draft of a new alert (not created yet).".to_string();
+                    }
+                    Some(alert_id) => {
+                        let api = match self.api() {
+                            Ok(v) => v,
+                            Err(e) => {
+                                self.account.ai_status = e.to_string();
+                                return;
+                            }
+                        };
+                        self.spawn_job(
+                            JobKind::AiAlerts,
+                            async move { api.ai_alert(&alert_id).await },
+                            move |app, result| match result {
+                                Ok(detail) => {
+                                    app.ai_alerts_apply_detail(detail);
+                                    app.account.ai_alerts_name = name;
+                                    app.account.ai_alerts_prompt = prompt;
+                                    app.account.ai_test_result.clear();
+                                    app.ai_alert_public_refresh();
+                                    app.account.ai_status = "Restored alert draft (not saved yet).".to_string();
+                                }
+                                Err(e) => {
+                                    warn!(error = ?e, "restore alert draft failed");
+                                    app.account.ai_status = format!("Restore draft failed: {e:#}");
+                                }
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use tracing::warn;
+
+use crate::models::TemplateVersionSummary;
+use crate::templates::sort_versions;
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+/// Versions newest first, by their last save or publish.
+pub(crate) fn history_timeline(versions: &[TemplateVersionSummary]) -> Vec<TemplateVersionSummary> {
+    let last_activity = |v: &TemplateVersionSummary| {
+        v.published_at
+            .as_deref()
+            .filter(|p| *p > v.updated_at.as_str())
+            .unwrap_or(&v.updated_at)
+            .to_string()
+    };
+    let mut timeline = versions.to_vec();
+    timeline.sort_by_key(|v| std::cmp::Reverse(last_activity(v)));
+    timeline
+}
+
+/// `2024-05-01T18:30:12Z` → `2024-05-01 18:30`; anything else is shown as is.
+pub(crate) fn format_timestamp(value: &str) -> String {
+    match value.get(..16) {
+        Some(prefix) if value.as_bytes().get(10) == Some(&b'T') => prefix.replacen('T', " ", 1),
+        _ => value.to_string(),
+    }
+}
+
+impl TwitchDeskApp {
+    pub(crate) fn templates_open_history(&mut self) {
+        if self.account.templates_selected_template_id.is_none() {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        }
+        self.account.templates_history_open = true;
+        self.templates_refresh_versions();
+    }
+
+    /// Reload the version list of the open template without touching the editor.
+    pub(crate) fn templates_refresh_versions(&mut self) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template(&template_id).await },
+            |app, result| match result {
+                Ok(detail) => {
+                    if app.account.templates_selected_template_id.as_deref() == Some(detail.id.as_str()) {
+                        app.account.templates_versions = detail.versions;
+                        sort_versions(&mut app.account.templates_versions);
+                    }
+                }
+                Err(e) => {
+                    warn!(error = ?e, "template versions failed");
+                    app.account.templates_status = format!("Load versions failed: {e:#}");
+                }
+            },
+        );
+    }
+
+    /// Publish an older version again, right away. Unlike Publish this leaves the editor
+    /// (and any unsaved edits in it) alone.
+    pub(crate) fn templates_rollback(&mut self, version: &str) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+        let previous = self
+            .account
+            .templates_versions
+            .iter()
+            .find(|v| v.is_published)
+            .map(|v| v.version.clone());
+
+        let version = version.to_string();
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template_version_publish(&template_id, &version).await },
+            move |app, result| match result {
+                Ok(v) => {
+                    app.account.templates_status = match previous {
+                        Some(p) => format!("Rolled back: version {} is published again (was {p}).", v.version),
+                        None => format!("Published version {}.", v.version),
+                    };
+                    app.templates_refresh_versions();
+                    app.templates_refresh_list();
+                }
+                Err(e) => {
+                    warn!(error = ?e, "rollback failed");
+                    app.account.templates_status = format!("Roll back failed: {e:#}");
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use eframe::egui;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::mpsc;
+
+use crate::api::{self, ApiError};
+
+use super::state::TwitchDeskApp;
+
+/// Which part of the UI a background job belongs to (drives spinners/disabled buttons).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum JobKind {
+    Auth,
+    Account,
+    TwitchLookup,
+    Channels,
+    AiToken,
+    AiAlerts,
+    Templates,
+    /// Background sync of a template version mirrored to a folder.
+    Workspace,
+    /// Checking leftover drafts against the server.
+    Drafts,
+}
+
+impl JobKind {
+    /// Whether results only touch the account's own state (see `AccountState`), and so
+    /// can be applied to a profile that is not active anymore.
+    fn is_account_scoped(self) -> bool {
+        matches!(
+            self,
+            JobKind::TwitchLookup | JobKind::AiToken | JobKind::AiAlerts | JobKind::Templates | JobKind::Workspace
+        )
+    }
+}
+
+type ApplyFn = Box<dyn FnOnce(&mut TwitchDeskApp) + Send>;
+
+struct JobDone {
+    kind: JobKind,
+    apply: ApplyFn,
+}
+
+/// Finished jobs are sent back to the UI thread and applied in `eframe::App::update`.
+pub(crate) struct Jobs {
+    tx: mpsc::Sender<JobDone>,
+    rx: mpsc::Receiver<JobDone>,
+    pending: HashMap<JobKind, usize>,
+    /// Validation messages from the last failed job of each kind, keyed by request field.
+    field_errors: HashMap<JobKind, BTreeMap<String, String>>,
+    ctx: egui::Context,
+}
+
+impl Jobs {
+    pub(crate) fn new(ctx: egui::Context) -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            tx,
+            rx,
+            pending: HashMap::new(),
+            field_errors: HashMap::new(),
+            ctx,
+        }
+    }
+
+    pub(crate) fn is_busy(&self, kind: JobKind) -> bool {
+        self.pending.get(&kind).copied().unwrap_or(0) > 0
+    }
+}
+
+impl TwitchDeskApp {
+    /// Run `fut` on the tokio runtime and hand its result to `apply` on the UI thread.
+    pub(crate) fn spawn_job<T, F, A>(&mut self, kind: JobKind, fut: F, apply: A)
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, ApiError>> + Send + 'static,
+        A: FnOnce(&mut TwitchDeskApp, Result<T, ApiError>) + Send + 'static,
+    {
+        let profile = self.local.active_profile.clone();
+        self.spawn_job_for_profile(profile, kind, fut, apply);
+    }
+
+    /// [`spawn_job`](Self::spawn_job) for a request made with `profile`'s client, which
+    /// may not be the active one. If `profile` is no longer active, an account-scoped result
+    /// goes to its cached state; anything else (a 401 included) is dropped.
+    pub(crate) fn spawn_job_for_profile<T, F, A>(&mut self, profile: String, kind: JobKind, fut: F, apply: A)
+    where
+        T: Send + 'static,
+        F: Future<Output = Result<T, ApiError>> + Send + 'static,
+        A: FnOnce(&mut TwitchDeskApp, Result<T, ApiError>) + Send + 'static,
+    {
+        *self.jobs.pending.entry(kind).or_default() += 1;
+        self.jobs.field_errors.remove(&kind);
+
+        let tx = self.jobs.tx.clone();
+        let ctx = self.jobs.ctx.clone();
+        self.rt.spawn(async move {
+            let (result, retries) = api::count_retries(fut).await;
+            let apply: ApplyFn = Box::new(move |app| {
+                // The user switched profiles while this was running; the result belongs to
+                // another account. Account-scoped results still update that account's cached
+                // state (a save must advance its `updated_at`), the rest is dropped.
+                if app.local.active_profile != profile {
+                    let unauthorized = matches!(&result, Err(ApiError::Unauthorized(_)));
+                    let applied = kind.is_account_scoped()
+                        && !unauthorized
+                        && app.with_cached_profile(&profile, |app| {
+                            apply(app, result);
+                            app.note_retries(kind, retries);
+                        });
+                    if !applied {
+                        tracing::debug!(?kind, %profile, "dropping job result for inactive profile");
+                    }
+                    return;
+                }
+                if let Err(e) = &result {
+                    // A 401 outside of login/register means our session is gone.
+                    if matches!(e, ApiError::Unauthorized(_)) && kind != JobKind::Auth {
+                        app.session_expired();
+                        return;
+                    }
+                    if let Some(fields) = e.field_errors() {
+                        app.jobs.field_errors.insert(kind, fields.clone());
+                    }
+                }
+                apply(app, result);
+                app.note_retries(kind, retries);
+            });
+            let _ = tx.send(JobDone { kind, apply });
+            ctx.request_repaint();
+        });
+    }
+
+    /// Apply every job that finished since the last frame.
+    pub(crate) fn poll_jobs(&mut self) {
+        while let Ok(done) = self.jobs.rx.try_recv() {
+            if let Some(n) = self.jobs.pending.get_mut(&done.kind) {
+                *n = n.saturating_sub(1);
+            }
+            (done.apply)(self);
+        }
+    }
+
+    pub(crate) fn is_busy(&self, kind: JobKind) -> bool {
+        self.jobs.is_busy(kind)
+    }
+
+    fn note_retries(&mut self, kind: JobKind, retries: u32) {
+        if retries > 0 {
+            let status = self.status_for(kind);
+            let plural = if retries == 1 { "retry" } else { "retries" };
+            status.push_str(&format!(" (after {retries} {plural})"));
+        }
+    }
+
+    /// The status line a job of `kind` reports into.
+    fn status_for(&mut self, kind: JobKind) -> &mut String {
+        match kind {
+            JobKind::Auth | JobKind::Account | JobKind::Channels | JobKind::Drafts => &mut self.status,
+            JobKind::TwitchLookup => &mut self.account.test_result,
+            JobKind::AiToken | JobKind::AiAlerts => &mut self.account.ai_status,
+            JobKind::Templates | JobKind::Workspace => &mut self.account.templates_status,
+        }
+    }
+
+    /// Validation message for `field` from the last failed job of `kind`, shown next to the input.
+    pub(crate) fn ui_field_error(&self, ui: &mut egui::Ui, kind: JobKind, field: &str) {
+        let msg = self
+            .jobs
+            .field_errors
+            .get(&kind)
+            .and_then(|fields| fields.get(field));
+        if let Some(msg) = msg {
+            ui.label(
+                egui::RichText::new(msg)
+                    .small()
+                    .color(ui.visuals().error_fg_color),
+            );
+        }
+    }
+
+    /// Small inline spinner shown while any job of `kind` is running.
+    pub(crate) fn ui_job_spinner(&self, ui: &mut egui::Ui, kind: JobKind) {
+        if self.is_busy(kind) {
+            ui.spinner();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::templates::{lint_has_errors, lint_template, LintDiagnostic, LintSeverity, TemplateSources};
+
+use super::{state::TwitchDeskApp, unsaved::GuardedAction};
+
+/// Lint result for the editor buffers of one version.
+pub(crate) struct TemplateLint {
+    template_id: String,
+    version: String,
+    /// Buffers the diagnostics were computed from.
+    sources: TemplateSources,
+    pub(crate) diagnostics: Vec<LintDiagnostic>,
+    /// The user chose to publish these exact sources despite errors.
+    publish_anyway: bool,
+}
+
+impl TemplateLint {
+    pub(crate) fn errors(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == LintSeverity::Error)
+            .count()
+    }
+
+    pub(crate) fn warnings(&self) -> usize {
+        self.diagnostics.len() - self.errors()
+    }
+}
+
+impl TwitchDeskApp {
+    /// Lint result for the version in the editor, if it was checked.
+    pub(crate) fn templates_lint_current(&self) -> Option<&TemplateLint> {
+        self.account.templates_lint.as_ref().filter(|l| {
+            self.account.templates_selected_template_id.as_deref() == Some(&l.template_id)
+                && self.account.templates_selected_version.as_deref() == Some(&l.version)
+        })
+    }
+
+    /// The editor changed since the last check.
+    pub(crate) fn templates_lint_outdated(&self) -> bool {
+        self.templates_lint_current()
+            .is_some_and(|l| l.sources != self.editor_sources())
+    }
+
+    /// Lint the editor buffers; `true` if there are errors.
+    pub(crate) fn templates_run_lint(&mut self) -> bool {
+        let (Some(template_id), Some(version)) = (
+            self.account.templates_selected_template_id.clone(),
+            self.account.templates_selected_version.clone(),
+        ) else {
+            self.account.templates_lint = None;
+            return false;
+        };
+        let sources = self.editor_sources();
+        // Re-checking unchanged contents (e.g. the save before a publish) keeps the override.
+        let publish_anyway = self
+            .templates_lint_current()
+            .is_some_and(|l| l.publish_anyway && l.sources == sources);
+        let diagnostics = lint_template(&sources);
+        let errors = lint_has_errors(&diagnostics);
+        self.account.templates_lint = Some(TemplateLint {
+            template_id,
+            version,
+            sources,
+            diagnostics,
+            publish_anyway,
+        });
+        errors
+    }
+
+    /// Lint before publishing. Errors block the publish unless the user overrode them
+    /// for the current contents.
+    pub(crate) fn templates_lint_allows_publish(&mut self) -> bool {
+        if !self.templates_run_lint() || self.account.templates_lint.as_ref().is_some_and(|l| l.publish_anyway) {
+            return true;
+        }
+        let errors = self.account.templates_lint.as_ref().map(|l| l.errors()).unwrap_or_default();
+        self.account.templates_status = format!(
+            "Publish blocked: {errors} lint error(s). Fix them or use Publish anyway."
+        );
+        false
+    }
+
+    /// Publish despite lint errors in the current contents.
+    pub(crate) fn templates_publish_anyway(&mut self) {
+        self.templates_run_lint();
+        if let Some(lint) = self.account.templates_lint.as_mut() {
+            lint.publish_anyway = true;
+        }
+        self.templates_guard(GuardedAction::Publish);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::preview_server::{PreviewError, PreviewServer};
+
+use super::state::TwitchDeskApp;
+
+/// Script errors kept from the preview page (repeats are dropped).
+const MAX_PREVIEW_ERRORS: usize = 20;
+
+impl TwitchDeskApp {
+    /// URL of the local preview server (started on first use).
+    pub(crate) fn local_preview_url(&mut self, mock: bool) -> Option<String> {
+        if self.preview_server.is_none() {
+            match PreviewServer::start(&self.rt) {
+                Ok(server) => {
+                    self.preview_server = Some(server);
+                    self.templates_sync_preview_assets();
+                }
+                Err(e) => {
+                    self.account.templates_status = format!("Local preview failed: {e:#}");
+                    return None;
+                }
+            }
+        }
+        self.tick_local_preview();
+        let query = self.templates_preview_query(mock);
+        self.preview_server.as_ref().map(|s| s.url(&query))
+    }
+
+    /// Open the preview window on the unsaved editor buffers; it reloads as you type.
+    pub(crate) fn open_local_preview(&mut self, mock: bool) {
+        let Some(url) = self.local_preview_url(mock) else {
+            return;
+        };
+        match self.open_template_preview(&url) {
+            Ok(()) => self.account.templates_status = format!("Opened local preview at {url}."),
+            Err(e) => self.account.templates_status = format!("Preview failed: {e:#}"),
+        }
+    }
+
+    /// Keep the local preview server in sync with the editor buffers and collect the
+    /// script errors it reported. Errors are cleared whenever the page fully reloads.
+    pub(crate) fn tick_local_preview(&mut self) {
+        let Some(server) = &mut self.preview_server else {
+            return;
+        };
+        if server.sync(
+            &self.account.templates_index_html,
+            &self.account.templates_style_css,
+            &self.account.templates_overlay_js,
+        ) {
+            self.templates_preview_errors.clear();
+        }
+        for error in server.take_errors() {
+            if self.templates_preview_errors.len() < MAX_PREVIEW_ERRORS
+                && !self.templates_preview_errors.contains(&error)
+            {
+                self.templates_preview_errors.push(error);
+            }
+        }
+    }
+
+    /// Open the line a preview error points at, if it came from a template file.
+    pub(crate) fn templates_jump_to_preview_error(&mut self, error: &PreviewError) {
+        if let Some(file) = error.file() {
+            let column = (error.column > 0).then_some(error.column);
+            self.templates_jump_to(file, error.line.max(1), column);
+        }
+    }
+}
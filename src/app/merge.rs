@@ -0,0 +1,161 @@
+use tracing::warn;
+
+use crate::templates::{merge3, merge_result, unresolved_count, MergeChunk, TemplateFile, TemplateSources};
+
+use super::{jobs::JobKind, state::TwitchDeskApp, unsaved::GuardedAction};
+
+/// Three-way merge after a save was rejected because the server copy changed.
+pub(crate) struct TemplateMerge {
+    pub(crate) template_id: String,
+    pub(crate) version: String,
+    /// Server's reason for rejecting the save.
+    pub(crate) message: String,
+    /// Current server copy ("theirs").
+    remote: TemplateSources,
+    remote_updated_at: String,
+    pub(crate) files: Vec<(TemplateFile, Vec<MergeChunk>)>,
+    pub(crate) file: TemplateFile,
+    /// Action the rejected save was made for (see the unsaved-changes prompt); runs once
+    /// the merge is resolved and, for merged edits, saved.
+    after: Option<GuardedAction>,
+}
+
+impl TemplateMerge {
+    pub(crate) fn chunks_mut(&mut self, file: TemplateFile) -> &mut Vec<MergeChunk> {
+        let idx = self.files.iter().position(|(f, _)| *f == file).unwrap_or(0);
+        &mut self.files[idx].1
+    }
+
+    pub(crate) fn unresolved(&self) -> usize {
+        self.files.iter().map(|(_, c)| unresolved_count(c)).sum()
+    }
+
+    /// Merged sources, once every conflict has a choice.
+    fn result(&self) -> Option<TemplateSources> {
+        let mut out = TemplateSources::default();
+        for (file, chunks) in &self.files {
+            *out.get_mut(*file) = merge_result(chunks)?;
+        }
+        Some(out)
+    }
+}
+
+impl TwitchDeskApp {
+    /// Fetch the server copy that rejected a save and open the merge window against it.
+    pub(crate) fn templates_open_merge(
+        &mut self,
+        template_id: String,
+        version: String,
+        message: String,
+        after: Option<GuardedAction>,
+    ) {
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+        let (id, ver) = (template_id.clone(), version.clone());
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template_version(&id, &ver).await },
+            move |app, result| match result {
+                Ok(v) => {
+                    let same_version = app.account.templates_selected_template_id.as_deref() == Some(&template_id)
+                        && app.account.templates_selected_version.as_deref() == Some(&version);
+                    if !same_version {
+                        return;
+                    }
+                    let remote = TemplateSources::from(&v);
+                    let local = app.editor_sources();
+                    // Without a known base, treat the server copy as base: local edits win.
+                    let base = app.account.templates_loaded.clone().unwrap_or_else(|| remote.clone());
+                    let files = TemplateFile::ALL
+                        .into_iter()
+                        .map(|f| (f, merge3(base.get(f), local.get(f), remote.get(f))))
+                        .collect();
+                    app.account.templates_merge = Some(TemplateMerge {
+                        template_id,
+                        version,
+                        message,
+                        remote,
+                        remote_updated_at: v.updated_at,
+                        files,
+                        file: app.account.templates_editor_tab.file(),
+                        after,
+                    });
+                }
+                Err(e) => {
+                    warn!(error = ?e, "merge fetch failed");
+                    app.account.templates_status = format!("Load server copy failed: {e:#}");
+                }
+            },
+        );
+    }
+
+    /// Rebase the editor on the server copy from the merge, so the next save passes the
+    /// precondition. `false` if the editor moved on to another version meanwhile.
+    fn templates_merge_rebase(&mut self, merge: &TemplateMerge) -> bool {
+        let same_version = self.account.templates_selected_template_id.as_deref() == Some(&merge.template_id)
+            && self.account.templates_selected_version.as_deref() == Some(&merge.version);
+        if !same_version {
+            self.account.templates_status = "The editor shows another version; merge cancelled.".to_string();
+            return false;
+        }
+        self.account.templates_loaded = Some(merge.remote.clone());
+        self.account.templates_loaded_updated_at = merge.remote_updated_at.clone();
+        true
+    }
+
+    /// Put the merged result into the editor (unsaved).
+    pub(crate) fn templates_merge_apply(&mut self) {
+        let Some(merge) = self.account.templates_merge.take() else {
+            return;
+        };
+        let Some(merged) = merge.result() else {
+            self.account.templates_merge = Some(merge);
+            return;
+        };
+        if !self.templates_merge_rebase(&merge) {
+            return;
+        }
+        self.set_editor_sources(merged);
+        match merge.after {
+            // The user already asked to save before moving on.
+            Some(action) => self.templates_save_current_version_then(Some(action)),
+            None => {
+                self.account.templates_status = format!(
+                    "Merged server changes into version {}. Review and Save.",
+                    merge.version
+                );
+            }
+        }
+    }
+
+    /// Save the local buffers over the server copy.
+    pub(crate) fn templates_merge_keep_mine(&mut self) {
+        let Some(merge) = self.account.templates_merge.take() else {
+            return;
+        };
+        if self.templates_merge_rebase(&merge) {
+            self.templates_save_current_version_then(merge.after);
+        }
+    }
+
+    /// Drop local edits and load the server copy.
+    pub(crate) fn templates_merge_take_server(&mut self) {
+        let Some(merge) = self.account.templates_merge.take() else {
+            return;
+        };
+        if !self.templates_merge_rebase(&merge) {
+            return;
+        }
+        self.set_editor_sources(merge.remote.clone());
+        self.account.templates_status = format!("Loaded the server copy of version {}.", merge.version);
+        // Nothing left to save.
+        if let Some(action) = merge.after {
+            self.run_guarded_action(action);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use tracing::warn;
+
+use crate::{
+    mock_events::{replay_sequence, MockEventHub},
+    models::{MockEvent, MockEventPreset, MockSequenceStep},
+};
+
+use super::state::TwitchDeskApp;
+
+/// Delay suggested for new sequence steps (long enough for most alert animations).
+const DEFAULT_STEP_DELAY_MS: u64 = 3000;
+
+/// Mock event injector panel.
+pub(crate) struct MockEventsState {
+    /// Started with the first preview window; windows connect to it on launch.
+    pub(crate) hub: Option<MockEventHub>,
+    /// Event being edited in the panel.
+    pub(crate) draft: MockEvent,
+    pub(crate) preset_name: String,
+    pub(crate) step_delay_ms: u64,
+    pub(crate) replay: Option<MockReplay>,
+}
+
+impl Default for MockEventsState {
+    fn default() -> Self {
+        let [first, ..] = MockEvent::samples();
+        Self {
+            hub: None,
+            draft: first,
+            preset_name: String::new(),
+            step_delay_ms: DEFAULT_STEP_DELAY_MS,
+            replay: None,
+        }
+    }
+}
+
+/// A running "replay sequence"; aborted when dropped.
+pub(crate) struct MockReplay {
+    task: tokio::task::JoinHandle<()>,
+    progress: Arc<AtomicUsize>,
+    pub(crate) total: usize,
+}
+
+impl MockReplay {
+    pub(crate) fn sent(&self) -> usize {
+        self.progress.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for MockReplay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl TwitchDeskApp {
+    fn mock_event_hub(&mut self) -> Option<&MockEventHub> {
+        if self.mock_events.hub.is_none() {
+            match MockEventHub::start(&self.rt) {
+                Ok(hub) => self.mock_events.hub = Some(hub),
+                Err(e) => {
+                    warn!(error = ?e, "mock event channel failed");
+                    self.account.templates_status = format!("Mock events unavailable: {e:#}");
+                }
+            }
+        }
+        self.mock_events.hub.as_ref()
+    }
+
+    /// Open a preview window that accepts mock events from the injector.
+    pub(crate) fn open_template_preview(&mut self, url: &str) -> anyhow::Result<()> {
+        let args = self
+            .mock_event_hub()
+            .map(|hub| hub.preview_args())
+            .unwrap_or_default();
+        crate::preview::open_preview_with_args(url, &args)
+    }
+
+    /// Preview windows that will receive mock events.
+    pub(crate) fn mock_events_connected(&self) -> usize {
+        self.mock_events.hub.as_ref().map(|h| h.connected()).unwrap_or(0)
+    }
+
+    pub(crate) fn mock_events_send(&mut self, event: &MockEvent) {
+        let Some(hub) = &self.mock_events.hub else {
+            self.account.templates_status = "No preview window to send to. Open one with Preview first.".to_string();
+            return;
+        };
+        self.account.templates_status = match hub.sender().send(event) {
+            Ok(0) => "No preview window to send to. Open one with Preview first.".to_string(),
+            Ok(n) => format!("Sent \"{}\" to {n} preview window(s).", event.summary()),
+            Err(e) => format!("Send mock event failed: {e:#}"),
+        };
+    }
+
+    /// Save the draft event under the typed name, replacing a preset with the same name.
+    pub(crate) fn mock_events_save_preset(&mut self) {
+        let name = self.mock_events.preset_name.trim().to_string();
+        if name.is_empty() {
+            self.account.templates_status = "Name the preset first.".to_string();
+            return;
+        }
+        let preset = MockEventPreset {
+            name: name.clone(),
+            event: self.mock_events.draft.clone(),
+        };
+        let presets = &mut self.local.mock_event_presets;
+        match presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+        self.mock_events.preset_name.clear();
+        self.schedule_local_save();
+        self.account.templates_status = format!("Saved mock event preset \"{name}\".");
+    }
+
+    pub(crate) fn mock_events_delete_preset(&mut self, index: usize) {
+        if index < self.local.mock_event_presets.len() {
+            self.local.mock_event_presets.remove(index);
+            self.schedule_local_save();
+        }
+    }
+
+    pub(crate) fn mock_events_add_step(&mut self, event: MockEvent) {
+        self.local.mock_event_sequence.push(MockSequenceStep {
+            delay_ms: self.mock_events.step_delay_ms,
+            event,
+        });
+        self.schedule_local_save();
+    }
+
+    /// Send the sequence to the preview windows in order, honouring each step's delay.
+    pub(crate) fn mock_events_replay(&mut self) {
+        let steps = self.local.mock_event_sequence.clone();
+        if steps.is_empty() {
+            return;
+        }
+        if self.mock_events_connected() == 0 {
+            self.account.templates_status = "No preview window to send to. Open one with Preview first.".to_string();
+            return;
+        }
+        let Some(sender) = self.mock_events.hub.as_ref().map(|h| h.sender()) else {
+            return;
+        };
+        let progress = Arc::new(AtomicUsize::new(0));
+        let total = steps.len();
+        let task = self.rt.spawn(replay_sequence(sender, steps, progress.clone()));
+        self.mock_events.replay = Some(MockReplay {
+            task,
+            progress,
+            total,
+        });
+        self.account.templates_status = format!("Replaying {total} mock event(s).");
+    }
+
+    pub(crate) fn mock_events_stop(&mut self) {
+        if let Some(replay) = self.mock_events.replay.take() {
+            self.account.templates_status = format!("Stopped replay after {} of {} event(s).", replay.sent(), replay.total);
+        }
+    }
+}
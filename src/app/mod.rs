@@ -1,12 +1,49 @@
 use anyhow::Result;
 
 mod actions;
+mod assets;
+mod bundle;
+mod code_editor;
+mod drafts;
+mod history;
+mod jobs;
+mod lint;
+mod local_preview;
+mod merge;
+mod mock_events;
+mod params;
+mod profiles;
+mod starters;
 mod state;
+mod template_search;
+mod thumbnails;
+mod trash;
 mod types;
+mod unsaved;
+mod version_diff;
+mod workspace;
+mod ui_assets;
+mod ui_bundle;
+mod ui_code_editor;
 mod ui_dashboard;
+mod ui_drafts;
 mod ui_header;
+mod ui_history;
+mod ui_lint;
+mod ui_local_state;
 mod ui_login;
+mod ui_merge;
+mod ui_mock_events;
+mod ui_params;
+mod ui_profiles;
+mod ui_secrets;
+mod ui_starters;
+mod ui_template_list;
 mod ui_transition;
+mod ui_trash;
+mod ui_unsaved;
+mod ui_version_diff;
+mod ui_workspace;
 
 use state::TwitchDeskApp;
 
@@ -17,7 +54,7 @@ pub fn run_app() -> Result<()> {
         native_options,
         Box::new(|cc| {
             apply_theme(&cc.egui_ctx);
-            Ok(Box::new(TwitchDeskApp::new()))
+            Ok(Box::new(TwitchDeskApp::new(&cc.egui_ctx)))
         }),
     )
     .map_err(|e| anyhow::anyhow!(e.to_string()))
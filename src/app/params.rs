@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use crate::templates::{preview_query, read_param_schema, with_example_param_manifest, ParamSchema};
+
+use super::state::TwitchDeskApp;
+
+impl TwitchDeskApp {
+    /// Parameter manifest declared in the editor's `index.html`.
+    pub(crate) fn templates_param_schema(&self) -> anyhow::Result<Option<ParamSchema>> {
+        read_param_schema(&self.account.templates_index_html)
+    }
+
+    /// Stored parameter values of the selected template.
+    pub(crate) fn templates_param_values(&self) -> BTreeMap<String, String> {
+        self.account.templates_selected_template_id
+            .as_ref()
+            .and_then(|id| self.local.profile().template_params.get(id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn templates_set_param_values(&mut self, values: BTreeMap<String, String>) {
+        let Some(id) = self.account.templates_selected_template_id.clone() else {
+            return;
+        };
+        let params = &mut self.local.profile_mut().template_params;
+        if values.is_empty() {
+            params.remove(&id);
+        } else {
+            params.insert(id, values);
+        }
+        self.schedule_local_save();
+    }
+
+    /// Query string for preview URLs of the selected template: its parameter values
+    /// (or defaults) and `mock=true` if requested. An invalid manifest adds no parameters.
+    pub(crate) fn templates_preview_query(&self, mock: bool) -> String {
+        let params = match self.templates_param_schema() {
+            Ok(Some(schema)) => schema.resolve(&self.templates_param_values()),
+            _ => vec![],
+        };
+        preview_query(&params, mock)
+    }
+
+    pub(crate) fn templates_add_param_manifest(&mut self) {
+        self.account.templates_index_html = with_example_param_manifest(&self.account.templates_index_html);
+        self.account.templates_status = "Added an example parameter manifest to index.html.".to_string();
+    }
+}
@@ -0,0 +1,256 @@
+use std::mem;
+
+use crate::models::{
+    AiAlertListItem, ApiProfile, ChannelStatus, TemplateAsset, TemplateListItem,
+    TemplateVersionSummary, UserConfig,
+};
+use crate::templates::TemplateSources;
+
+use super::{
+    lint::TemplateLint,
+    merge::TemplateMerge,
+    state::{TemplatesEditorTab, TwitchDeskApp},
+    template_search::TemplateSearch,
+    trash::TemplateConfirm,
+    types::{Screen, View},
+    version_diff::VersionDiff,
+    workspace::WorkspaceLink,
+};
+
+/// Account-scoped UI state: what one profile's account has open. The active profile's is
+/// `TwitchDeskApp::account`; switching profiles swaps it with the cached one.
+pub(crate) struct AccountState {
+    pub(crate) active_view: View,
+
+    pub(crate) test_login: String,
+    pub(crate) test_result: String,
+    pub(crate) channel_to_add: String,
+    pub(crate) channel_statuses: Vec<ChannelStatus>,
+
+    // Templates
+    pub(crate) templates_list: Vec<TemplateListItem>,
+    pub(crate) templates_new_name: String,
+    pub(crate) templates_selected_template_id: Option<String>,
+    pub(crate) templates_selected_template_name: Option<String>,
+    pub(crate) templates_selected_version: Option<String>,
+    pub(crate) templates_versions: Vec<TemplateVersionSummary>,
+    pub(crate) templates_editor_tab: TemplatesEditorTab,
+    pub(crate) templates_index_html: String,
+    pub(crate) templates_style_css: String,
+    pub(crate) templates_overlay_js: String,
+    /// Server copy the editor buffers were loaded from (or last saved as); `None` until a version is loaded.
+    pub(crate) templates_loaded: Option<TemplateSources>,
+    pub(crate) templates_loaded_updated_at: String,
+    pub(crate) templates_new_version: String,
+    pub(crate) templates_duplicate_template_name: String,
+    pub(crate) templates_rename_name: String,
+    /// Assets of the selected version.
+    pub(crate) templates_assets: Vec<TemplateAsset>,
+    /// File to upload as an asset.
+    pub(crate) templates_asset_path: String,
+    /// Delete / unpublish / publish waiting for confirmation.
+    pub(crate) templates_confirm: Option<TemplateConfirm>,
+    pub(crate) templates_status: String,
+    /// Open merge window after a save was rejected by the server.
+    pub(crate) templates_merge: Option<TemplateMerge>,
+    /// Open "Compare versions" window.
+    pub(crate) templates_diff: Option<VersionDiff>,
+    /// "Version history" window of the open template.
+    pub(crate) templates_history_open: bool,
+    /// Search, tag filter and sort of the template list.
+    pub(crate) templates_search: TemplateSearch,
+    /// Last lint run on the editor buffers.
+    pub(crate) templates_lint: Option<TemplateLint>,
+    /// Version mirrored to the workspace folder, if any.
+    pub(crate) workspace: Option<WorkspaceLink>,
+
+    // AI Alerts
+    pub(crate) ai_status: String,
+    pub(crate) ai_token_connected: Option<bool>,
+    pub(crate) ai_token_input: String,
+    pub(crate) ai_alerts_list: Vec<AiAlertListItem>,
+    pub(crate) ai_alerts_selected_id: Option<String>,
+    pub(crate) ai_alerts_name: String,
+    pub(crate) ai_alerts_prompt: String,
+    pub(crate) ai_alerts_is_enabled: bool,
+    pub(crate) ai_alerts_cooldown_ms: i32,
+    /// (name, prompt) as loaded or last saved; the editor is dirty when it differs.
+    pub(crate) ai_alerts_loaded: Option<(String, String)>,
+    pub(crate) ai_alerts_loaded_updated_at: String,
+    pub(crate) ai_public_enabled: bool,
+    pub(crate) ai_public_url: String,
+    pub(crate) ai_test_event_id: String,
+    pub(crate) ai_test_username: String,
+    pub(crate) ai_test_message: String,
+    pub(crate) ai_test_result: String,
+}
+
+impl Default for AccountState {
+    fn default() -> Self {
+        Self {
+            active_view: View::Home,
+
+            test_login: "someuser".to_string(),
+            test_result: "".to_string(),
+            channel_to_add: "".to_string(),
+            channel_statuses: vec![],
+
+            templates_list: vec![],
+            templates_new_name: "".to_string(),
+            templates_selected_template_id: None,
+            templates_selected_template_name: None,
+            templates_selected_version: None,
+            templates_versions: vec![],
+            templates_editor_tab: TemplatesEditorTab::Html,
+            templates_index_html: "".to_string(),
+            templates_style_css: "".to_string(),
+            templates_overlay_js: "".to_string(),
+            templates_loaded: None,
+            templates_loaded_updated_at: "".to_string(),
+            templates_new_version: "".to_string(),
+            templates_duplicate_template_name: "".to_string(),
+            templates_rename_name: "".to_string(),
+            templates_assets: vec![],
+            templates_asset_path: "".to_string(),
+            templates_confirm: None,
+            templates_status: "".to_string(),
+            templates_merge: None,
+            templates_diff: None,
+            templates_history_open: false,
+            templates_search: TemplateSearch::default(),
+            templates_lint: None,
+            workspace: None,
+
+            ai_status: "".to_string(),
+            ai_token_connected: None,
+            ai_token_input: "".to_string(),
+            ai_alerts_list: vec![],
+            ai_alerts_selected_id: None,
+            ai_alerts_name: "".to_string(),
+            ai_alerts_prompt: "".to_string(),
+            ai_alerts_is_enabled: true,
+            ai_alerts_cooldown_ms: 0,
+            ai_alerts_loaded: Some(Default::default()),
+            ai_alerts_loaded_updated_at: "".to_string(),
+            ai_public_enabled: false,
+            ai_public_url: "".to_string(),
+            ai_test_event_id: "test-1".to_string(),
+            ai_test_username: "".to_string(),
+            ai_test_message: "".to_string(),
+            ai_test_result: "".to_string(),
+        }
+    }
+}
+
+/// A profile that is not active right now. Its settings synced from the API are cached
+/// too, since `LocalClientState::user_cfg` only holds the active profile's.
+#[derive(Default)]
+pub(crate) struct ProfileCache {
+    user_cfg: UserConfig,
+    pub(crate) account: AccountState,
+}
+
+impl TwitchDeskApp {
+    /// Exchange the live account-scoped state with `cache`.
+    fn swap_profile_cache(&mut self, cache: &mut ProfileCache) {
+        mem::swap(&mut self.local.user_cfg, &mut cache.user_cfg);
+        mem::swap(&mut self.account, &mut cache.account);
+        self.code_editor.pending = None;
+    }
+
+    /// Run `f` with the cached profile `id` standing in for the active one, so it updates
+    /// that profile's state (and requests go out with its client). Returns `false` if `id`
+    /// has no cached state.
+    pub(crate) fn with_cached_profile(&mut self, id: &str, f: impl FnOnce(&mut Self)) -> bool {
+        let Some(mut cache) = self.profile_caches.remove(id) else {
+            return false;
+        };
+        let pending = self.code_editor.pending.take();
+        self.swap_profile_cache(&mut cache);
+        let active = mem::replace(&mut self.local.active_profile, id.to_string());
+        f(self);
+        self.local.active_profile = active;
+        self.swap_profile_cache(&mut cache);
+        self.code_editor.pending = pending;
+        self.profile_caches.insert(id.to_string(), cache);
+        true
+    }
+
+    /// Make `id` the active profile, keeping the current profile's state cached in memory.
+    pub(crate) fn switch_profile(&mut self, id: &str) {
+        if id == self.local.active_profile || self.local.profile_index(id).is_none() {
+            return;
+        }
+
+        let cached = self.profile_caches.remove(id);
+        let fresh = cached.is_none();
+        let mut cache = cached.unwrap_or_default();
+        self.swap_profile_cache(&mut cache);
+        self.profile_caches
+            .insert(mem::replace(&mut self.local.active_profile, id.to_string()), cache);
+
+        self.username = self.local.profile().username.clone().unwrap_or_default();
+        self.password.clear();
+        // Different backend: re-probe health right away.
+        self.api_health = None;
+        self.api_health_task = None;
+        self.api_health_last_checked = None;
+
+        self.save_local();
+        self.drafts_check_recovery();
+
+        if self.has_access_token() {
+            if fresh {
+                self.load_user_config_from_api();
+            }
+            if self.screen != Screen::Dashboard {
+                self.start_transition(Screen::Dashboard);
+            }
+        } else {
+            self.screen = Screen::Login;
+            self.pending_screen = None;
+        }
+        self.status = format!("Switched to profile \"{}\".", self.local.profile().name);
+    }
+
+    /// Add a profile (copying the current base URL) and switch to it.
+    pub(crate) fn create_profile(&mut self) -> String {
+        let id = self.local.next_profile_id();
+        let name = format!("Profile {}", self.local.profiles.len() + 1);
+        let url = self.local.profile().api_base_url.clone();
+        self.local.profiles.push(ApiProfile::new(id.clone(), name, url));
+        self.switch_profile(&id);
+        id
+    }
+
+    /// Remove a profile together with its stored token and cached state.
+    pub(crate) fn delete_profile(&mut self, id: &str) {
+        if self.local.profiles.len() <= 1 {
+            return;
+        }
+        let Some(idx) = self.local.profile_index(id) else {
+            return;
+        };
+
+        if id == self.local.active_profile {
+            let other = self
+                .local
+                .profiles
+                .iter()
+                .find(|p| p.id != id)
+                .map(|p| p.id.clone())
+                .unwrap_or_default();
+            self.switch_profile(&other);
+        }
+
+        let profile = self.local.profiles.remove(idx);
+        self.profile_caches.remove(id);
+        if let Some(store) = &self.secrets {
+            if let Err(e) = store.delete(&profile.token_ref) {
+                tracing::warn!(error = ?e, profile = %profile.id, "delete profile token failed");
+            }
+        }
+        self.save_local();
+        self.status = format!("Deleted profile \"{}\".", profile.name);
+    }
+}
@@ -0,0 +1,149 @@
+use eframe::egui;
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::models::{TemplateCreateRequest, TemplateVersionUpdateRequest};
+use crate::templates::{
+    builtin_starters, load_team_starters, preview_query, read_param_schema, unique_template_name,
+    StarterTemplate,
+};
+
+use super::{
+    jobs::JobKind, state::TwitchDeskApp, thumbnails::{Thumbnail, ThumbnailSource}, unsaved::GuardedAction,
+};
+
+/// "New from starter" window.
+#[derive(Default)]
+pub(crate) struct StarterPicker {
+    pub(crate) open: bool,
+    /// Built-in starters first, then the ones in the team folder.
+    pub(crate) starters: Vec<StarterTemplate>,
+    /// Team folder entries that could not be read.
+    pub(crate) team_errors: Vec<String>,
+    pub(crate) selected: Option<String>,
+    /// Name for the new template; follows the selection until edited.
+    pub(crate) name: String,
+}
+
+impl StarterPicker {
+    pub(crate) fn selected(&self) -> Option<&StarterTemplate> {
+        let id = self.selected.as_deref()?;
+        self.starters.iter().find(|s| s.id == id)
+    }
+}
+
+impl TwitchDeskApp {
+    pub(crate) fn starters_open(&mut self) {
+        self.starters.open = true;
+        self.starters_reload();
+    }
+
+    /// Re-read the team folder (built-in starters never change).
+    pub(crate) fn starters_reload(&mut self) {
+        let mut starters = builtin_starters();
+        self.starters.team_errors.clear();
+        let dir = self.local.starters_dir.trim().to_string();
+        if !dir.is_empty() {
+            match load_team_starters(Path::new(&dir)) {
+                Ok((team, errors)) => {
+                    starters.extend(team);
+                    self.starters.team_errors = errors;
+                }
+                Err(e) => self.starters.team_errors.push(format!("{e:#}")),
+            }
+        }
+        self.starters.starters = starters;
+        let still_there = self.starters.selected().is_some();
+        if !still_there {
+            let first = self.starters.starters.first().map(|s| s.id.clone());
+            if let Some(id) = first {
+                self.starters_select(&id);
+            }
+        }
+    }
+
+    pub(crate) fn starters_select(&mut self, id: &str) {
+        let previous = self.starters.selected().map(|s| s.name.clone());
+        self.starters.selected = Some(id.to_string());
+        let Some(name) = self.starters.selected().map(|s| s.name.clone()) else {
+            return;
+        };
+        if self.starters.name.trim().is_empty() || previous.as_deref() == Some(self.starters.name.as_str()) {
+            self.starters.name = name;
+        }
+    }
+
+    /// Preview of `starter` with its parameter defaults and `?mock=true`; rendered on
+    /// first use.
+    pub(crate) fn starters_thumbnail(&mut self, ctx: &egui::Context, starter: &StarterTemplate) -> Thumbnail {
+        let key = format!("starter-{}", starter.content_key());
+        self.thumbnail_or_render(ctx, &key, || {
+            let params = match read_param_schema(&starter.sources.index_html) {
+                Ok(Some(schema)) => schema.resolve(&Default::default()),
+                _ => vec![],
+            };
+            ThumbnailSource::Sources {
+                sources: starter.sources.clone(),
+                query: preview_query(&params, true),
+            }
+        })
+    }
+
+    /// Render the starter's preview again (after a failure).
+    pub(crate) fn starters_rerender_thumbnail(&mut self, starter: &StarterTemplate) {
+        self.thumbnail_forget(&format!("starter-{}", starter.content_key()));
+    }
+
+    /// Create a template named like the picker says and fill its first version with the
+    /// selected starter.
+    pub(crate) fn starters_create(&mut self) {
+        let Some(starter) = self.starters.selected().cloned() else {
+            self.account.templates_status = "Pick a starter first".to_string();
+            return;
+        };
+        let wanted = match self.starters.name.trim() {
+            "" => starter.name.clone(),
+            name => name.to_string(),
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                // Fresh list, so names created elsewhere since the last refresh count too.
+                let taken: Vec<String> = api.templates().await?.templates.into_iter().map(|t| t.name).collect();
+                let name = unique_template_name(&wanted, &taken);
+                let created = api.template_create(&TemplateCreateRequest { name: name.clone() }).await?;
+                if let Some(first) = created.versions.first() {
+                    let req = TemplateVersionUpdateRequest {
+                        index_html: starter.sources.index_html.clone(),
+                        style_css: starter.sources.style_css.clone(),
+                        overlay_js: starter.sources.overlay_js.clone(),
+                    };
+                    api.template_version_update(&created.id, &first.version, &req, None).await?;
+                }
+                Ok((created.id, name, starter.name))
+            },
+            |app, result| match result {
+                Ok((id, name, starter)) => {
+                    app.starters.open = false;
+                    app.starters.name.clear();
+                    app.account.templates_status = format!("Created {name} from the {starter} starter.");
+                    app.templates_refresh_list();
+                    app.templates_guard(GuardedAction::SelectTemplate(id));
+                }
+                Err(e) => {
+                    warn!(error = ?e, "create from starter failed");
+                    app.account.templates_status = format!("Create from starter failed: {e:#}");
+                }
+            },
+        );
+    }
+}
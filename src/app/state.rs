@@ -1,12 +1,55 @@
 use eframe::egui;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::warn;
 
 use crate::{
-    storage,
+    storage::{self, SecretStore, SecretStoreOpen},
     models::LocalClientState,
+    preview_server::PreviewError,
 };
 
-use super::types::{Screen, View};
+use super::{
+    code_editor::CodeEditorState,
+    drafts::{DraftAutosave, RecoverableDraft},
+    jobs::Jobs,
+    mock_events::MockEventsState,
+    profiles::{AccountState, ProfileCache},
+    starters::StarterPicker,
+    thumbnails::Thumbnails,
+    trash::TrashedItem,
+    unsaved::GuardedAction,
+    types::{Screen, View},
+};
+
+/// Wait for edits to settle before writing the local state file.
+const LOCAL_SAVE_DELAY: Duration = Duration::from_millis(750);
+
+/// Passphrase prompt for the encrypted secrets file (shown when no OS keyring is available).
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SecretsUnlock {
+    /// `true` when unlocking an existing file, `false` when choosing a new passphrase.
+    pub(crate) existing: bool,
+    pub(crate) passphrase: String,
+    pub(crate) error: String,
+}
+
+/// Why the local state file could not be loaded, for the recovery prompt.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LocalStateRecovery {
+    pub(crate) error: String,
+    pub(crate) backup_available: bool,
+}
+
+/// Edit window for a profile's name and API base URL.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProfileEditor {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) api_base_url: String,
+    /// Set after the first click on "Delete"; the second click deletes.
+    pub(crate) confirm_delete: bool,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum TemplatesEditorTab {
@@ -18,6 +61,12 @@ pub(crate) enum TemplatesEditorTab {
 pub(crate) struct TwitchDeskApp {
     pub(crate) local: LocalClientState,
     pub(crate) status: String,
+    /// Set when `local-state.json` could not be loaded; nothing is saved until resolved.
+    pub(crate) local_state_recovery: Option<LocalStateRecovery>,
+
+    /// Account-scoped state of inactive profiles, keyed by profile id.
+    pub(crate) profile_caches: HashMap<String, ProfileCache>,
+    pub(crate) profile_editor: Option<ProfileEditor>,
 
     pub(crate) username: String,
     pub(crate) password: String,
@@ -25,13 +74,9 @@ pub(crate) struct TwitchDeskApp {
     pub(crate) screen: Screen,
     pub(crate) pending_screen: Option<Screen>,
     pub(crate) transition_started_at: Option<Instant>,
-    pub(crate) active_view: View,
-
-    pub(crate) test_login: String,
-    pub(crate) test_result: String,
 
-    pub(crate) channel_to_add: String,
-    pub(crate) channel_statuses: Vec<crate::models::ChannelStatus>,
+    /// What the active profile's account has open; inactive ones wait in `profile_caches`.
+    pub(crate) account: AccountState,
 
     pub(crate) api_health: Option<bool>,
     pub(crate) api_health_last_checked: Option<Instant>,
@@ -43,47 +88,60 @@ pub(crate) struct TwitchDeskApp {
     pub(crate) update_check_task: Option<tokio::task::JoinHandle<Result<Option<String>, String>>>,
 
     // Templates
-    pub(crate) templates_list: Vec<crate::models::TemplateListItem>,
-    pub(crate) templates_new_name: String,
-    pub(crate) templates_selected_template_id: Option<String>,
-    pub(crate) templates_selected_template_name: Option<String>,
-    pub(crate) templates_selected_version: Option<String>,
-    pub(crate) templates_versions: Vec<crate::models::TemplateVersionSummary>,
-    pub(crate) templates_editor_tab: TemplatesEditorTab,
-    pub(crate) templates_index_html: String,
-    pub(crate) templates_style_css: String,
-    pub(crate) templates_overlay_js: String,
-    pub(crate) templates_new_version: String,
-    pub(crate) templates_duplicate_template_name: String,
-    pub(crate) templates_status: String,
-
-    // AI Alerts
-    pub(crate) ai_status: String,
-    pub(crate) ai_token_connected: Option<bool>,
-    pub(crate) ai_token_input: String,
-
-    pub(crate) ai_alerts_list: Vec<crate::models::AiAlertListItem>,
-    pub(crate) ai_alerts_selected_id: Option<String>,
-    pub(crate) ai_alerts_name: String,
-    pub(crate) ai_alerts_prompt: String,
-    pub(crate) ai_alerts_is_enabled: bool,
-    pub(crate) ai_alerts_cooldown_ms: i32,
-    pub(crate) ai_public_enabled: bool,
-    pub(crate) ai_public_url: String,
-
-    pub(crate) ai_test_event_id: String,
-    pub(crate) ai_test_username: String,
-    pub(crate) ai_test_message: String,
-    pub(crate) ai_test_result: String,
+    /// Action waiting on the Save / Discard / Cancel prompt.
+    pub(crate) templates_unsaved_prompt: Option<GuardedAction>,
+    /// Set once quitting with unsaved edits was confirmed.
+    pub(crate) close_confirmed: bool,
+
+    pub(crate) drafts: DraftAutosave,
+    /// Drafts from a previous run waiting for Restore / Discard.
+    pub(crate) drafts_recovery: Option<Vec<RecoverableDraft>>,
+    /// Zip bundle path for template export/import.
+    pub(crate) templates_bundle_path: String,
+    /// Confirmed deletes still in their undo window (all profiles).
+    pub(crate) templates_trash: Vec<TrashedItem>,
+    /// Script errors reported by the local preview page.
+    pub(crate) templates_preview_errors: Vec<PreviewError>,
+    /// Find/replace and go-to-line state of the code editor.
+    pub(crate) code_editor: CodeEditorState,
+    /// When to write the local state file after settings changed; see `schedule_local_save`.
+    pub(crate) local_save_at: Option<Instant>,
 
     pub(crate) alert_popup: Option<String>,
 
+    /// Where the bearer token is persisted; `None` while the encrypted file is locked
+    /// (or the user chose not to remember the session).
+    pub(crate) secrets: Option<Box<dyn SecretStore>>,
+    pub(crate) secrets_unlock: Option<SecretsUnlock>,
+
+    /// Serves the editor buffers on localhost; started on first local preview.
+    pub(crate) preview_server: Option<crate::preview_server::PreviewServer>,
+    /// Mock event injector (and its channel to the preview windows).
+    pub(crate) mock_events: MockEventsState,
+    /// "New from starter" window.
+    pub(crate) starters: StarterPicker,
+    /// Rendered page previews (starters, template versions).
+    pub(crate) thumbnails: Thumbnails,
+
+    /// Shared HTTP client (connection pool) used by every `ApiClient`.
+    pub(crate) http: reqwest::Client,
+    pub(crate) jobs: Jobs,
     pub(crate) rt: tokio::runtime::Runtime,
 }
 
 impl TwitchDeskApp {
-    pub(crate) fn new() -> Self {
-        let local = storage::load_local_state().unwrap_or_else(|_| LocalClientState::default());
+    pub(crate) fn new(ctx: &egui::Context) -> Self {
+        let (local, local_state_recovery) = match storage::load_local_state() {
+            Ok(st) => (st, None),
+            Err(e) => {
+                warn!(error = ?e, "load local state failed");
+                let recovery = LocalStateRecovery {
+                    error: format!("{e:#}"),
+                    backup_available: storage::load_local_state_backup().is_ok(),
+                };
+                (LocalClientState::default(), Some(recovery))
+            }
+        };
         let status = match storage::local_state_path() {
             Ok(p) => format!("Local state: {}", p.display()),
             Err(_) => "Local state: <unknown>".to_string(),
@@ -94,31 +152,31 @@ impl TwitchDeskApp {
             .build()
             .expect("tokio runtime");
 
-        let username = local.username.clone().unwrap_or_default();
-        let screen = if local
-            .access_token
-            .as_ref()
-            .map(|t| !t.is_empty())
-            .unwrap_or(false)
-        {
-            Screen::Dashboard
-        } else {
-            Screen::Login
+        let (secrets, secrets_unlock) = match storage::open_secret_store() {
+            SecretStoreOpen::Ready(store) => (Some(store), None),
+            SecretStoreOpen::NeedsPassphrase { existing } => (
+                None,
+                Some(SecretsUnlock {
+                    existing,
+                    ..SecretsUnlock::default()
+                }),
+            ),
         };
 
+        let username = local.profile().username.clone().unwrap_or_default();
+
         let mut app = Self {
             local,
             status,
+            local_state_recovery,
+            profile_caches: HashMap::new(),
+            profile_editor: None,
             username,
             password: "".to_string(),
-            screen,
+            screen: Screen::Login,
             pending_screen: None,
             transition_started_at: None,
-            active_view: View::Home,
-            test_login: "someuser".to_string(),
-            test_result: "".to_string(),
-            channel_to_add: "".to_string(),
-            channel_statuses: vec![],
+            account: AccountState::default(),
 
             api_health: None,
             api_health_last_checked: None,
@@ -128,45 +186,31 @@ impl TwitchDeskApp {
             update_last_error: None,
             update_check_task: None,
 
-            templates_list: vec![],
-            templates_new_name: "".to_string(),
-            templates_selected_template_id: None,
-            templates_selected_template_name: None,
-            templates_selected_version: None,
-            templates_versions: vec![],
-            templates_editor_tab: TemplatesEditorTab::Html,
-            templates_index_html: "".to_string(),
-            templates_style_css: "".to_string(),
-            templates_overlay_js: "".to_string(),
-            templates_new_version: "".to_string(),
-            templates_duplicate_template_name: "".to_string(),
-            templates_status: "".to_string(),
-
-            ai_status: "".to_string(),
-            ai_token_connected: None,
-            ai_token_input: "".to_string(),
-
-            ai_alerts_list: vec![],
-            ai_alerts_selected_id: None,
-            ai_alerts_name: "".to_string(),
-            ai_alerts_prompt: "".to_string(),
-            ai_alerts_is_enabled: true,
-            ai_alerts_cooldown_ms: 0,
-            ai_public_enabled: false,
-            ai_public_url: "".to_string(),
-
-            ai_test_event_id: "test-1".to_string(),
-            ai_test_username: "".to_string(),
-            ai_test_message: "".to_string(),
-            ai_test_result: "".to_string(),
+            templates_unsaved_prompt: None,
+            close_confirmed: false,
+            drafts: DraftAutosave::default(),
+            drafts_recovery: None,
+            templates_bundle_path: "".to_string(),
+            templates_trash: vec![],
+            templates_preview_errors: vec![],
+            code_editor: CodeEditorState::default(),
+            local_save_at: None,
 
             alert_popup: None,
+            secrets,
+            secrets_unlock,
+            preview_server: None,
+            mock_events: MockEventsState::default(),
+            starters: StarterPicker::default(),
+            thumbnails: Thumbnails::default(),
+            http: reqwest::Client::new(),
+            jobs: Jobs::new(ctx.clone()),
             rt,
         };
 
-        // If we have a persisted bearer token, sync user config from the API.
-        if app.screen == Screen::Dashboard {
-            let _ = app.load_user_config_from_api();
+        // Wait for the user's decision before touching an unreadable state file.
+        if app.local_state_recovery.is_none() {
+            app.resume_session();
         }
 
         // Check for updates once on startup (release builds only).
@@ -181,26 +225,150 @@ impl TwitchDeskApp {
         self.transition_started_at = Some(Instant::now());
     }
 
+    /// Load stored tokens and, if the active profile has a session, go straight to the dashboard.
+    pub(crate) fn resume_session(&mut self) {
+        self.load_access_tokens();
+
+        // Leftover drafts are on disk; offer them even if the API can't be reached.
+        self.drafts_check_recovery();
+
+        // If we have a persisted bearer token, sync user config from the API.
+        if self.has_access_token() {
+            self.screen = Screen::Dashboard;
+            self.load_user_config_from_api();
+        }
+    }
+
     pub(crate) fn save_local(&mut self) {
+        if self.local_state_recovery.is_some() {
+            self.status = "Local state not saved: resolve the recovery prompt first.".to_string();
+            return;
+        }
         match storage::save_local_state(&self.local) {
             Ok(path) => self.status = format!("Saved local state to {}", path.display()),
             Err(e) => self.status = format!("Save local state failed: {e:#}"),
         }
+        if let Err(e) = self.persist_access_token() {
+            self.status = format!("Save access token failed: {e:#}");
+        }
+    }
+
+    /// Save the local state once edits settle (typing, dragging a slider), without
+    /// touching the status line. Used for settings changed in the editor panels.
+    pub(crate) fn schedule_local_save(&mut self) {
+        self.local_save_at = Some(Instant::now() + LOCAL_SAVE_DELAY);
+    }
+
+    fn tick_local_save(&mut self, ctx: &egui::Context) {
+        let Some(at) = self.local_save_at else {
+            return;
+        };
+        let now = Instant::now();
+        if now < at {
+            ctx.request_repaint_after(at - now);
+            return;
+        }
+        self.flush_local_save();
+    }
+
+    fn flush_local_save(&mut self) {
+        if self.local_save_at.take().is_none() || self.local_state_recovery.is_some() {
+            return;
+        }
+        if let Err(e) = storage::save_local_state(&self.local) {
+            warn!(error = ?e, "save local state failed");
+            self.status = format!("Save local state failed: {e:#}");
+        }
+    }
+
+    pub(crate) fn has_access_token(&self) -> bool {
+        self.local.profile().has_access_token()
+    }
+
+    /// Write (or remove) the active profile's bearer token to the secret store, if one is open.
+    fn persist_access_token(&self) -> anyhow::Result<()> {
+        let Some(store) = &self.secrets else {
+            return Ok(());
+        };
+        let profile = self.local.profile();
+        match profile.access_token.as_deref().filter(|t| !t.is_empty()) {
+            Some(token) => store.set(&profile.token_ref, token),
+            None => store.delete(&profile.token_ref),
+        }
+    }
+
+    /// Load every profile's bearer token from the secret store. Tokens already in memory
+    /// (from an old plaintext state file, or a login while the store was locked) are moved
+    /// into the store instead and the state file is rewritten without them.
+    pub(crate) fn load_access_tokens(&mut self) {
+        let Some(store) = &self.secrets else {
+            return;
+        };
+        let backend = store.backend_name();
+
+        let mut moved = false;
+        let mut error = None;
+        for profile in &mut self.local.profiles {
+            if let Some(token) = profile.access_token.as_deref().filter(|t| !t.is_empty()) {
+                match store.set(&profile.token_ref, token) {
+                    Ok(()) => moved = true,
+                    Err(e) => {
+                        error = Some(format!("Move session token into the {backend} failed: {e:#}"))
+                    }
+                }
+                continue;
+            }
+            match store.get(&profile.token_ref) {
+                Ok(token) => profile.access_token = token,
+                Err(e) => {
+                    warn!(error = ?e, profile = %profile.id, "read access token failed");
+                    error = Some(format!("Read access token from the {backend} failed: {e:#}"));
+                }
+            }
+        }
+
+        if moved {
+            if let Err(e) = storage::save_local_state(&self.local) {
+                error = Some(format!("Save local state failed: {e:#}"));
+            }
+        }
+        if let Some(e) = error {
+            self.status = e;
+        } else if moved {
+            self.status = format!("Session token stored in the {backend}.");
+        }
     }
 
     pub(crate) fn logout(&mut self) {
-        self.local.access_token = None;
+        self.local.profile_mut().access_token = None;
         self.local.user_cfg = crate::models::UserConfig::default();
         self.save_local();
         self.start_transition(Screen::Login);
-        self.active_view = View::Home;
+        self.account.active_view = View::Home;
         self.status = "Logged out.".to_string();
     }
+
+    /// Called when the API rejects our bearer token (HTTP 401).
+    pub(crate) fn session_expired(&mut self) {
+        if self.screen == Screen::Login || self.pending_screen == Some(Screen::Login) {
+            return;
+        }
+        self.logout();
+        self.status = "Session expired. Please log in again.".to_string();
+    }
 }
 
 impl eframe::App for TwitchDeskApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_jobs();
+        self.tick_close_guard(ctx);
         self.tick_api_health();
+        self.tick_workspace(ctx);
+        self.tick_local_preview();
+        self.tick_drafts(ctx);
+        self.tick_templates_trash(ctx);
+        self.tick_local_save(ctx);
+        self.tick_thumbnails(ctx);
 
         match self.screen {
             Screen::Login => {
@@ -219,6 +387,21 @@ impl eframe::App for TwitchDeskApp {
         }
 
         self.ui_alert_popup(ctx);
+        self.ui_templates_merge_window(ctx);
+        self.ui_templates_diff_window(ctx);
+        self.ui_templates_history_window(ctx);
+        self.ui_templates_starters_window(ctx);
+        self.ui_templates_confirm_dialog(ctx);
+        self.ui_unsaved_changes_dialog(ctx);
+        self.ui_drafts_recovery_dialog(ctx);
+        self.ui_profile_editor(ctx);
+        self.ui_local_state_recovery_dialog(ctx);
+        self.ui_secrets_unlock_dialog(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.templates_trash_flush();
+        self.flush_local_save();
     }
 }
 
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::api::ApiError;
+use crate::models::{TemplateListItem, TemplateTagsRequest, TemplateVersionSummary};
+use crate::templates::{
+    has_tag, has_unpublished_changes, parse_tags, sources_contain, TemplateSort, TemplateSources,
+};
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+/// Search, tag filter and sort of the template list.
+#[derive(Default)]
+pub(crate) struct TemplateSearch {
+    pub(crate) query: String,
+    /// Also match the query against the contents of loaded versions.
+    pub(crate) in_contents: bool,
+    /// Only templates with this tag.
+    pub(crate) tag: Option<String>,
+    pub(crate) sort: TemplateSort,
+    /// Only templates whose latest version isn't published.
+    pub(crate) unpublished_only: bool,
+    /// Tags field of the open template.
+    pub(crate) tags_input: String,
+    /// Saved sources of the versions loaded so far, with the `updated_at` they had.
+    loaded: HashMap<(String, String), (String, TemplateSources)>,
+}
+
+impl TemplateSearch {
+    /// Sources of `version` as last loaded, if still current.
+    fn contents(&self, template_id: &str, version: &TemplateVersionSummary) -> Option<&TemplateSources> {
+        let (updated_at, sources) = self.loaded.get(&(template_id.to_string(), version.version.clone()))?;
+        (*updated_at == version.updated_at).then_some(sources)
+    }
+
+    /// Versions of the list that were loaded and are still current, out of all.
+    pub(crate) fn loaded_count(&self, templates: &[TemplateListItem]) -> (usize, usize) {
+        let versions = templates.iter().flat_map(|t| t.versions.iter().map(move |v| (t, v)));
+        versions.fold((0, 0), |(loaded, all), (t, v)| {
+            (loaded + usize::from(self.contents(&t.id, v).is_some()), all + 1)
+        })
+    }
+
+    pub(crate) fn is_filtering(&self) -> bool {
+        !self.query.trim().is_empty() || self.tag.is_some() || self.unpublished_only
+    }
+
+    fn matches(&self, template: &TemplateListItem) -> bool {
+        if self.tag.as_deref().is_some_and(|tag| !has_tag(template, tag)) {
+            return false;
+        }
+        if self.unpublished_only
+            && !has_unpublished_changes(&template.versions, |v| self.contents(&template.id, v))
+        {
+            return false;
+        }
+        let query = self.query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        template.name.to_lowercase().contains(&query)
+            || template.tags.iter().any(|t| t.to_lowercase().contains(&query))
+            || (self.in_contents
+                && template
+                    .versions
+                    .iter()
+                    .filter_map(|v| self.contents(&template.id, v))
+                    .any(|sources| sources_contain(sources, &query)))
+    }
+}
+
+impl TwitchDeskApp {
+    /// Templates to list: filtered and sorted as the search bar says.
+    pub(crate) fn templates_visible_list(&self) -> Vec<TemplateListItem> {
+        let search = &self.account.templates_search;
+        let mut list: Vec<TemplateListItem> = self
+            .account
+            .templates_list
+            .iter()
+            .filter(|t| search.matches(t))
+            .cloned()
+            .collect();
+        list.sort_by(|a, b| search.sort.compare(a, b));
+        list
+    }
+
+    /// Every tag used in the list, sorted.
+    pub(crate) fn templates_all_tags(&self) -> Vec<String> {
+        let all = self.account.templates_list.iter().flat_map(|t| t.tags.iter().cloned());
+        parse_tags(&all.collect::<Vec<_>>().join(","))
+    }
+
+    /// Keep the saved sources of a version for content search.
+    pub(crate) fn templates_remember_sources(
+        &mut self,
+        template_id: &str,
+        version: &str,
+        updated_at: &str,
+        sources: TemplateSources,
+    ) {
+        self.account.templates_search.loaded.insert(
+            (template_id.to_string(), version.to_string()),
+            (updated_at.to_string(), sources),
+        );
+    }
+
+    /// Load every version of every template that isn't loaded yet (or changed since), so
+    /// content search and the unpublished filter see all of them.
+    pub(crate) fn templates_load_all_contents(&mut self) {
+        let missing: Vec<(String, String)> = self
+            .account
+            .templates_list
+            .iter()
+            .flat_map(|t| {
+                t.versions
+                    .iter()
+                    .filter(|v| self.account.templates_search.contents(&t.id, v).is_none())
+                    .map(|v| (t.id.clone(), v.version.clone()))
+            })
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                // One version failing doesn't lose the others; only a lost session stops early.
+                let mut results = Vec::with_capacity(missing.len());
+                for (template_id, version) in missing {
+                    match api.template_version(&template_id, &version).await {
+                        Err(e @ ApiError::Unauthorized(_)) => return Err(e),
+                        result => results.push((template_id, version, result)),
+                    }
+                }
+                Ok(results)
+            },
+            |app, result| {
+                let results = match result {
+                    Ok(v) => v,
+                    Err(e) => {
+                        warn!(error = ?e, "load versions for search failed");
+                        app.account.templates_status = format!("Load versions for search failed: {e:#}");
+                        return;
+                    }
+                };
+                let mut loaded = 0;
+                let mut failed = Vec::new();
+                for (template_id, version, result) in results {
+                    match result {
+                        Ok(v) => {
+                            app.templates_remember_sources(&template_id, &v.version, &v.updated_at, (&v).into());
+                            loaded += 1;
+                        }
+                        Err(e) => {
+                            warn!(error = ?e, template_id, version, "load version for search failed");
+                            let name = app
+                                .account
+                                .templates_list
+                                .iter()
+                                .find(|t| t.id == template_id)
+                                .map_or(template_id.as_str(), |t| t.name.as_str());
+                            failed.push(format!("{name} {version}: {e}"));
+                        }
+                    }
+                }
+                app.account.templates_status = match failed.len() {
+                    0 => format!("Loaded {loaded} version(s) for search."),
+                    n => {
+                        let more = if n > 3 { "; …" } else { "" };
+                        format!(
+                            "Loaded {loaded} version(s) for search; {n} failed ({}{more}). Load again to retry them.",
+                            failed[..n.min(3)].join("; ")
+                        )
+                    }
+                };
+            },
+        );
+    }
+
+    /// Show the open template's tags in the tags field.
+    pub(crate) fn templates_reset_tags_input(&mut self) {
+        let tags = self
+            .account
+            .templates_selected_template_id
+            .as_deref()
+            .and_then(|id| self.account.templates_list.iter().find(|t| t.id == id))
+            .map(|t| t.tags.join(", "))
+            .unwrap_or_default();
+        self.account.templates_search.tags_input = tags;
+    }
+
+    /// Store the tags field on the open template.
+    pub(crate) fn templates_save_tags(&mut self) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let req = TemplateTagsRequest {
+            tags: parse_tags(&self.account.templates_search.tags_input),
+        };
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template_set_tags(&template_id, &req).await },
+            |app, result| match result {
+                Ok(t) => {
+                    if let Some(item) = app.account.templates_list.iter_mut().find(|i| i.id == t.id) {
+                        item.tags = t.tags.clone();
+                    }
+                    if app.account.templates_selected_template_id.as_deref() == Some(&t.id) {
+                        app.account.templates_search.tags_input = t.tags.join(", ");
+                    }
+                    app.account.templates_status = match t.tags.len() {
+                        0 => format!("Removed the tags of {}.", t.name),
+                        _ => format!("Tagged {} with {}.", t.name, t.tags.join(", ")),
+                    };
+                }
+                Err(e) => {
+                    warn!(error = ?e, "template tags failed");
+                    app.account.templates_status = format!("Save tags failed: {e:#}");
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use eframe::egui;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use tracing::warn;
+
+use crate::models::TemplateVersionSummary;
+use crate::preview_server::PreviewServer;
+use crate::templates::{preview_query, TemplateSources};
+
+use super::state::TwitchDeskApp;
+
+/// Size pages are rendered at; the UI scales the image down.
+const RENDER_SIZE: (u32, u32) = (640, 360);
+/// Time a page gets for fonts, images and first animations before the capture.
+const RENDER_DELAY_MS: u64 = 1500;
+
+/// Rendered preview image of a page.
+#[derive(Clone)]
+pub(crate) enum Thumbnail {
+    /// Queued or rendering.
+    Pending,
+    Ready(egui::TextureHandle),
+    Failed(String),
+}
+
+/// What to render.
+pub(crate) enum ThumbnailSource {
+    /// A page on the backend.
+    Url(String),
+    /// Template files, served by a local preview server; `query` is appended to its URL.
+    Sources { sources: TemplateSources, query: String },
+}
+
+/// Thumbnails by key, cached on disk. Renders run one at a time in the preview helper.
+pub(crate) struct Thumbnails {
+    images: HashMap<String, Thumbnail>,
+    queue: VecDeque<(String, ThumbnailSource)>,
+    in_flight: bool,
+    done_tx: mpsc::Sender<RenderDone>,
+    done_rx: mpsc::Receiver<RenderDone>,
+    /// Serves [`ThumbnailSource::Sources`] renders.
+    server: Option<PreviewServer>,
+}
+
+type RenderDone = (String, Result<egui::TextureHandle>);
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        let (done_tx, done_rx) = mpsc::channel();
+        Self {
+            images: HashMap::new(),
+            queue: VecDeque::new(),
+            in_flight: false,
+            done_tx,
+            done_rx,
+            server: None,
+        }
+    }
+}
+
+/// Key of a saved template version's thumbnail; changes with every save.
+fn version_thumbnail_key(template_id: &str, version: &str, updated_at: &str) -> String {
+    format!("version-{template_id}-{version}-{updated_at}")
+}
+
+fn thumbnail_path(key: &str) -> Result<PathBuf> {
+    let proj = ProjectDirs::from("com", "TwitchDesk", "TwitchDesk")
+        .ok_or_else(|| anyhow::anyhow!("Could not determine local data directory"))?;
+    let dir = proj.cache_dir().join("thumbnails");
+    fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+    let file: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    Ok(dir.join(format!("{file}.png")))
+}
+
+fn load_thumbnail(ctx: &egui::Context, key: &str, path: &Path) -> Result<egui::TextureHandle> {
+    let bytes = fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let image = image::load_from_memory_with_format(&bytes, image::ImageFormat::Png)
+        .context("decode thumbnail")?
+        .to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    let pixels = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+    Ok(ctx.load_texture(key, pixels, egui::TextureOptions::LINEAR))
+}
+
+impl TwitchDeskApp {
+    /// Thumbnail for `key` from memory or the disk cache; `None` if it was never rendered.
+    pub(crate) fn thumbnail(&mut self, ctx: &egui::Context, key: &str) -> Option<Thumbnail> {
+        if !self.thumbnails.images.contains_key(key) {
+            let path = thumbnail_path(key).ok().filter(|p| p.is_file())?;
+            let thumb = match load_thumbnail(ctx, key, &path) {
+                Ok(texture) => Thumbnail::Ready(texture),
+                Err(e) => Thumbnail::Failed(format!("{e:#}")),
+            };
+            self.thumbnails.images.insert(key.to_string(), thumb);
+        }
+        self.thumbnails.images.get(key).cloned()
+    }
+
+    /// Thumbnail for `key`, queueing a render of `source` if there is none yet.
+    pub(crate) fn thumbnail_or_render(
+        &mut self,
+        ctx: &egui::Context,
+        key: &str,
+        source: impl FnOnce() -> ThumbnailSource,
+    ) -> Thumbnail {
+        if let Some(thumb) = self.thumbnail(ctx, key) {
+            return thumb;
+        }
+        self.thumbnails.images.insert(key.to_string(), Thumbnail::Pending);
+        self.thumbnails.queue.push_back((key.to_string(), source()));
+        Thumbnail::Pending
+    }
+
+    /// Preview of a saved template version with `?mock=true`, rendered from its backend
+    /// page; `None` while the username is unknown.
+    pub(crate) fn version_thumbnail(
+        &mut self,
+        ctx: &egui::Context,
+        template_id: &str,
+        template_name: &str,
+        version: &TemplateVersionSummary,
+    ) -> Option<Thumbnail> {
+        // Saves don't refresh the version summaries; the open version has the newer stamp.
+        let open = self.account.templates_selected_template_id.as_deref() == Some(template_id)
+            && self.account.templates_selected_version.as_deref() == Some(version.version.as_str())
+            && !self.account.templates_loaded_updated_at.is_empty();
+        let updated_at = if open {
+            self.account.templates_loaded_updated_at.clone()
+        } else {
+            version.updated_at.clone()
+        };
+        let key = version_thumbnail_key(template_id, &version.version, &updated_at);
+        let url = format!(
+            "{}{}",
+            self.templates_page_url(template_name, &version.version)?,
+            preview_query(&[], true)
+        );
+        Some(self.thumbnail_or_render(ctx, &key, || ThumbnailSource::Url(url)))
+    }
+
+    /// Drop the image for `key` (memory and disk) so the next request renders it again.
+    pub(crate) fn thumbnail_forget(&mut self, key: &str) {
+        if matches!(self.thumbnails.images.get(key), Some(Thumbnail::Pending)) {
+            return;
+        }
+        self.thumbnails.images.remove(key);
+        if let Ok(path) = thumbnail_path(key) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Called every frame: collect the finished render and start the next queued one.
+    pub(crate) fn tick_thumbnails(&mut self, ctx: &egui::Context) {
+        while let Ok((key, result)) = self.thumbnails.done_rx.try_recv() {
+            self.thumbnails.in_flight = false;
+            let thumb = match result {
+                Ok(texture) => Thumbnail::Ready(texture),
+                Err(e) => {
+                    warn!(error = ?e, %key, "thumbnail render failed");
+                    Thumbnail::Failed(format!("{e:#}"))
+                }
+            };
+            self.thumbnails.images.insert(key, thumb);
+        }
+        if self.thumbnails.in_flight {
+            return;
+        }
+        let Some((key, source)) = self.thumbnails.queue.pop_front() else {
+            return;
+        };
+
+        let url = match source {
+            ThumbnailSource::Url(url) => url,
+            ThumbnailSource::Sources { sources, query } => {
+                if self.thumbnails.server.is_none() {
+                    match PreviewServer::start(&self.rt) {
+                        Ok(server) => self.thumbnails.server = Some(server),
+                        Err(e) => {
+                            self.thumbnails.images.insert(key, Thumbnail::Failed(format!("{e:#}")));
+                            return;
+                        }
+                    }
+                }
+                let Some(server) = &self.thumbnails.server else {
+                    return;
+                };
+                server.sync(&sources.index_html, &sources.style_css, &sources.overlay_js);
+                server.url(&query)
+            }
+        };
+        let path = match thumbnail_path(&key) {
+            Ok(p) => p,
+            Err(e) => {
+                self.thumbnails.images.insert(key, Thumbnail::Failed(format!("{e:#}")));
+                return;
+            }
+        };
+
+        self.thumbnails.in_flight = true;
+        let tx = self.thumbnails.done_tx.clone();
+        let ctx = ctx.clone();
+        self.rt.spawn_blocking(move || {
+            let result = crate::preview::capture_screenshot(&url, &path, RENDER_SIZE, RENDER_DELAY_MS)
+                .and_then(|()| load_thumbnail(&ctx, &key, &path));
+            let _ = tx.send((key, result));
+            ctx.request_repaint();
+        });
+    }
+}
+
+/// Draw `thumb` scaled to `width` (16:9 placeholder while pending or failed).
+pub(crate) fn ui_thumbnail(ui: &mut egui::Ui, thumb: &Thumbnail, width: f32) -> egui::Response {
+    let placeholder = egui::vec2(width, width * RENDER_SIZE.1 as f32 / RENDER_SIZE.0 as f32);
+    match thumb {
+        Thumbnail::Ready(texture) => {
+            let size = texture.size_vec2();
+            ui.image((texture.id(), size * (width / size.x.max(1.0))))
+        }
+        Thumbnail::Pending => {
+            let (rect, response) = ui.allocate_exact_size(placeholder, egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+            ui.put(rect, egui::Spinner::new());
+            response.on_hover_text("Rendering preview…")
+        }
+        Thumbnail::Failed(e) => {
+            let (rect, response) = ui.allocate_exact_size(placeholder, egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "no preview",
+                egui::FontId::proportional(10.0),
+                ui.visuals().weak_text_color(),
+            );
+            response.on_hover_text(format!("Preview failed: {e}"))
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::api::{ApiClient, ApiError};
+use crate::models::TemplateRenameRequest;
+use crate::templates::{unique_template_name, TemplateSources};
+
+use super::{jobs::JobKind, profiles::AccountState, state::TwitchDeskApp};
+
+/// How long a deleted template or version can be restored before it is removed on the server.
+pub(crate) const UNDO_WINDOW: Duration = Duration::from_secs(10);
+/// How long quitting waits for deletes still in the undo window.
+const EXIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A template or version to delete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TrashTarget {
+    Template {
+        template_id: String,
+        name: String,
+    },
+    Version {
+        template_id: String,
+        template_name: String,
+        version: String,
+    },
+}
+
+impl TrashTarget {
+    pub(crate) fn template_id(&self) -> &str {
+        match self {
+            TrashTarget::Template { template_id, .. } | TrashTarget::Version { template_id, .. } => template_id,
+        }
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            TrashTarget::Template { name, .. } => format!("template {name}"),
+            TrashTarget::Version {
+                template_name, version, ..
+            } => format!("version {version} of {template_name}"),
+        }
+    }
+
+    async fn delete(&self, api: &ApiClient) -> Result<(), ApiError> {
+        match self {
+            TrashTarget::Template { template_id, .. } => api.template_delete(template_id).await,
+            TrashTarget::Version {
+                template_id, version, ..
+            } => api.template_version_delete(template_id, version).await,
+        }
+    }
+}
+
+/// Template action waiting in the confirmation dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplateConfirm {
+    Delete(TrashTarget),
+    Unpublish {
+        template_id: String,
+        template_name: String,
+        version: String,
+    },
+    DeleteAsset {
+        template_id: String,
+        version: String,
+        name: String,
+    },
+    /// Shows the published version and the one replacing it side by side.
+    Publish {
+        template_id: String,
+        template_name: String,
+        version: String,
+        /// Version published right now.
+        previous: Option<String>,
+    },
+}
+
+/// A confirmed delete that is still in its undo window. It keeps the client it was
+/// confirmed with, so switching profiles or logging out does not send it to another account.
+pub(crate) struct TrashedItem {
+    pub(crate) target: TrashTarget,
+    profile: String,
+    api: ApiClient,
+    deadline: Instant,
+}
+
+impl TrashedItem {
+    pub(crate) fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+}
+
+impl AccountState {
+    /// Remove a deleted template or version from the list and the open template.
+    pub(crate) fn forget_deleted(&mut self, target: &TrashTarget) {
+        match target {
+            TrashTarget::Template { template_id, .. } => {
+                self.templates_list.retain(|t| t.id != *template_id);
+            }
+            TrashTarget::Version {
+                template_id, version, ..
+            } => {
+                if self.templates_selected_template_id.as_deref() == Some(template_id) {
+                    self.templates_versions.retain(|v| v.version != *version);
+                }
+                if let Some(t) = self.templates_list.iter_mut().find(|t| t.id == *template_id) {
+                    t.versions.retain(|v| v.version != *version);
+                }
+            }
+        }
+    }
+}
+
+impl TwitchDeskApp {
+    /// Trashed items of the active profile, oldest first.
+    pub(crate) fn templates_trash_visible(&self) -> impl Iterator<Item = (usize, &TrashedItem)> {
+        let profile = &self.local.active_profile;
+        self.templates_trash
+            .iter()
+            .enumerate()
+            .filter(move |(_, item)| item.profile == *profile)
+    }
+
+    /// Hidden from the list while waiting for the undo window to run out.
+    pub(crate) fn templates_is_trashed(&self, template_id: &str, version: Option<&str>) -> bool {
+        self.templates_trash_visible().any(|(_, item)| match (&item.target, version) {
+            (TrashTarget::Template { template_id: id, .. }, _) => id == template_id,
+            (TrashTarget::Version { template_id: id, version: v, .. }, Some(version)) => {
+                id == template_id && v == version
+            }
+            (TrashTarget::Version { .. }, None) => false,
+        })
+    }
+
+    /// Ask before deleting the selected template.
+    pub(crate) fn templates_confirm_delete_template(&mut self) {
+        let (Some(template_id), Some(name)) = (
+            self.account.templates_selected_template_id.clone(),
+            self.account.templates_selected_template_name.clone(),
+        ) else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        self.account.templates_confirm = Some(TemplateConfirm::Delete(TrashTarget::Template { template_id, name }));
+    }
+
+    /// Ask before deleting the selected version. The published version and the last
+    /// remaining one cannot be deleted.
+    pub(crate) fn templates_confirm_delete_version(&mut self) {
+        let (Some(template_id), Some(version)) = (
+            self.account.templates_selected_template_id.clone(),
+            self.account.templates_selected_version.clone(),
+        ) else {
+            self.account.templates_status = "Select a version first".to_string();
+            return;
+        };
+        if self
+            .account
+            .templates_versions
+            .iter()
+            .any(|v| v.version == version && v.is_published)
+        {
+            self.account.templates_status = "Unpublish this version before deleting it.".to_string();
+            return;
+        }
+        let remaining = self
+            .account
+            .templates_versions
+            .iter()
+            .filter(|v| !self.templates_is_trashed(&template_id, Some(&v.version)))
+            .count();
+        if remaining <= 1 {
+            self.account.templates_status = "This is the only version; delete the template instead.".to_string();
+            return;
+        }
+        let template_name = self
+            .account
+            .templates_selected_template_name
+            .clone()
+            .unwrap_or_else(|| template_id.clone());
+        self.account.templates_confirm = Some(TemplateConfirm::Delete(TrashTarget::Version {
+            template_id,
+            template_name,
+            version,
+        }));
+    }
+
+    pub(crate) fn templates_confirm_unpublish(&mut self) {
+        let (Some(template_id), Some(version)) = (
+            self.account.templates_selected_template_id.clone(),
+            self.account.templates_selected_version.clone(),
+        ) else {
+            self.account.templates_status = "Select a version first".to_string();
+            return;
+        };
+        let template_name = self
+            .account
+            .templates_selected_template_name
+            .clone()
+            .unwrap_or_else(|| template_id.clone());
+        self.account.templates_confirm = Some(TemplateConfirm::Unpublish {
+            template_id,
+            template_name,
+            version,
+        });
+    }
+
+    /// Run the action the confirmation dialog was opened for.
+    pub(crate) fn templates_confirmed(&mut self) {
+        match self.account.templates_confirm.take() {
+            Some(TemplateConfirm::Delete(target)) => self.templates_trash_add(target),
+            Some(TemplateConfirm::Unpublish { template_id, version, .. }) => {
+                self.templates_unpublish(template_id, version)
+            }
+            Some(TemplateConfirm::DeleteAsset {
+                template_id,
+                version,
+                name,
+            }) => self.templates_delete_asset(template_id, version, name),
+            Some(TemplateConfirm::Publish { template_id, version, .. }) => {
+                self.templates_publish(template_id, version)
+            }
+            None => {}
+        }
+    }
+
+    /// Move a template or version to the trash: it disappears from the UI now and is
+    /// deleted on the server once the undo window runs out.
+    fn templates_trash_add(&mut self, target: TrashTarget) {
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let open_template = self.account.templates_selected_template_id.as_deref() == Some(target.template_id());
+        match &target {
+            TrashTarget::Template { template_id, .. } => {
+                if open_template {
+                    self.templates_close_editor();
+                }
+                if self.account.workspace.as_ref().is_some_and(|l| l.template_id == *template_id) {
+                    self.workspace_unlink();
+                }
+            }
+            TrashTarget::Version {
+                template_id, version, ..
+            } => {
+                if open_template && self.account.templates_selected_version.as_deref() == Some(version) {
+                    // Show another version of the same template instead.
+                    let next = self
+                        .account
+                        .templates_versions
+                        .iter()
+                        .map(|v| v.version.clone())
+                        .find(|v| v != version && !self.templates_is_trashed(template_id, Some(v)));
+                    self.templates_discard_edits();
+                    self.account.templates_diff = None;
+                    self.account.templates_merge = None;
+                    match next {
+                        Some(v) => {
+                            self.account.templates_selected_version = Some(v.clone());
+                            self.templates_load_version(template_id, &v);
+                        }
+                        None => self.account.templates_selected_version = None,
+                    }
+                }
+                if self
+                    .account
+                    .workspace
+                    .as_ref()
+                    .is_some_and(|l| l.template_id == *template_id && l.version == *version)
+                {
+                    self.workspace_unlink();
+                }
+            }
+        }
+
+        self.account.templates_status = format!("Deleted {}. Undo is available for a few seconds.", target.describe());
+        self.templates_trash.push(TrashedItem {
+            target,
+            profile: self.local.active_profile.clone(),
+            api,
+            deadline: Instant::now() + UNDO_WINDOW,
+        });
+    }
+
+    /// Deselect the template and clear the editor without prompting.
+    fn templates_close_editor(&mut self) {
+        self.account.templates_selected_template_id = None;
+        self.account.templates_selected_template_name = None;
+        self.account.templates_selected_version = None;
+        self.account.templates_versions.clear();
+        self.account.templates_assets.clear();
+        self.set_editor_sources(TemplateSources::default());
+        self.account.templates_loaded = None;
+        self.account.templates_loaded_updated_at.clear();
+        self.account.templates_diff = None;
+        self.account.templates_merge = None;
+    }
+
+    pub(crate) fn templates_trash_undo(&mut self, index: usize) {
+        if index >= self.templates_trash.len() {
+            return;
+        }
+        let item = self.templates_trash.remove(index);
+        self.account.templates_status = format!("Restored {}.", item.target.describe());
+    }
+
+    /// Called every frame: delete trashed items whose undo window ran out.
+    pub(crate) fn tick_templates_trash(&mut self, ctx: &egui::Context) {
+        let Some(next) = self.templates_trash.iter().map(|i| i.remaining()).min() else {
+            return;
+        };
+        // Keep the countdown moving.
+        ctx.request_repaint_after(next.min(Duration::from_secs(1)));
+
+        let (expired, keep): (Vec<_>, Vec<_>) = self
+            .templates_trash
+            .drain(..)
+            .partition(|i| i.remaining().is_zero());
+        self.templates_trash = keep;
+        for item in expired {
+            // The item may belong to a profile that isn't active anymore; its client still
+            // carries that profile's token, and its result goes to that profile's state.
+            let TrashedItem {
+                target, profile, api, ..
+            } = item;
+            let for_apply = target.clone();
+            self.spawn_job_for_profile(
+                profile,
+                JobKind::Templates,
+                async move { target.delete(&api).await },
+                move |app, result| app.templates_trash_deleted(for_apply, result),
+            );
+        }
+    }
+
+    fn templates_trash_deleted(&mut self, target: TrashTarget, result: Result<(), ApiError>) {
+        match result {
+            Ok(()) | Err(ApiError::NotFound(_)) => {
+                self.account.forget_deleted(&target);
+                self.account.templates_status = format!("Deleted {} permanently.", target.describe());
+            }
+            Err(e) => {
+                warn!(error = ?e, "template delete failed");
+                self.account.templates_status = format!("Delete {} failed: {e:#}", target.describe());
+                self.templates_refresh_list();
+            }
+        }
+    }
+
+    /// Send deletes still in their undo window before quitting, waiting a few seconds at most.
+    pub(crate) fn templates_trash_flush(&mut self) {
+        if self.templates_trash.is_empty() {
+            return;
+        }
+        let items: Vec<_> = self
+            .templates_trash
+            .drain(..)
+            .map(|i| (i.target, i.api))
+            .collect();
+        let result = self.rt.block_on(tokio::time::timeout(EXIT_FLUSH_TIMEOUT, async move {
+            for (target, api) in items {
+                if let Err(e) = target.delete(&api).await {
+                    warn!(error = ?e, what = %target.describe(), "delete on exit failed");
+                }
+            }
+        }));
+        if result.is_err() {
+            warn!("timed out deleting trashed templates on exit");
+        }
+    }
+
+    fn templates_unpublish(&mut self, template_id: String, version: String) {
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template_version_unpublish(&template_id, &version).await },
+            |app, result| match result {
+                Ok(v) => {
+                    if app.account.templates_selected_template_id.as_deref() == Some(&v.template_id) {
+                        for s in app.account.templates_versions.iter_mut().filter(|s| s.version == v.version) {
+                            s.is_published = false;
+                        }
+                    }
+                    app.account.templates_status = format!("Unpublished version {}.", v.version);
+                    app.templates_refresh_list();
+                }
+                Err(e) => {
+                    warn!(error = ?e, "unpublish failed");
+                    app.account.templates_status = format!("Unpublish failed: {e:#}");
+                }
+            },
+        );
+    }
+
+    /// Rename the selected template to `templates_rename_name`.
+    pub(crate) fn templates_rename(&mut self) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        let name = self.account.templates_rename_name.trim().to_string();
+        if name.is_empty() {
+            self.account.templates_status = "Missing new template name".to_string();
+            return;
+        }
+        let taken: Vec<String> = self
+            .account
+            .templates_list
+            .iter()
+            .filter(|t| t.id != template_id)
+            .map(|t| t.name.clone())
+            .collect();
+        if unique_template_name(&name, &taken) != name {
+            self.account.templates_status = format!("A template named {name} already exists.");
+            return;
+        }
+
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let req = TemplateRenameRequest { name };
+        self.spawn_job(
+            JobKind::Templates,
+            async move { api.template_rename(&template_id, &req).await },
+            |app, result| match result {
+                Ok(t) => {
+                    if app.account.templates_selected_template_id.as_deref() == Some(&t.id) {
+                        app.account.templates_selected_template_name = Some(t.name.clone());
+                    }
+                    if let Some(item) = app.account.templates_list.iter_mut().find(|i| i.id == t.id) {
+                        item.name = t.name.clone();
+                    }
+                    if let Some(link) = app.account.workspace.as_mut().filter(|l| l.template_id == t.id) {
+                        link.template_name = t.name.clone();
+                    }
+                    app.account.templates_rename_name.clear();
+                    app.account.templates_status = format!("Renamed template to {}.", t.name);
+                }
+                Err(e) => {
+                    warn!(error = ?e, "rename template failed");
+                    app.account.templates_status = format!("Rename template failed: {e:#}");
+                }
+            },
+        );
+    }
+}
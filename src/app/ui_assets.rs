@@ -0,0 +1,84 @@
+use eframe::egui;
+
+use crate::templates::{asset_extensions, asset_url, format_size, MAX_ASSET_BYTES};
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+impl TwitchDeskApp {
+    /// Images, fonts and sounds stored with the selected version.
+    pub(crate) fn ui_templates_assets(&mut self, ui: &mut egui::Ui) {
+        let title = match self.account.templates_assets.len() {
+            0 => "Assets".to_string(),
+            n => format!("Assets ({n})"),
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt("templates_assets")
+            .show(ui, |ui| {
+                let busy = self.is_busy(JobKind::Templates);
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.account.templates_asset_path)
+                            .hint_text("File to upload")
+                            .desired_width(320.0),
+                    );
+                    if ui
+                        .add_enabled(
+                            !busy && !self.account.templates_asset_path.trim().is_empty(),
+                            egui::Button::new("Upload"),
+                        )
+                        .on_hover_text("A file with the same name replaces the existing asset")
+                        .clicked()
+                    {
+                        self.templates_upload_asset();
+                    }
+                    if ui.add_enabled(!busy, egui::Button::new("Refresh")).clicked() {
+                        self.templates_refresh_assets();
+                    }
+                });
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Up to {} per file: {}. Reference assets by their relative URL, e.g. <img src=\"assets/alert.gif\"> or url(\"assets/font.woff2\").",
+                        format_size(MAX_ASSET_BYTES),
+                        asset_extensions()
+                    ))
+                    .small()
+                    .weak(),
+                );
+
+                if self.account.templates_assets.is_empty() {
+                    ui.label("No assets in this version");
+                    return;
+                }
+                let mut copy = None;
+                let mut delete = None;
+                egui::Grid::new("templates_assets_grid")
+                    .num_columns(5)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for asset in &self.account.templates_assets {
+                            let url = asset_url(&asset.name);
+                            ui.label(&asset.name);
+                            ui.label(format_size(asset.size));
+                            ui.label(egui::RichText::new(&asset.mime_type).weak());
+                            ui.label(egui::RichText::new(&url).monospace());
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Copy URL").clicked() {
+                                    copy = Some(url.clone());
+                                }
+                                if ui.add_enabled(!busy, egui::Button::new("Delete…").small()).clicked() {
+                                    delete = Some(asset.name.clone());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                if let Some(url) = copy {
+                    ui.ctx().copy_text(url.clone());
+                    self.account.templates_status = format!("Copied {url}");
+                }
+                if let Some(name) = delete {
+                    self.templates_confirm_delete_asset(&name);
+                }
+            });
+    }
+}
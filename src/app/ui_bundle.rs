@@ -0,0 +1,46 @@
+use eframe::egui;
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+impl TwitchDeskApp {
+    /// Export the selected template to / import a template from a zip bundle.
+    pub(crate) fn ui_templates_bundle(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Import / export")
+            .id_salt("templates_bundle")
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.templates_bundle_path)
+                        .hint_text("Bundle file or folder")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.horizontal(|ui| {
+                    let busy = self.is_busy(JobKind::Templates);
+                    if ui
+                        .add_enabled(
+                            !busy && self.account.templates_selected_template_id.is_some(),
+                            egui::Button::new("Export selected"),
+                        )
+                        .on_hover_text("Write every version and its publish state to a .zip")
+                        .clicked()
+                    {
+                        self.templates_export_bundle();
+                    }
+                    if ui
+                        .add_enabled(
+                            !busy && !self.templates_bundle_path.trim().is_empty(),
+                            egui::Button::new("Import"),
+                        )
+                        .on_hover_text("Create a new template from a bundle")
+                        .clicked()
+                    {
+                        self.templates_import_bundle();
+                    }
+                });
+                ui.label(
+                    egui::RichText::new("Empty path or a folder exports to <folder>/<template>.twitchdesk.zip.")
+                        .small()
+                        .weak(),
+                );
+            });
+    }
+}
@@ -0,0 +1,443 @@
+use std::sync::Arc;
+
+use eframe::egui::{
+    self,
+    text::{CCursor, CCursorRange, LayoutJob},
+    Color32, Key, KeyboardShortcut, Modifiers, TextFormat,
+};
+
+use crate::templates::{
+    char_to_byte, find_matches, highlight, matching_bracket, FindQuery, TemplateFile, TokenKind,
+};
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+const FIND: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+const REPLACE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::H);
+const GOTO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::G);
+const FIND_NEXT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F3);
+const FIND_PREV: KeyboardShortcut = KeyboardShortcut::new(Modifiers::SHIFT, Key::F3);
+
+#[derive(Hash)]
+struct Palette {
+    keyword: Color32,
+    string: Color32,
+    comment: Color32,
+    number: Color32,
+    tag: Color32,
+    attribute: Color32,
+    punct: Color32,
+    text: Color32,
+    find_match: Color32,
+    find_current: Color32,
+    bracket: Color32,
+}
+
+impl Palette {
+    fn new(visuals: &egui::Visuals) -> Self {
+        if visuals.dark_mode {
+            Self {
+                keyword: Color32::from_rgb(0xC5, 0x86, 0xC0),
+                string: Color32::from_rgb(0xCE, 0x91, 0x78),
+                comment: Color32::from_rgb(0x6A, 0x99, 0x55),
+                number: Color32::from_rgb(0xB5, 0xCE, 0xA8),
+                tag: Color32::from_rgb(0x56, 0x9C, 0xD6),
+                attribute: Color32::from_rgb(0x9C, 0xDC, 0xFE),
+                punct: Color32::from_gray(0xA0),
+                text: visuals.text_color(),
+                find_match: Color32::from_rgba_unmultiplied(0xFF, 0xC8, 0x00, 0x40),
+                find_current: Color32::from_rgba_unmultiplied(0xFF, 0x8C, 0x00, 0xA0),
+                bracket: Color32::from_rgba_unmultiplied(0x00, 0xF5, 0xD4, 0x50),
+            }
+        } else {
+            Self {
+                keyword: Color32::from_rgb(0xAF, 0x00, 0xDB),
+                string: Color32::from_rgb(0xA3, 0x15, 0x15),
+                comment: Color32::from_rgb(0x00, 0x80, 0x00),
+                number: Color32::from_rgb(0x09, 0x86, 0x58),
+                tag: Color32::from_rgb(0x80, 0x00, 0x00),
+                attribute: Color32::from_rgb(0xE5, 0x00, 0x00),
+                punct: Color32::from_gray(0x60),
+                text: visuals.text_color(),
+                find_match: Color32::from_rgba_unmultiplied(0xFF, 0xC8, 0x00, 0x60),
+                find_current: Color32::from_rgba_unmultiplied(0xFF, 0x8C, 0x00, 0xB0),
+                bracket: Color32::from_rgba_unmultiplied(0x00, 0x90, 0x80, 0x50),
+            }
+        }
+    }
+
+    fn color(&self, kind: TokenKind) -> Color32 {
+        match kind {
+            TokenKind::Text => self.text,
+            TokenKind::Keyword => self.keyword,
+            TokenKind::String => self.string,
+            TokenKind::Comment => self.comment,
+            TokenKind::Number => self.number,
+            TokenKind::Tag => self.tag,
+            TokenKind::Attribute => self.attribute,
+            TokenKind::Punct => self.punct,
+        }
+    }
+}
+
+/// Colors `text` and marks find matches and the bracket pair at `cursor` (a byte offset).
+fn layout_code(
+    file: TemplateFile,
+    text: &str,
+    font_id: &egui::FontId,
+    palette: &Palette,
+    re: Option<&regex::Regex>,
+    current: usize,
+    cursor: Option<usize>,
+) -> LayoutJob {
+    let tokens = highlight(file, text);
+    let matches = re.map(|re| find_matches(re, text)).unwrap_or_default();
+    let bracket = cursor.and_then(|c| matching_bracket(text, &tokens, c));
+
+    let mut cuts = vec![0, text.len()];
+    for t in &tokens {
+        cuts.extend([t.range.start, t.range.end]);
+    }
+    for m in &matches {
+        cuts.extend([m.start, m.end]);
+    }
+    if let Some((a, b)) = bracket {
+        cuts.extend([a, a + 1, b, b + 1]);
+    }
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut job = LayoutJob::default();
+    job.wrap.max_width = f32::INFINITY;
+    // Cuts, tokens and matches are all in text order: walk them together.
+    let (mut token_idx, mut match_idx) = (0, 0);
+    for pair in cuts.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        while tokens.get(token_idx).is_some_and(|t| t.range.end <= start) {
+            token_idx += 1;
+        }
+        let kind = tokens
+            .get(token_idx)
+            .filter(|t| t.range.contains(&start))
+            .map(|t| t.kind)
+            .unwrap_or(TokenKind::Text);
+        while matches.get(match_idx).is_some_and(|m| m.end <= start) {
+            match_idx += 1;
+        }
+        let match_idx = matches
+            .get(match_idx)
+            .is_some_and(|m| m.contains(&start))
+            .then_some(match_idx);
+        let background = if match_idx == Some(current) {
+            palette.find_current
+        } else if match_idx.is_some() {
+            palette.find_match
+        } else if bracket.is_some_and(|(a, b)| start == a || start == b) {
+            palette.bracket
+        } else {
+            Color32::TRANSPARENT
+        };
+        job.append(
+            &text[start..end],
+            0.0,
+            TextFormat {
+                font_id: font_id.clone(),
+                color: palette.color(kind),
+                background,
+                ..Default::default()
+            },
+        );
+    }
+    job
+}
+
+/// Everything the layout of the editor text depends on.
+#[derive(Clone, Copy, Hash)]
+struct CodeLayoutKey<'a> {
+    file: TemplateFile,
+    text: &'a str,
+    query: Option<&'a FindQuery>,
+    current: usize,
+    cursor: Option<usize>,
+    font_id: &'a egui::FontId,
+    palette: &'a Palette,
+}
+
+#[derive(Default)]
+struct CodeLayouter;
+
+impl egui::util::cache::ComputerMut<CodeLayoutKey<'_>, LayoutJob> for CodeLayouter {
+    fn compute(&mut self, key: CodeLayoutKey<'_>) -> LayoutJob {
+        let re = key.query.and_then(|q| q.compile().ok().flatten());
+        layout_code(key.file, key.text, key.font_id, key.palette, re.as_ref(), key.current, key.cursor)
+    }
+}
+
+/// Layouts of the last frame, so an unchanged editor isn't highlighted and searched again
+/// every frame.
+type CodeLayoutCache = egui::util::cache::FrameCache<LayoutJob, CodeLayouter>;
+
+impl TwitchDeskApp {
+    /// Editor for the open template file: line numbers, highlighting, find/replace and go-to-line.
+    pub(crate) fn ui_templates_code_editor(&mut self, ui: &mut egui::Ui) {
+        let file = self.account.templates_editor_tab.file();
+        let edit_id = egui::Id::new(("templates_code_edit", file));
+        self.ui_code_editor_shortcuts(ui, edit_id);
+
+        ui.horizontal(|ui| {
+            if ui.small_button("Find…").on_hover_text("Ctrl+F").clicked() {
+                self.code_editor.find_open = true;
+                self.code_editor.focus_find = true;
+            }
+            if ui.small_button("Replace…").on_hover_text("Ctrl+H").clicked() {
+                self.code_editor.find_open = true;
+                self.code_editor.replace_open = true;
+                self.code_editor.focus_find = true;
+            }
+            if ui.small_button("Go to line…").on_hover_text("Ctrl+G").clicked() {
+                self.code_editor.goto_open = true;
+                self.code_editor.focus_goto = true;
+            }
+        });
+        if self.code_editor.find_open {
+            self.ui_code_editor_find_bar(ui);
+        }
+        if self.code_editor.goto_open {
+            self.ui_code_editor_goto_bar(ui);
+        }
+
+        let mut state = egui::TextEdit::load_state(ui.ctx(), edit_id).unwrap_or_default();
+        let mut scroll_to = None;
+        let mut focus = false;
+        if let Some(pending) = self.code_editor.pending.take_if(|p| p.file == file) {
+            state.cursor.set_char_range(Some(CCursorRange::two(
+                CCursor::new(pending.range.start),
+                CCursor::new(pending.range.end),
+            )));
+            state.clone().store(ui.ctx(), edit_id);
+            scroll_to = Some(pending.range.start);
+            focus = pending.focus;
+        }
+
+        let query = self.code_editor.find_open.then(|| self.code_editor.query.clone());
+        let current = self.code_editor.current;
+        let palette = Palette::new(ui.visuals());
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let text = self.templates_text_mut(file);
+        let cursor = state
+            .cursor
+            .char_range()
+            .map(|r| char_to_byte(text, r.primary.index));
+        let line_count = text.split('\n').count();
+        let gutter = (1..=line_count)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut layouter = |ui: &egui::Ui, text: &str, _wrap_width: f32| -> Arc<egui::Galley> {
+            let key = CodeLayoutKey {
+                file,
+                text,
+                query: query.as_ref(),
+                current,
+                cursor,
+                font_id: &font_id,
+                palette: &palette,
+            };
+            let job = ui.memory_mut(|m| m.caches.cache::<CodeLayoutCache>().get(key));
+            ui.fonts(|f| f.layout_job(job))
+        };
+
+        egui::ScrollArea::both()
+            .id_salt(("templates_code_scroll", file))
+            .max_height(380.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        // Matches the TextEdit's inner margin so the numbers line up.
+                        ui.add_space(2.0);
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(gutter).monospace().weak())
+                                .selectable(false),
+                        );
+                    });
+                    let output = egui::TextEdit::multiline(text)
+                        .id(edit_id)
+                        .code_editor()
+                        .desired_rows(18)
+                        .desired_width(ui.available_width())
+                        .layouter(&mut layouter)
+                        .show(ui);
+                    if focus {
+                        output.response.request_focus();
+                    }
+                    if let Some(at) = scroll_to {
+                        let rect = output
+                            .galley
+                            .pos_from_ccursor(CCursor::new(at))
+                            .translate(output.galley_pos.to_vec2());
+                        ui.scroll_to_rect(rect.expand(8.0), Some(egui::Align::Center));
+                    }
+                });
+            });
+
+        let field = match file {
+            TemplateFile::Html => "index_html",
+            TemplateFile::Css => "style_css",
+            TemplateFile::Js => "overlay_js",
+        };
+        self.ui_field_error(ui, JobKind::Templates, field);
+    }
+
+    fn ui_code_editor_shortcuts(&mut self, ui: &mut egui::Ui, edit_id: egui::Id) {
+        let (find, replace, goto, next, prev, escape) = ui.input_mut(|i| {
+            (
+                i.consume_shortcut(&FIND),
+                i.consume_shortcut(&REPLACE),
+                i.consume_shortcut(&GOTO),
+                i.consume_shortcut(&FIND_NEXT),
+                i.consume_shortcut(&FIND_PREV),
+                i.key_pressed(Key::Escape),
+            )
+        });
+        if find || replace {
+            // Seed the query with a single-line selection.
+            let selected = egui::TextEdit::load_state(ui.ctx(), edit_id)
+                .and_then(|s| s.cursor.char_range())
+                .filter(|r| r.primary != r.secondary)
+                .map(|r| {
+                    let text = self.templates_text(self.account.templates_editor_tab.file());
+                    let [a, b] = r.sorted();
+                    text.chars()
+                        .skip(a.index)
+                        .take(b.index - a.index)
+                        .collect::<String>()
+                })
+                .filter(|s| !s.contains('\n'));
+            if let Some(selected) = selected {
+                self.code_editor.query.pattern = selected;
+                self.code_editor.current = 0;
+            }
+            self.code_editor.find_open = true;
+            self.code_editor.replace_open |= replace;
+            self.code_editor.focus_find = true;
+        }
+        if goto {
+            self.code_editor.goto_open = true;
+            self.code_editor.focus_goto = true;
+        }
+        if next || prev {
+            self.code_editor.find_open = true;
+            self.code_editor_find_step(next);
+        }
+        if escape {
+            self.code_editor.find_open = false;
+            self.code_editor.goto_open = false;
+        }
+    }
+
+    fn ui_code_editor_find_bar(&mut self, ui: &mut egui::Ui) {
+        let matches = self.code_editor_matches();
+        let compiled = self.code_editor.query.compile();
+        let mut step = None;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            let state = &mut self.code_editor;
+            let before = state.query.clone();
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut state.query.pattern)
+                    .hint_text("Find")
+                    .desired_width(200.0),
+            );
+            if std::mem::take(&mut state.focus_find) {
+                response.request_focus();
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                step = Some(!ui.input(|i| i.modifiers.shift));
+                response.request_focus();
+            }
+            ui.checkbox(&mut state.query.regex, "Regex");
+            ui.checkbox(&mut state.query.match_case, "Match case");
+            if state.query != before {
+                state.current = 0;
+                changed = true;
+            }
+            if ui.small_button("↑").on_hover_text("Previous (Shift+F3)").clicked() {
+                step = Some(false);
+            }
+            if ui.small_button("↓").on_hover_text("Next (F3)").clicked() {
+                step = Some(true);
+            }
+            match &compiled {
+                Err(err) => {
+                    ui.label(egui::RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+                }
+                Ok(None) => {}
+                Ok(Some(_)) if matches.is_empty() => {
+                    ui.label(egui::RichText::new("No matches").weak());
+                }
+                Ok(Some(_)) => {
+                    let current = state.current.min(matches.len() - 1) + 1;
+                    ui.label(format!("{current} of {}", matches.len()));
+                }
+            }
+            ui.toggle_value(&mut state.replace_open, "Replace");
+            if ui.small_button("✕").on_hover_text("Close (Esc)").clicked() {
+                state.find_open = false;
+            }
+        });
+
+        let mut replace_one = false;
+        let mut replace_all = false;
+        if self.code_editor.replace_open {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.code_editor.replacement)
+                        .hint_text(if self.code_editor.query.regex {
+                            "Replace ($1 for groups)"
+                        } else {
+                            "Replace"
+                        })
+                        .desired_width(200.0),
+                );
+                let enabled = !matches.is_empty();
+                replace_one = ui.add_enabled(enabled, egui::Button::new("Replace")).clicked();
+                replace_all = ui.add_enabled(enabled, egui::Button::new("Replace all")).clicked();
+            });
+        }
+
+        if let Some(forward) = step {
+            self.code_editor_find_step(forward);
+        } else if changed {
+            // Show the first match while typing.
+            let matches = self.code_editor_matches();
+            self.code_editor_select_match(&matches);
+        }
+        if replace_one {
+            self.code_editor_replace_current();
+        } else if replace_all {
+            self.code_editor_replace_all();
+        }
+    }
+
+    fn ui_code_editor_goto_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.code_editor.goto_line)
+                    .hint_text("line or line:column")
+                    .desired_width(120.0),
+            );
+            if std::mem::take(&mut self.code_editor.focus_goto) {
+                response.request_focus();
+            }
+            let submit = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+            if ui.button("Go").clicked() || submit {
+                self.code_editor_goto();
+            }
+            if ui.small_button("✕").on_hover_text("Close (Esc)").clicked() {
+                self.code_editor.goto_open = false;
+            }
+        });
+    }
+}
@@ -1,6 +1,8 @@
 use eframe::egui;
 
-use super::{state::{TwitchDeskApp, TemplatesEditorTab}, types::View};
+use crate::templates::suggest_next_versions;
+
+use super::{jobs::JobKind, state::{TwitchDeskApp, TemplatesEditorTab}, types::View, unsaved::GuardedAction};
 
 impl TwitchDeskApp {
     pub(crate) fn ui_sidebar(&mut self, ctx: &egui::Context) {
@@ -8,51 +10,37 @@ impl TwitchDeskApp {
             ui.heading("Menu");
             ui.separator();
 
-            if ui.selectable_label(self.active_view == View::Home, "Home").clicked() {
-                self.active_view = View::Home;
-            }
-            if ui
-                .selectable_label(self.active_view == View::Settings, "Settings")
-                .clicked()
-            {
-                self.active_view = View::Settings;
-            }
-            if ui
-                .selectable_label(self.active_view == View::Channels, "Channels")
-                .clicked()
-            {
-                self.active_view = View::Channels;
-            }
-            if ui
-                .selectable_label(self.active_view == View::TwitchLookup, "Twitch lookup")
-                .clicked()
-            {
-                self.active_view = View::TwitchLookup;
-            }
-
-            if ui
-                .selectable_label(self.active_view == View::Templates, "Templates")
-                .clicked()
-            {
-                self.active_view = View::Templates;
-                // Auto-load templates when opening the view.
-                self.templates_refresh_list();
+            for (view, label) in [
+                (View::Home, "Home"),
+                (View::Settings, "Settings"),
+                (View::Channels, "Channels"),
+                (View::TwitchLookup, "Twitch lookup"),
+                (View::Templates, "Templates"),
+                (View::AiAlerts, "AI Alerts"),
+            ] {
+                if ui.selectable_label(self.account.active_view == view, label).clicked() {
+                    self.templates_guard(GuardedAction::OpenView(view));
+                }
             }
+        });
+    }
 
-            if ui
-                .selectable_label(self.active_view == View::AiAlerts, "AI Alerts")
-                .clicked()
-            {
-                self.active_view = View::AiAlerts;
-                // Auto-load when opening the view.
+    /// Switch the dashboard view, loading its data.
+    pub(crate) fn open_view(&mut self, view: View) {
+        self.account.active_view = view;
+        match view {
+            // Auto-load when opening the view.
+            View::Templates => self.templates_refresh_list(),
+            View::AiAlerts => {
                 self.ai_token_refresh_status();
                 self.ai_alerts_refresh_list();
             }
-        });
+            _ => {}
+        }
     }
 
     pub(crate) fn ui_view(&mut self, ui: &mut egui::Ui) {
-        match self.active_view {
+        match self.account.active_view {
             View::Home => {
                 ui.heading("Dashboard");
                 ui.label(&self.status);
@@ -61,7 +49,14 @@ impl TwitchDeskApp {
                 ui.label("Connection");
                 ui.horizontal(|ui| {
                     ui.label("API base URL");
-                    ui.text_edit_singleline(&mut self.local.api_base_url);
+                    ui.text_edit_singleline(&mut self.local.profile_mut().api_base_url);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Request timeout (s)");
+                    ui.add(egui::DragValue::new(&mut self.local.request_timeout_secs).range(1..=120));
+                    ui.separator();
+                    ui.label("Retries");
+                    ui.add(egui::DragValue::new(&mut self.local.max_retries).range(0..=10));
                     if ui.button("Save").clicked() {
                         self.save_local();
                     }
@@ -80,6 +75,7 @@ impl TwitchDeskApp {
                         ui.horizontal(|ui| {
                             ui.label("Twitch Client ID");
                             ui.text_edit_singleline(&mut self.local.user_cfg.twitch_client_id);
+                            self.ui_field_error(ui, JobKind::Account, "twitch_client_id");
                         });
                         ui.horizontal(|ui| {
                             ui.label("Twitch Client Secret");
@@ -89,6 +85,7 @@ impl TwitchDeskApp {
                                 )
                                 .password(true),
                             );
+                            self.ui_field_error(ui, JobKind::Account, "twitch_client_secret");
                         });
 
                         ui.add_space(8.0);
@@ -99,13 +96,14 @@ impl TwitchDeskApp {
                         if self.local.user_cfg.public_twitch_avatar_enabled {
                             let account = self
                                 .local
+                                .profile()
                                 .username
                                 .as_deref()
                                 .unwrap_or(self.username.as_str())
                                 .trim();
                             ui.label(format!(
                                 "URL: {}/{}/twitchavatar?username={{name}}",
-                                self.local.api_base_url.trim().trim_end_matches('/'),
+                                self.local.profile().api_base_url.trim().trim_end_matches('/'),
                                 account
                             ));
                         }
@@ -115,11 +113,12 @@ impl TwitchDeskApp {
                         ui.label("Twitch OAuth Redirect URL");
                         let account = self
                             .local
+                            .profile()
                             .username
                             .as_deref()
                             .unwrap_or(self.username.as_str())
                             .trim();
-                        let base = self.local.api_base_url.trim().trim_end_matches('/');
+                        let base = self.local.profile().api_base_url.trim().trim_end_matches('/');
                         let callback_url = format!("{}/{}/twitch/oauth/callback", base, account);
                         ui.horizontal(|ui| {
                             ui.monospace(&callback_url);
@@ -135,9 +134,12 @@ impl TwitchDeskApp {
                         );
 
                         ui.add_space(8.0);
-                        if ui.button("Save").clicked() {
-                            self.save_user_config_to_api();
-                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Save").clicked() {
+                                self.save_user_config_to_api();
+                            }
+                            self.ui_job_spinner(ui, JobKind::Account);
+                        });
                     });
             }
             View::Channels => {
@@ -147,7 +149,7 @@ impl TwitchDeskApp {
 
                 ui.horizontal(|ui| {
                     ui.add(
-                        egui::TextEdit::singleline(&mut self.channel_to_add)
+                        egui::TextEdit::singleline(&mut self.account.channel_to_add)
                             .hint_text("e.g. shroud")
                             .desired_width(240.0),
                     );
@@ -157,16 +159,18 @@ impl TwitchDeskApp {
                     if ui.button("Refresh").clicked() {
                         self.refresh_channel_statuses();
                     }
+                    self.ui_job_spinner(ui, JobKind::Channels);
                 });
+                self.ui_field_error(ui, JobKind::Channels, "login");
 
                 ui.add_space(8.0);
-                if self.channel_statuses.is_empty() {
+                if self.account.channel_statuses.is_empty() {
                     ui.label("No channels yet");
                 } else {
                     egui::Frame::group(ui.style())
                         .inner_margin(egui::Margin::same(12.0))
                         .show(ui, |ui| {
-                            for ch in self.channel_statuses.clone() {
+                            for ch in self.account.channel_statuses.clone() {
                                 ui.horizontal(|ui| {
                                     let dot = if ch.is_live {
                                         egui::RichText::new("●").color(egui::Color32::GREEN)
@@ -194,12 +198,13 @@ impl TwitchDeskApp {
                 ui.label("Test: call cloud API Twitch proxy");
                 ui.horizontal(|ui| {
                     ui.label("login");
-                    ui.text_edit_singleline(&mut self.test_login);
+                    ui.text_edit_singleline(&mut self.account.test_login);
                     if ui.button("Fetch").clicked() {
                         self.test_twitch_lookup();
                     }
+                    self.ui_job_spinner(ui, JobKind::TwitchLookup);
                 });
-                ui.text_edit_multiline(&mut self.test_result);
+                ui.text_edit_multiline(&mut self.account.test_result);
             }
 
             View::Templates => {
@@ -214,8 +219,8 @@ impl TwitchDeskApp {
 
     fn ui_ai_alerts(&mut self, ui: &mut egui::Ui) {
         ui.heading("AI Alerts");
-        if !self.ai_status.trim().is_empty() {
-            ui.label(self.ai_status.clone());
+        if !self.account.ai_status.trim().is_empty() {
+            ui.label(self.account.ai_status.clone());
         }
 
         ui.add_space(8.0);
@@ -223,8 +228,8 @@ impl TwitchDeskApp {
             .inner_margin(egui::Margin::same(12.0))
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    let connected = self.ai_token_connected.unwrap_or(false);
-                    let label = if self.ai_token_connected.is_none() {
+                    let connected = self.account.ai_token_connected.unwrap_or(false);
+                    let label = if self.account.ai_token_connected.is_none() {
                         "Token status: unknown"
                     } else if connected {
                         "Token status: connected"
@@ -235,12 +240,13 @@ impl TwitchDeskApp {
                     if ui.button("Refresh").clicked() {
                         self.ai_token_refresh_status();
                     }
+                    self.ui_job_spinner(ui, JobKind::AiToken);
                 });
 
                 ui.add_space(6.0);
                 ui.horizontal(|ui| {
                     ui.label("OpenAI API key");
-                    ui.add(egui::TextEdit::singleline(&mut self.ai_token_input).password(true));
+                    ui.add(egui::TextEdit::singleline(&mut self.account.ai_token_input).password(true));
                     if ui.button("Save").clicked() {
                         self.ai_token_save();
                     }
@@ -248,6 +254,7 @@ impl TwitchDeskApp {
                         self.ai_token_disconnect();
                     }
                 });
+                self.ui_field_error(ui, JobKind::AiToken, "token");
                 ui.label("Note: Token is stored encrypted in the cloud API.");
             });
 
@@ -259,6 +266,7 @@ impl TwitchDeskApp {
             if ui.button("New alert").clicked() {
                 self.ai_alerts_clear_editor();
             }
+            self.ui_job_spinner(ui, JobKind::AiAlerts);
         });
 
         ui.add_space(10.0);
@@ -273,13 +281,14 @@ impl TwitchDeskApp {
                     ui.heading("Your alerts");
                     ui.add_space(6.0);
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        if self.ai_alerts_list.is_empty() {
+                        if self.account.ai_alerts_list.is_empty() {
                             ui.label("No alerts yet");
                             return;
                         }
 
-                        for a in self.ai_alerts_list.clone() {
+                        for a in self.account.ai_alerts_list.clone() {
                             let selected = self
+                                .account
                                 .ai_alerts_selected_id
                                 .as_deref()
                                 .map(|id| id == a.id)
@@ -301,28 +310,31 @@ impl TwitchDeskApp {
             ui.separator();
 
             ui.vertical(|ui| {
-                let is_editing = self.ai_alerts_selected_id.is_some();
+                let is_editing = self.account.ai_alerts_selected_id.is_some();
                 ui.heading(if is_editing { "Edit alert" } else { "Create alert" });
 
                 ui.add_space(6.0);
                 ui.horizontal(|ui| {
                     ui.label("Name");
-                    ui.text_edit_singleline(&mut self.ai_alerts_name);
+                    ui.text_edit_singleline(&mut self.account.ai_alerts_name);
+                    self.ui_field_error(ui, JobKind::AiAlerts, "name");
                 });
                 ui.add_space(6.0);
                 ui.label("Prompt");
                 ui.add(
-                    egui::TextEdit::multiline(&mut self.ai_alerts_prompt)
+                    egui::TextEdit::multiline(&mut self.account.ai_alerts_prompt)
                         .desired_rows(6)
                         .hint_text("Use {{username}} and {{message}}"),
                 );
+                self.ui_field_error(ui, JobKind::AiAlerts, "prompt");
 
                 ui.add_space(6.0);
                 ui.horizontal(|ui| {
-                    ui.checkbox(&mut self.ai_alerts_is_enabled, "Enabled");
+                    ui.checkbox(&mut self.account.ai_alerts_is_enabled, "Enabled");
                     ui.separator();
                     ui.label("Cooldown (ms)");
-                    ui.add(egui::DragValue::new(&mut self.ai_alerts_cooldown_ms).range(0..=600_000));
+                    ui.add(egui::DragValue::new(&mut self.account.ai_alerts_cooldown_ms).range(0..=600_000));
+                    self.ui_field_error(ui, JobKind::AiAlerts, "cooldown_ms");
                 });
 
                 ui.add_space(8.0);
@@ -342,12 +354,12 @@ impl TwitchDeskApp {
                 ui.add_space(12.0);
                 ui.separator();
 
-                if let Some(alert_id) = self.ai_alerts_selected_id.clone() {
+                if let Some(alert_id) = self.account.ai_alerts_selected_id.clone() {
                     ui.heading("Public trigger");
                     ui.add_space(6.0);
 
                     ui.horizontal(|ui| {
-                        ui.label(if self.ai_public_enabled {
+                        ui.label(if self.account.ai_public_enabled {
                             "Public: enabled"
                         } else {
                             "Public: disabled"
@@ -356,7 +368,7 @@ impl TwitchDeskApp {
                         if ui.button("Refresh").clicked() {
                             self.ai_alert_public_refresh();
                         }
-                        if !self.ai_public_enabled {
+                        if !self.account.ai_public_enabled {
                             if ui.button("Enable public").clicked() {
                                 self.ai_alert_public_enable();
                             }
@@ -365,11 +377,11 @@ impl TwitchDeskApp {
                         }
                     });
 
-                    if !self.ai_public_url.trim().is_empty() {
+                    if !self.account.ai_public_url.trim().is_empty() {
                         ui.horizontal(|ui| {
-                            ui.monospace(self.ai_public_url.clone());
+                            ui.monospace(self.account.ai_public_url.clone());
                             if ui.button("Copy").clicked() {
-                                ui.output_mut(|o| o.copied_text = self.ai_public_url.clone());
+                                ui.output_mut(|o| o.copied_text = self.account.ai_public_url.clone());
                             }
                         });
                     }
@@ -379,29 +391,29 @@ impl TwitchDeskApp {
                     ui.add_space(6.0);
                     ui.horizontal(|ui| {
                         ui.label("event_id");
-                        ui.text_edit_singleline(&mut self.ai_test_event_id);
+                        ui.text_edit_singleline(&mut self.account.ai_test_event_id);
                     });
                     ui.horizontal(|ui| {
                         ui.label("username");
-                        ui.text_edit_singleline(&mut self.ai_test_username);
+                        ui.text_edit_singleline(&mut self.account.ai_test_username);
                     });
                     ui.horizontal(|ui| {
                         ui.label("message");
-                        ui.text_edit_singleline(&mut self.ai_test_message);
+                        ui.text_edit_singleline(&mut self.account.ai_test_message);
                     });
                     ui.horizontal(|ui| {
                         if ui.button("Fire").clicked() {
                             self.ai_alert_test_fire();
                         }
                         if ui.button("Clear").clicked() {
-                            self.ai_test_result.clear();
+                            self.account.ai_test_result.clear();
                         }
                     });
 
-                    if !self.ai_test_result.trim().is_empty() {
+                    if !self.account.ai_test_result.trim().is_empty() {
                         ui.add_space(6.0);
                         ui.add(
-                            egui::TextEdit::multiline(&mut self.ai_test_result)
+                            egui::TextEdit::multiline(&mut self.account.ai_test_result)
                                 .desired_rows(6)
                                 .desired_width(f32::INFINITY),
                         );
@@ -418,27 +430,37 @@ impl TwitchDeskApp {
 
     fn ui_templates(&mut self, ui: &mut egui::Ui) {
         ui.heading("Templates");
-        if !self.templates_status.trim().is_empty() {
-            ui.label(self.templates_status.clone());
+        if !self.account.templates_status.trim().is_empty() {
+            ui.label(self.account.templates_status.clone());
         }
+        self.ui_templates_trash_bar(ui);
 
         ui.add_space(8.0);
         ui.horizontal(|ui| {
             if ui.button("Refresh").clicked() {
                 self.templates_refresh_list();
             }
+            self.ui_job_spinner(ui, JobKind::Templates);
 
             ui.separator();
 
             ui.label("New template");
             ui.add(
-                egui::TextEdit::singleline(&mut self.templates_new_name)
+                egui::TextEdit::singleline(&mut self.account.templates_new_name)
                     .hint_text("e.g. Alerts")
                     .desired_width(180.0),
             );
             if ui.button("Create").clicked() {
                 self.templates_create();
             }
+            if ui
+                .button("New from starter…")
+                .on_hover_text("Start from a bundled or team template")
+                .clicked()
+            {
+                self.starters_open();
+            }
+            self.ui_field_error(ui, JobKind::Templates, "name");
         });
 
         ui.add_space(10.0);
@@ -454,22 +476,9 @@ impl TwitchDeskApp {
                 |ui| {
                     ui.heading("Your templates");
                     ui.add_space(6.0);
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        if self.templates_list.is_empty() {
-                            ui.label("No templates yet");
-                            return;
-                        }
-                        for t in self.templates_list.clone() {
-                            let selected = self
-                                .templates_selected_template_id
-                                .as_deref()
-                                .map(|id| id == t.id)
-                                .unwrap_or(false);
-                            if ui.selectable_label(selected, t.name.clone()).clicked() {
-                                self.templates_select_template(&t.id);
-                            }
-                        }
-                    });
+                    self.ui_templates_bundle(ui);
+                    ui.add_space(6.0);
+                    self.ui_templates_list(ui);
                 },
             );
 
@@ -479,20 +488,52 @@ impl TwitchDeskApp {
                 ui.heading("Editor");
                 ui.add_space(6.0);
 
-                let Some(template_id) = self.templates_selected_template_id.clone() else {
+                let Some(template_id) = self.account.templates_selected_template_id.clone() else {
                     ui.label("Select a template to edit.");
                     return;
                 };
 
                 let template_name = self
+                    .account
                     .templates_selected_template_name
                     .clone()
                     .unwrap_or_else(|| "<unknown>".to_string());
-                ui.label(format!("Template: {}", template_name));
+                ui.horizontal(|ui| {
+                    if self.templates_is_dirty() {
+                        ui.label(format!("Template: {} (unsaved changes)", template_name));
+                    } else {
+                        ui.label(format!("Template: {}", template_name));
+                    }
+                    ui.separator();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.account.templates_rename_name)
+                            .hint_text("New name")
+                            .desired_width(140.0),
+                    );
+                    if ui.button("Rename").clicked() {
+                        self.templates_rename();
+                    }
+                    if ui.button("Delete template…").clicked() {
+                        self.templates_confirm_delete_template();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Tags");
+                    let field = ui.add(
+                        egui::TextEdit::singleline(&mut self.account.templates_search.tags_input)
+                            .hint_text("e.g. alerts, subathon")
+                            .desired_width(220.0),
+                    );
+                    let enter = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Save tags").clicked() || enter {
+                        self.templates_save_tags();
+                    }
+                });
 
                 ui.horizontal(|ui| {
                 ui.label("Version");
-                let mut selected = self.templates_selected_version.clone().unwrap_or_default();
+                let mut selected = self.account.templates_selected_version.clone().unwrap_or_default();
                 egui::ComboBox::from_id_salt("templates_version_combo")
                     .selected_text(if selected.is_empty() {
                         "<none>".to_string()
@@ -500,158 +541,213 @@ impl TwitchDeskApp {
                         selected.clone()
                     })
                     .show_ui(ui, |ui| {
-                        for v in self.templates_versions.clone() {
+                        for v in self.account.templates_versions.clone() {
+                            if self.templates_is_trashed(&template_id, Some(&v.version)) {
+                                continue;
+                            }
                             let tag = if v.is_published { " (published)" } else { "" };
                             let text = format!("{}{}", v.version, tag);
                             ui.selectable_value(&mut selected, v.version.clone(), text);
                         }
                     });
 
-                if selected != self.templates_selected_version.clone().unwrap_or_default() {
+                if selected != self.account.templates_selected_version.clone().unwrap_or_default() {
                     if !selected.trim().is_empty() {
-                        self.templates_load_version(&template_id, &selected);
+                        self.templates_guard(GuardedAction::LoadVersion {
+                            template_id: template_id.clone(),
+                            version: selected,
+                        });
                     }
                 }
+
+                if ui
+                    .add_enabled(self.account.templates_versions.len() > 1, egui::Button::new("Compare…"))
+                    .clicked()
+                {
+                    self.templates_open_diff();
+                }
+                if ui.button("History…").clicked() {
+                    self.templates_open_history();
+                }
                 });
 
                 ui.add_space(6.0);
                 ui.horizontal(|ui| {
-                if ui.button("Save").clicked() {
+                let busy = self.is_busy(JobKind::Templates);
+                if ui.add_enabled(!busy, egui::Button::new("Save")).clicked() {
                     self.templates_save_current_version();
                 }
-                if ui.button("Publish").clicked() {
-                    self.templates_publish_current_version();
+                if ui.add_enabled(!busy, egui::Button::new("Publish")).clicked() {
+                    self.templates_guard(GuardedAction::Publish);
+                }
+                if self.templates_lint_current().is_some_and(|l| l.errors() > 0)
+                    && ui
+                        .add_enabled(!busy, egui::Button::new("Publish anyway"))
+                        .on_hover_text("Publish despite lint errors")
+                        .clicked()
+                {
+                    self.templates_publish_anyway();
+                }
+                if ui.button("Check").on_hover_text("Lint HTML, CSS and JS").clicked() {
+                    self.templates_run_lint();
+                }
+                let published = self
+                    .account
+                    .templates_versions
+                    .iter()
+                    .any(|v| Some(&v.version) == self.account.templates_selected_version.as_ref() && v.is_published);
+                if ui.add_enabled(!busy && published, egui::Button::new("Unpublish…")).clicked() {
+                    self.templates_confirm_unpublish();
+                }
+                if ui.add_enabled(!busy, egui::Button::new("Delete version…")).clicked() {
+                    self.templates_confirm_delete_version();
                 }
+                self.ui_job_spinner(ui, JobKind::Templates);
                 });
 
                 ui.add_space(6.0);
-                if let (Some(ver), Some(username)) = (
-                self.templates_selected_version.clone(),
-                self.local
-                    .username
+                self.ui_templates_params(ui);
+                self.ui_templates_assets(ui);
+
+                if let Some(page) = self
+                    .account
+                    .templates_selected_version
                     .clone()
-                    .or_else(|| Some(self.username.clone()))
-                    .filter(|s| !s.trim().is_empty()),
-                ) {
-                    let base = self.local.api_base_url.trim().trim_end_matches('/');
-                    let url = format!(
-                        "{}/{}/template/{}/{}",
-                        base,
-                        urlencoding::encode(username.trim()),
-                        urlencoding::encode(template_name.trim()),
-                        urlencoding::encode(ver.trim())
-                    );
+                    .and_then(|ver| self.templates_page_url(&template_name, &ver))
+                {
+                    let url = format!("{page}{}", self.templates_preview_query(false));
+                    let mock_url = format!("{page}{}", self.templates_preview_query(true));
                     ui.horizontal(|ui| {
                         ui.label("Preview URL");
                         ui.label(url.clone());
                     });
                     ui.horizontal(|ui| {
+                        if ui.button("Copy").clicked() {
+                            ui.output_mut(|o| o.copied_text = url.clone());
+                            self.account.templates_status = "Copied preview URL.".to_string();
+                        }
+
                         if ui.button("Copy (mock)").clicked() {
-                            ui.output_mut(|o| o.copied_text = format!("{}?mock=true", url));
-                            self.templates_status = "Copied preview URL.".to_string();
+                            ui.output_mut(|o| o.copied_text = mock_url.clone());
+                            self.account.templates_status = "Copied preview URL.".to_string();
                         }
 
                         if ui.button("Preview").clicked() {
-                            match crate::preview::open_preview(&url) {
-                                Ok(()) => self.templates_status = "Opened in-app preview.".to_string(),
-                                Err(e) => self.templates_status = format!("Preview failed: {e:#}"),
+                            match self.open_template_preview(&url) {
+                                Ok(()) => self.account.templates_status = "Opened in-app preview.".to_string(),
+                                Err(e) => self.account.templates_status = format!("Preview failed: {e:#}"),
                             }
                         }
 
                         if ui.button("Preview (mock)").clicked() {
-                            let u = format!("{}?mock=true", url);
-                            match crate::preview::open_preview(&u) {
+                            match self.open_template_preview(&mock_url) {
                                 Ok(()) => {
-                                    self.templates_status = "Opened in-app preview (mock).".to_string()
+                                    self.account.templates_status = "Opened in-app preview (mock).".to_string()
                                 }
-                                Err(e) => self.templates_status = format!("Preview failed: {e:#}"),
+                                Err(e) => self.account.templates_status = format!("Preview failed: {e:#}"),
                             }
                         }
 
                         if ui.button("Open").clicked() {
                             match webbrowser::open(&url) {
-                                Ok(_) => self.templates_status = "Opened preview URL.".to_string(),
-                                Err(e) => self.templates_status = format!("Open failed: {e}"),
+                                Ok(_) => self.account.templates_status = "Opened preview URL.".to_string(),
+                                Err(e) => self.account.templates_status = format!("Open failed: {e}"),
                             }
                         }
 
                         if ui.button("Open (mock)").clicked() {
-                            let u = format!("{}?mock=true", url);
-                            match webbrowser::open(&u) {
-                                Ok(_) => self.templates_status = "Opened preview URL (mock).".to_string(),
-                                Err(e) => self.templates_status = format!("Open failed: {e}"),
+                            match webbrowser::open(&mock_url) {
+                                Ok(_) => self.account.templates_status = "Opened preview URL (mock).".to_string(),
+                                Err(e) => self.account.templates_status = format!("Open failed: {e}"),
                             }
                         }
                     });
                 }
 
+                ui.horizontal(|ui| {
+                    ui.label("Unsaved edits");
+                    if ui.button("Preview local").clicked() {
+                        self.open_local_preview(false);
+                    }
+                    if ui.button("Preview local (mock)").clicked() {
+                        self.open_local_preview(true);
+                    }
+                    if ui.button("Copy local URL").clicked() {
+                        if let Some(u) = self.local_preview_url(false) {
+                            ui.output_mut(|o| o.copied_text = u);
+                            self.account.templates_status = "Copied local preview URL.".to_string();
+                        }
+                    }
+                });
+
+                self.ui_templates_mock_events(ui);
+
+                ui.add_space(6.0);
+                self.ui_templates_workspace(ui);
+
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
                 ui.label("New version");
+                let existing = self.account.templates_versions.iter().map(|v| v.version.as_str());
+                let suggestions = suggest_next_versions(existing);
+                let hint = suggestions.first().map(|(_, label)| label.as_str()).unwrap_or("e.g. 2");
                 ui.add(
-                    egui::TextEdit::singleline(&mut self.templates_new_version)
-                        .hint_text("e.g. 2")
+                    egui::TextEdit::singleline(&mut self.account.templates_new_version)
+                        .hint_text(hint)
                         .desired_width(120.0),
                 );
+                for (bump, label) in &suggestions {
+                    if ui
+                        .small_button(bump.label())
+                        .on_hover_text(format!("Use {label}"))
+                        .clicked()
+                    {
+                        self.account.templates_new_version = label.clone();
+                    }
+                }
                 if ui.button("Create from current").clicked() {
-                    self.templates_create_version_from_current();
+                    self.templates_guard(GuardedAction::CreateVersion);
                 }
+                self.ui_field_error(ui, JobKind::Templates, "new_version");
                 });
 
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
                 ui.label("Duplicate template");
                 ui.add(
-                    egui::TextEdit::singleline(&mut self.templates_duplicate_template_name)
+                    egui::TextEdit::singleline(&mut self.account.templates_duplicate_template_name)
                         .hint_text("e.g. Alerts Copy")
                         .desired_width(160.0),
                 );
                 if ui.button("Duplicate").clicked() {
-                    self.templates_duplicate_template();
+                    self.templates_guard(GuardedAction::DuplicateTemplate);
                 }
+                self.ui_field_error(ui, JobKind::Templates, "new_name");
                 });
 
                 ui.add_space(12.0);
                 ui.horizontal(|ui| {
-                let html_sel = self.templates_editor_tab == TemplatesEditorTab::Html;
-                let css_sel = self.templates_editor_tab == TemplatesEditorTab::Css;
-                let js_sel = self.templates_editor_tab == TemplatesEditorTab::Js;
-
-                if ui.selectable_label(html_sel, "HTML").clicked() {
-                    self.templates_editor_tab = TemplatesEditorTab::Html;
-                }
-                if ui.selectable_label(css_sel, "CSS").clicked() {
-                    self.templates_editor_tab = TemplatesEditorTab::Css;
-                }
-                if ui.selectable_label(js_sel, "JS").clicked() {
-                    self.templates_editor_tab = TemplatesEditorTab::Js;
+                let dirty = self.templates_dirty_files();
+                for (tab, label) in [
+                    (TemplatesEditorTab::Html, "HTML"),
+                    (TemplatesEditorTab::Css, "CSS"),
+                    (TemplatesEditorTab::Js, "JS"),
+                ] {
+                    let text = if dirty.contains(&tab.file()) {
+                        format!("{label} ●")
+                    } else {
+                        label.to_string()
+                    };
+                    if ui.selectable_label(self.account.templates_editor_tab == tab, text).clicked() {
+                        self.account.templates_editor_tab = tab;
+                    }
                 }
                 });
 
+                self.ui_templates_lint(ui);
+                self.ui_templates_preview_errors(ui);
                 ui.add_space(6.0);
-                match self.templates_editor_tab {
-                    TemplatesEditorTab::Html => {
-                        ui.add(
-                            egui::TextEdit::multiline(&mut self.templates_index_html)
-                                .desired_rows(18)
-                                .code_editor(),
-                        );
-                    }
-                    TemplatesEditorTab::Css => {
-                        ui.add(
-                            egui::TextEdit::multiline(&mut self.templates_style_css)
-                                .desired_rows(18)
-                                .code_editor(),
-                        );
-                    }
-                    TemplatesEditorTab::Js => {
-                        ui.add(
-                            egui::TextEdit::multiline(&mut self.templates_overlay_js)
-                                .desired_rows(18)
-                                .code_editor(),
-                        );
-                    }
-                }
+                self.ui_templates_code_editor(ui);
             });
         });
     }
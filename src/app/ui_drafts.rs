@@ -0,0 +1,68 @@
+use eframe::egui;
+
+use super::{drafts::DraftServerState, state::TwitchDeskApp};
+
+impl TwitchDeskApp {
+    /// Offers drafts left behind by a previous run (crash, API outage) for restore.
+    pub(crate) fn ui_drafts_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let Some(items) = self.drafts_recovery.as_ref() else {
+            return;
+        };
+        let profile = self.local.active_profile.clone();
+
+        let mut restore = None;
+        let mut discard = None;
+        let mut later = false;
+        egui::Window::new("Recover unsaved drafts")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("These edits were not saved when TwitchDesk last closed.");
+                ui.add_space(8.0);
+                for (i, item) in items.iter().enumerate() {
+                    if item.draft.profile != profile {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label(item.draft.content.title());
+                        if ui.button("Restore").clicked() {
+                            restore = Some(i);
+                        }
+                        if ui.button("Discard").clicked() {
+                            discard = Some(i);
+                        }
+                    });
+                    let note = match item.server {
+                        DraftServerState::Unchanged => None,
+                        DraftServerState::Changed => Some("The server copy was saved again since this draft was started."),
+                        DraftServerState::Unknown => Some("The server copy could not be checked."),
+                    };
+                    if let Some(note) = note {
+                        ui.label(
+                            egui::RichText::new(note)
+                                .small()
+                                .color(ui.visuals().warn_fg_color),
+                        );
+                    }
+                }
+                ui.add_space(8.0);
+                if ui.button("Decide later").clicked() {
+                    later = true;
+                }
+                ui.label(
+                    egui::RichText::new("Drafts you keep are offered again on the next start.")
+                        .small()
+                        .weak(),
+                );
+            });
+
+        if let Some(i) = restore {
+            self.drafts_restore(i);
+        } else if let Some(i) = discard {
+            self.drafts_discard(i);
+        } else if later {
+            self.drafts_recovery = None;
+        }
+    }
+}
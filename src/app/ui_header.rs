@@ -1,7 +1,7 @@
 use eframe::egui;
 use std::time::{Duration, Instant};
 
-use super::{state::TwitchDeskApp, types::Screen};
+use super::{state::TwitchDeskApp, types::Screen, unsaved::GuardedAction};
 use crate::update;
 
 impl TwitchDeskApp {
@@ -46,16 +46,14 @@ impl TwitchDeskApp {
             return;
         }
 
-        let base = self.local.api_base_url.trim().trim_end_matches('/').to_string();
-        let url = format!("{}/health", base);
+        let Ok(api) = self.api_anonymous() else {
+            self.api_health = Some(false);
+            self.api_health_last_checked = Some(Instant::now());
+            return;
+        };
         self.api_health_task = Some(self.rt.spawn(async move {
-            let client = reqwest::Client::new();
-            let req = client.get(url).send();
-            let resp = tokio::time::timeout(Duration::from_millis(800), req).await;
-            match resp {
-                Ok(Ok(r)) => r.status().is_success(),
-                _ => false,
-            }
+            let resp = tokio::time::timeout(Duration::from_millis(800), api.health()).await;
+            matches!(resp, Ok(Ok(())))
         }));
     }
 
@@ -163,15 +161,18 @@ impl TwitchDeskApp {
 
                     if self.screen == Screen::Dashboard {
                         if ui.button("Logout").clicked() {
-                            self.logout();
+                            self.templates_guard(GuardedAction::Logout);
                         }
-                        if let Some(u) = self.local.username.as_deref() {
+                        if let Some(u) = self.local.profile().username.as_deref() {
                             if !u.is_empty() {
                                 ui.separator();
                                 ui.label(format!("User: {}", u));
                             }
                         }
                     }
+
+                    ui.separator();
+                    self.ui_profile_switcher(ui);
                 });
             });
         });
@@ -0,0 +1,122 @@
+use eframe::egui;
+
+use super::{
+    history::{format_timestamp, history_timeline},
+    jobs::JobKind,
+    state::TwitchDeskApp,
+    thumbnails::ui_thumbnail,
+    unsaved::GuardedAction,
+};
+
+const HISTORY_THUMBNAIL_WIDTH: f32 = 96.0;
+
+impl TwitchDeskApp {
+    /// "Version history" window: every version, newest first, with roll back.
+    pub(crate) fn ui_templates_history_window(&mut self, ctx: &egui::Context) {
+        if !self.account.templates_history_open {
+            return;
+        }
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_history_open = false;
+            return;
+        };
+        let template_name = self
+            .account
+            .templates_selected_template_name
+            .clone()
+            .unwrap_or_else(|| template_id.clone());
+        let busy = self.is_busy(JobKind::Templates);
+        let timeline = history_timeline(&self.account.templates_versions);
+
+        let mut open = true;
+        let mut load = None;
+        let mut rollback = None;
+        egui::Window::new(format!("Version history: {template_name}"))
+            .id(egui::Id::new("templates_history"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([560.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!busy, egui::Button::new("Refresh")).clicked() {
+                        self.templates_refresh_versions();
+                    }
+                    if busy {
+                        ui.spinner();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for v in &timeline {
+                        if self.templates_is_trashed(&template_id, Some(&v.version)) {
+                            continue;
+                        }
+                        ui.horizontal_top(|ui| {
+                            if let Some(thumb) = self.version_thumbnail(ui.ctx(), &template_id, &template_name, v) {
+                                ui_thumbnail(ui, &thumb, HISTORY_THUMBNAIL_WIDTH);
+                            }
+                            ui.vertical(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new(&v.version).strong());
+                                    if v.is_published {
+                                        ui.label(
+                                            egui::RichText::new("LIVE")
+                                                .small()
+                                                .strong()
+                                                .color(ui.visuals().selection.stroke.color),
+                                        );
+                                    }
+                                    if self.account.templates_selected_version.as_deref() == Some(v.version.as_str()) {
+                                        ui.label(egui::RichText::new("open in editor").small().weak());
+                                    }
+                                });
+                                ui.label(
+                                    egui::RichText::new(format!("Saved {}", format_timestamp(&v.updated_at)))
+                                        .small()
+                                        .weak(),
+                                );
+                                let published = match (&v.published_by, &v.published_at) {
+                                    (Some(who), Some(at)) => Some(format!("Published by {who} on {}", format_timestamp(at))),
+                                    (None, Some(at)) => Some(format!("Published on {}", format_timestamp(at))),
+                                    (Some(who), None) => Some(format!("Published by {who}")),
+                                    (None, None) => None,
+                                };
+                                if let Some(published) = published {
+                                    ui.label(egui::RichText::new(published).small().weak());
+                                }
+                                ui.horizontal(|ui| {
+                                    if self.account.templates_selected_version.as_deref() != Some(v.version.as_str())
+                                        && ui.small_button("Open").clicked()
+                                    {
+                                        load = Some(v.version.clone());
+                                    }
+                                    if !v.is_published
+                                        && ui
+                                            .add_enabled(!busy, egui::Button::new("Roll back to this").small())
+                                            .on_hover_text("Publish this version again right away")
+                                            .clicked()
+                                    {
+                                        rollback = Some(v.version.clone());
+                                    }
+                                });
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+            });
+
+        if let Some(version) = load {
+            self.templates_guard(GuardedAction::LoadVersion {
+                template_id: template_id.clone(),
+                version,
+            });
+        }
+        if let Some(version) = rollback {
+            self.templates_rollback(&version);
+        }
+        if !open {
+            self.account.templates_history_open = false;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use eframe::egui;
+
+use crate::templates::LintSeverity;
+
+use super::state::TwitchDeskApp;
+
+impl TwitchDeskApp {
+    /// Lint diagnostics of the editor buffers; clicking one jumps to its line.
+    pub(crate) fn ui_templates_lint(&mut self, ui: &mut egui::Ui) {
+        let outdated = self.templates_lint_outdated();
+        let Some(lint) = self.templates_lint_current() else {
+            return;
+        };
+        if lint.diagnostics.is_empty() {
+            if !outdated {
+                ui.label(egui::RichText::new("No problems found.").small().weak());
+            }
+            return;
+        }
+
+        let mut title = format!("Problems: {} error(s), {} warning(s)", lint.errors(), lint.warnings());
+        if outdated {
+            title.push_str(" (edited since the last check)");
+        }
+        let mut jump = None;
+        egui::CollapsingHeader::new(title)
+            .id_salt("templates_lint")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt("templates_lint_scroll")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for d in &lint.diagnostics {
+                            let (label, color) = match d.severity {
+                                LintSeverity::Error => ("error", ui.visuals().error_fg_color),
+                                LintSeverity::Warning => ("warning", ui.visuals().warn_fg_color),
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(label).color(color));
+                                let location = format!("{}:{}:{}", d.file.file_name(), d.line, d.column);
+                                if ui.link(egui::RichText::new(location).monospace()).clicked() {
+                                    jump = Some((d.file, d.line, d.column));
+                                }
+                                ui.label(&d.message);
+                            });
+                        }
+                    });
+            });
+        if let Some((file, line, column)) = jump {
+            self.templates_jump_to(file, line, Some(column));
+        }
+    }
+
+    /// Script errors reported by the local preview page; clicking one jumps to its line.
+    pub(crate) fn ui_templates_preview_errors(&mut self, ui: &mut egui::Ui) {
+        if self.preview_server.is_none() {
+            return;
+        }
+        // Errors arrive from the preview page without any input in this window.
+        ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+        if self.templates_preview_errors.is_empty() {
+            return;
+        }
+
+        let mut jump = None;
+        let mut clear = false;
+        egui::CollapsingHeader::new(format!("Preview errors: {}", self.templates_preview_errors.len()))
+            .id_salt("templates_preview_errors")
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .id_salt("templates_preview_errors_scroll")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for e in &self.templates_preview_errors {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("error").color(ui.visuals().error_fg_color));
+                                match e.file() {
+                                    Some(file) => {
+                                        let location = format!("{}:{}:{}", file.file_name(), e.line, e.column);
+                                        if ui.link(egui::RichText::new(location).monospace()).clicked() {
+                                            jump = Some(e.clone());
+                                        }
+                                    }
+                                    None if !e.source.is_empty() => {
+                                        ui.label(egui::RichText::new(&e.source).monospace().weak());
+                                    }
+                                    None => {}
+                                }
+                                ui.label(&e.message);
+                            });
+                        }
+                    });
+                clear = ui.small_button("Clear").clicked();
+            });
+        if let Some(e) = jump {
+            self.templates_jump_to_preview_error(&e);
+        }
+        if clear {
+            self.templates_preview_errors.clear();
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use eframe::egui;
+
+use crate::{models::LocalClientState, storage};
+
+use super::state::TwitchDeskApp;
+
+impl TwitchDeskApp {
+    /// Shown instead of silently resetting when `local-state.json` cannot be loaded.
+    pub(crate) fn ui_local_state_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let Some(recovery) = self.local_state_recovery.as_mut() else {
+            return;
+        };
+
+        let mut restore = false;
+        let mut start_fresh = false;
+        egui::Window::new("Local state could not be loaded")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.set_max_width(420.0);
+                ui.label("TwitchDesk could not read its saved settings (profiles, API URLs, sessions):");
+                ui.label(egui::RichText::new(&recovery.error).monospace().weak());
+                ui.add_space(8.0);
+                if !recovery.backup_available {
+                    ui.label("No usable backup was found.");
+                }
+                ui.label("Either way, the unreadable file is kept next to the new one for inspection.");
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(recovery.backup_available, egui::Button::new("Restore last backup"))
+                        .clicked()
+                    {
+                        restore = true;
+                    }
+                    if ui.button("Start fresh").clicked() {
+                        start_fresh = true;
+                    }
+                });
+            });
+
+        let local = if restore {
+            match storage::load_local_state_backup() {
+                Ok(st) => st,
+                Err(e) => {
+                    recovery.error = format!("{e:#}");
+                    recovery.backup_available = false;
+                    return;
+                }
+            }
+        } else if start_fresh {
+            LocalClientState::default()
+        } else {
+            return;
+        };
+
+        let aside = match storage::set_aside_local_state() {
+            Ok(aside) => aside,
+            Err(e) => {
+                recovery.error = format!("{e:#}");
+                return;
+            }
+        };
+
+        self.local_state_recovery = None;
+        self.local = local;
+        self.username = self.local.profile().username.clone().unwrap_or_default();
+        self.save_local();
+        self.resume_session();
+
+        let kept = aside
+            .map(|p| format!(" The unreadable file was kept at {}.", p.display()))
+            .unwrap_or_default();
+        self.status = if restore {
+            format!("Restored local state from backup.{kept}")
+        } else {
+            format!("Started with fresh local state.{kept}")
+        };
+    }
+}
@@ -1,6 +1,6 @@
 use eframe::egui;
 
-use super::state::TwitchDeskApp;
+use super::{jobs::JobKind, state::TwitchDeskApp};
 
 impl TwitchDeskApp {
     pub(crate) fn ui_login(&mut self, ui: &mut egui::Ui) {
@@ -23,21 +23,25 @@ impl TwitchDeskApp {
                         .hint_text("Username")
                         .desired_width(f32::INFINITY),
                 );
+                self.ui_field_error(ui, JobKind::Auth, "username");
                 ui.add(
                     egui::TextEdit::singleline(&mut self.password)
                         .hint_text("Password")
                         .password(true)
                         .desired_width(f32::INFINITY),
                 );
+                self.ui_field_error(ui, JobKind::Auth, "password");
 
                 ui.add_space(8.0);
                 ui.horizontal(|ui| {
-                    if ui.button("Login").clicked() {
+                    let busy = self.is_busy(JobKind::Auth);
+                    if ui.add_enabled(!busy, egui::Button::new("Login")).clicked() {
                         self.login_user();
                     }
-                    if ui.button("Register").clicked() {
+                    if ui.add_enabled(!busy, egui::Button::new("Register")).clicked() {
                         self.register_user();
                     }
+                    self.ui_job_spinner(ui, JobKind::Auth);
                 });
 
                 if !self.status.is_empty() {
@@ -0,0 +1,147 @@
+use eframe::egui;
+
+use crate::templates::{conflict_count, ConflictChoice, MergeChunk, TemplateFile};
+
+use super::state::TwitchDeskApp;
+
+/// Unchanged runs longer than this are folded to their first and last lines.
+const FOLD_ABOVE: usize = 8;
+const FOLD_KEEP: usize = 3;
+
+fn code_lines(ui: &mut egui::Ui, lines: &[String], weak: bool) {
+    for line in lines {
+        let text = egui::RichText::new(line.trim_end_matches(['\n', '\r'])).monospace();
+        ui.label(if weak { text.weak() } else { text });
+    }
+}
+
+fn resolved_lines(ui: &mut egui::Ui, lines: &[String]) {
+    if lines.len() <= FOLD_ABOVE {
+        code_lines(ui, lines, true);
+        return;
+    }
+    code_lines(ui, &lines[..FOLD_KEEP], true);
+    ui.label(
+        egui::RichText::new(format!("… {} unchanged lines …", lines.len() - 2 * FOLD_KEEP))
+            .small()
+            .weak(),
+    );
+    code_lines(ui, &lines[lines.len() - FOLD_KEEP..], true);
+}
+
+impl TwitchDeskApp {
+    /// Three-way merge (base / mine / server) shown when a save hit a newer server copy.
+    pub(crate) fn ui_templates_merge_window(&mut self, ctx: &egui::Context) {
+        let Some(merge) = self.account.templates_merge.as_mut() else {
+            return;
+        };
+
+        let mut apply = false;
+        let mut keep_mine = false;
+        let mut take_server = false;
+        let mut cancel = false;
+        egui::Window::new("Merge server changes")
+            .collapsible(false)
+            .resizable(true)
+            .default_size([960.0, 600.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Version {} was saved by someone else since you loaded it. Pick what to keep for each conflict.",
+                    merge.version
+                ));
+                if !merge.message.trim().is_empty() {
+                    ui.label(egui::RichText::new(&merge.message).weak());
+                }
+
+                ui.horizontal(|ui| {
+                    for (file, chunks) in &merge.files {
+                        let conflicts = conflict_count(chunks);
+                        let label = if conflicts > 0 {
+                            format!("{} ({conflicts})", file.file_name())
+                        } else {
+                            file.file_name().to_string()
+                        };
+                        if ui.selectable_label(merge.file == *file, label).clicked() {
+                            merge.file = *file;
+                        }
+                    }
+                });
+                ui.separator();
+
+                let file: TemplateFile = merge.file;
+                let unresolved = merge.unresolved();
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .max_height(ui.available_height() - 48.0)
+                    .show(ui, |ui| {
+                        let chunks = merge.chunks_mut(file);
+                        if !chunks.iter().any(|c| matches!(c, MergeChunk::Conflict { .. })) {
+                            ui.label("No conflicts in this file; changes from both sides are merged.");
+                        }
+                        for (i, chunk) in chunks.iter_mut().enumerate() {
+                            match chunk {
+                                MergeChunk::Resolved(lines) => resolved_lines(ui, lines),
+                                MergeChunk::Conflict {
+                                    base,
+                                    local,
+                                    remote,
+                                    choice,
+                                } => {
+                                    egui::Frame::group(ui.style()).show(ui, |ui| {
+                                        ui.horizontal(|ui| {
+                                            ui.label(
+                                                egui::RichText::new("Conflict")
+                                                    .color(ui.visuals().warn_fg_color),
+                                            );
+                                            ui.radio_value(choice, Some(ConflictChoice::Local), "Mine");
+                                            ui.radio_value(choice, Some(ConflictChoice::Remote), "Server");
+                                            ui.radio_value(choice, Some(ConflictChoice::Both), "Both");
+                                        });
+                                        egui::Grid::new(("templates_merge_conflict", i))
+                                            .num_columns(3)
+                                            .min_col_width(260.0)
+                                            .show(ui, |ui| {
+                                                ui.strong("Base");
+                                                ui.strong("Mine");
+                                                ui.strong("Server");
+                                                ui.end_row();
+                                                for lines in [&*base, &*local, &*remote] {
+                                                    ui.vertical(|ui| code_lines(ui, lines, false));
+                                                }
+                                                ui.end_row();
+                                            });
+                                    });
+                                }
+                            }
+                        }
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    apply = ui
+                        .add_enabled(unresolved == 0, egui::Button::new("Apply merge"))
+                        .on_disabled_hover_text(format!("{unresolved} conflict(s) left"))
+                        .clicked();
+                    keep_mine = ui
+                        .button("Keep mine")
+                        .on_hover_text("Save your version over the server copy")
+                        .clicked();
+                    take_server = ui
+                        .button("Take server copy")
+                        .on_hover_text("Discard your edits")
+                        .clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if apply {
+            self.templates_merge_apply();
+        } else if keep_mine {
+            self.templates_merge_keep_mine();
+        } else if take_server {
+            self.templates_merge_take_server();
+        } else if cancel {
+            self.account.templates_merge = None;
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::models::MockEvent;
+
+use super::state::TwitchDeskApp;
+
+/// Fields of `event`, edited in place.
+fn ui_mock_event_fields(ui: &mut egui::Ui, event: &mut MockEvent) {
+    let text = |ui: &mut egui::Ui, label: &str, value: &mut String, width: f32| {
+        ui.label(label);
+        ui.add(egui::TextEdit::singleline(value).desired_width(width));
+    };
+    let number = |ui: &mut egui::Ui, label: &str, value: &mut u32| {
+        ui.label(label);
+        ui.add(egui::DragValue::new(value).range(1..=1_000_000));
+    };
+    match event {
+        MockEvent::Follow { user } | MockEvent::Sub { user } => text(ui, "User", user, 120.0),
+        MockEvent::Resub { user, months, message } => {
+            text(ui, "User", user, 120.0);
+            number(ui, "Months", months);
+            text(ui, "Message", message, 180.0);
+        }
+        MockEvent::Cheer { user, bits, message } => {
+            text(ui, "User", user, 120.0);
+            number(ui, "Bits", bits);
+            text(ui, "Message", message, 180.0);
+        }
+        MockEvent::Raid { user, viewers } => {
+            text(ui, "User", user, 120.0);
+            number(ui, "Viewers", viewers);
+        }
+        MockEvent::Chat { user, message } => {
+            text(ui, "User", user, 120.0);
+            text(ui, "Message", message, 220.0);
+        }
+        MockEvent::AiAlert { user, text: alert } => {
+            text(ui, "User", user, 120.0);
+            text(ui, "Text", alert, 260.0);
+        }
+    }
+}
+
+impl TwitchDeskApp {
+    /// Panel that sends synthetic events into open preview windows.
+    pub(crate) fn ui_templates_mock_events(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Mock events")
+            .id_salt("templates_mock_events")
+            .show(ui, |ui| {
+                // Windows connect and finish replays without any input here.
+                ui.ctx().request_repaint_after(Duration::from_millis(500));
+                let connected = self.mock_events_connected();
+                let status = if connected == 0 {
+                    "No preview window connected. Windows opened with Preview or Preview local receive the events.".to_string()
+                } else {
+                    format!("{connected} preview window(s) connected.")
+                };
+                ui.label(egui::RichText::new(status).small().weak());
+
+                ui.horizontal_wrapped(|ui| {
+                    let draft = &mut self.mock_events.draft;
+                    egui::ComboBox::from_id_salt("templates_mock_event_kind")
+                        .selected_text(draft.kind_label())
+                        .show_ui(ui, |ui| {
+                            for sample in MockEvent::samples() {
+                                let selected = std::mem::discriminant(&sample) == std::mem::discriminant(draft);
+                                if ui.selectable_label(selected, sample.kind_label()).clicked() && !selected {
+                                    // Keep the user when switching kinds.
+                                    let user = draft.user().to_string();
+                                    *draft = sample;
+                                    set_user(draft, user);
+                                }
+                            }
+                        });
+                    ui_mock_event_fields(ui, draft);
+                });
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(connected > 0, egui::Button::new("Send")).clicked() {
+                        let event = self.mock_events.draft.clone();
+                        self.mock_events_send(&event);
+                    }
+                    if ui.button("Add to sequence").clicked() {
+                        let event = self.mock_events.draft.clone();
+                        self.mock_events_add_step(event);
+                    }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.mock_events.preset_name)
+                            .hint_text("Preset name")
+                            .desired_width(140.0),
+                    );
+                    if ui.button("Save preset").clicked() {
+                        self.mock_events_save_preset();
+                    }
+                });
+
+                self.ui_mock_event_presets(ui, connected);
+                self.ui_mock_event_sequence(ui, connected);
+            });
+    }
+
+    fn ui_mock_event_presets(&mut self, ui: &mut egui::Ui, connected: usize) {
+        if self.local.mock_event_presets.is_empty() {
+            return;
+        }
+        ui.add_space(4.0);
+        ui.label(egui::RichText::new("Presets").strong());
+        let mut send = None;
+        let mut load = None;
+        let mut add = None;
+        let mut delete = None;
+        for (i, preset) in self.local.mock_event_presets.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(&preset.name);
+                ui.label(egui::RichText::new(preset.event.summary()).weak());
+                if ui.add_enabled(connected > 0, egui::Button::new("Send").small()).clicked() {
+                    send = Some(preset.event.clone());
+                }
+                if ui.small_button("Edit").clicked() {
+                    load = Some(i);
+                }
+                if ui.small_button("Add to sequence").clicked() {
+                    add = Some(preset.event.clone());
+                }
+                if ui.small_button("Delete").clicked() {
+                    delete = Some(i);
+                }
+            });
+        }
+        if let Some(event) = send {
+            self.mock_events_send(&event);
+        }
+        if let Some(preset) = load.and_then(|i| self.local.mock_event_presets.get(i)) {
+            self.mock_events.draft = preset.event.clone();
+            self.mock_events.preset_name = preset.name.clone();
+        }
+        if let Some(event) = add {
+            self.mock_events_add_step(event);
+        }
+        if let Some(i) = delete {
+            self.mock_events_delete_preset(i);
+        }
+    }
+
+    fn ui_mock_event_sequence(&mut self, ui: &mut egui::Ui, connected: usize) {
+        if self
+            .mock_events
+            .replay
+            .as_ref()
+            .is_some_and(|r| r.is_finished())
+        {
+            self.mock_events.replay = None;
+        }
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Sequence").strong());
+            ui.label("New steps wait");
+            ui.add(
+                egui::DragValue::new(&mut self.mock_events.step_delay_ms)
+                    .range(0..=60_000)
+                    .speed(50)
+                    .suffix(" ms"),
+            );
+        });
+        if self.local.mock_event_sequence.is_empty() {
+            ui.label(
+                egui::RichText::new("Add events to replay them in order, e.g. to test an alert queue.")
+                    .small()
+                    .weak(),
+            );
+            return;
+        }
+
+        let replaying = self.mock_events.replay.is_some();
+        let mut changed = false;
+        let mut remove = None;
+        let mut swap = None;
+        let len = self.local.mock_event_sequence.len();
+        for (i, step) in self.local.mock_event_sequence.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(!replaying, |ui| {
+                    ui.label(format!("{}.", i + 1));
+                    ui.label("after");
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut step.delay_ms)
+                                .range(0..=60_000)
+                                .speed(50)
+                                .suffix(" ms"),
+                        )
+                        .changed();
+                    ui.label(step.event.summary());
+                    if ui.add_enabled(i > 0, egui::Button::new("↑").small()).clicked() {
+                        swap = Some((i - 1, i));
+                    }
+                    if ui.add_enabled(i + 1 < len, egui::Button::new("↓").small()).clicked() {
+                        swap = Some((i, i + 1));
+                    }
+                    if ui.small_button("✕").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            });
+        }
+        if let Some((a, b)) = swap {
+            self.local.mock_event_sequence.swap(a, b);
+            changed = true;
+        }
+        if let Some(i) = remove {
+            self.local.mock_event_sequence.remove(i);
+            changed = true;
+        }
+        if changed {
+            self.schedule_local_save();
+        }
+
+        ui.horizontal(|ui| {
+            match &self.mock_events.replay {
+                Some(replay) => {
+                    let label = format!("Replaying: {} of {} sent", replay.sent(), replay.total);
+                    if ui.button("Stop").clicked() {
+                        self.mock_events_stop();
+                    }
+                    ui.label(label);
+                }
+                None => {
+                    if ui
+                        .add_enabled(connected > 0, egui::Button::new("Replay sequence"))
+                        .clicked()
+                    {
+                        self.mock_events_replay();
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.local.mock_event_sequence.clear();
+                        self.schedule_local_save();
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn set_user(event: &mut MockEvent, value: String) {
+    match event {
+        MockEvent::Follow { user }
+        | MockEvent::Sub { user }
+        | MockEvent::Resub { user, .. }
+        | MockEvent::Cheer { user, .. }
+        | MockEvent::Raid { user, .. }
+        | MockEvent::Chat { user, .. }
+        | MockEvent::AiAlert { user, .. } => *user = value,
+    }
+}
@@ -0,0 +1,127 @@
+use eframe::egui;
+
+use crate::templates::{format_number, hex_color, parse_hex_color, ParamKind, PARAMS_SCRIPT_ID};
+
+use super::state::TwitchDeskApp;
+
+impl TwitchDeskApp {
+    /// Form generated from the template's parameter manifest.
+    pub(crate) fn ui_templates_params(&mut self, ui: &mut egui::Ui) {
+        let schema = self.templates_param_schema();
+        let title = match &schema {
+            Ok(Some(schema)) => format!("Parameters ({})", schema.params.len()),
+            _ => "Parameters".to_string(),
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt("templates_params")
+            .show(ui, |ui| {
+                let schema = match schema {
+                    Ok(Some(schema)) => schema,
+                    Ok(None) => {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Declare parameters in a <script type=\"application/json\" id=\"{PARAMS_SCRIPT_ID}\"> block in index.html; templates read them from the URL query string."
+                            ))
+                            .small()
+                            .weak(),
+                        );
+                        if ui.button("Add example parameters").clicked() {
+                            self.templates_add_param_manifest();
+                        }
+                        return;
+                    }
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("{e:#}"));
+                        return;
+                    }
+                };
+
+                let mut values = self.templates_param_values();
+                let mut changed = false;
+                egui::Grid::new("templates_params_grid")
+                    .num_columns(3)
+                    .spacing([8.0, 4.0])
+                    .show(ui, |ui| {
+                        for p in &schema.params {
+                            let current = p.resolve(values.get(&p.name));
+                            let mut value = current.clone();
+                            ui.label(p.label()).on_hover_text(&p.name);
+                            match &p.kind {
+                                ParamKind::Text => {
+                                    ui.add(egui::TextEdit::singleline(&mut value).desired_width(200.0));
+                                }
+                                ParamKind::Number { min, max, step } => {
+                                    let mut n = value.parse::<f64>().unwrap_or_default();
+                                    let step = step.unwrap_or(1.0);
+                                    let mut drag = egui::DragValue::new(&mut n)
+                                        .speed(step)
+                                        .range(min.unwrap_or(f64::NEG_INFINITY)..=max.unwrap_or(f64::INFINITY));
+                                    if step.fract() == 0.0 {
+                                        drag = drag.max_decimals(0);
+                                    }
+                                    if ui.add(drag).changed() {
+                                        value = format_number(n);
+                                    }
+                                }
+                                ParamKind::Boolean => {
+                                    let mut b = value == "true";
+                                    if ui.checkbox(&mut b, "").changed() {
+                                        value = b.to_string();
+                                    }
+                                }
+                                ParamKind::Color => {
+                                    let mut rgb = parse_hex_color(&value).unwrap_or([255, 255, 255]);
+                                    ui.horizontal(|ui| {
+                                        if ui.color_edit_button_srgb(&mut rgb).changed() {
+                                            value = hex_color(rgb);
+                                        }
+                                        ui.monospace(&value);
+                                    });
+                                }
+                                ParamKind::Select { options } => {
+                                    egui::ComboBox::from_id_salt(("templates_param", &p.name))
+                                        .selected_text(&value)
+                                        .show_ui(ui, |ui| {
+                                            for o in options {
+                                                ui.selectable_value(&mut value, o.clone(), o);
+                                            }
+                                        });
+                                }
+                            }
+                            if value != current {
+                                values.insert(p.name.clone(), value);
+                                changed = true;
+                            }
+                            let is_default = values
+                                .get(&p.name)
+                                .is_none_or(|v| *v == p.default_value());
+                            if ui
+                                .add_enabled(!is_default, egui::Button::new("Reset").small())
+                                .clicked()
+                            {
+                                values.remove(&p.name);
+                                changed = true;
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Reset all").clicked() {
+                        values.clear();
+                        changed = true;
+                    }
+                    ui.label(
+                        egui::RichText::new("Saved for this template and added to the preview URLs.")
+                            .small()
+                            .weak(),
+                    );
+                });
+                if changed {
+                    // Drop values of parameters the manifest no longer declares.
+                    values.retain(|name, _| schema.params.iter().any(|p| p.name == *name));
+                    self.templates_set_param_values(values);
+                }
+            });
+    }
+}
@@ -0,0 +1,142 @@
+use eframe::egui;
+
+use super::state::{ProfileEditor, TwitchDeskApp};
+
+impl TwitchDeskApp {
+    /// Header combo box listing all profiles; picking one switches immediately.
+    pub(crate) fn ui_profile_switcher(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to = None;
+        let mut create = false;
+        let mut edit = false;
+
+        egui::ComboBox::from_id_salt("profile_switcher")
+            .selected_text(format!("Profile: {}", self.local.profile().name))
+            .show_ui(ui, |ui| {
+                for p in &self.local.profiles {
+                    let label = match p.username.as_deref().filter(|u| !u.is_empty()) {
+                        Some(u) => format!("{} ({u})", p.name),
+                        None => p.name.clone(),
+                    };
+                    let label = if p.has_access_token() {
+                        label
+                    } else {
+                        format!("{label} – signed out")
+                    };
+                    if ui
+                        .selectable_label(p.id == self.local.active_profile, label)
+                        .clicked()
+                    {
+                        switch_to = Some(p.id.clone());
+                    }
+                }
+                ui.separator();
+                if ui.button("New profile…").clicked() {
+                    create = true;
+                }
+                if ui.button("Edit profile…").clicked() {
+                    edit = true;
+                }
+            });
+
+        if let Some(id) = switch_to {
+            self.switch_profile(&id);
+        } else if create {
+            self.create_profile();
+            self.open_profile_editor();
+        } else if edit {
+            self.open_profile_editor();
+        }
+    }
+
+    fn open_profile_editor(&mut self) {
+        let p = self.local.profile();
+        self.profile_editor = Some(ProfileEditor {
+            id: p.id.clone(),
+            name: p.name.clone(),
+            api_base_url: p.api_base_url.clone(),
+            confirm_delete: false,
+        });
+    }
+
+    pub(crate) fn ui_profile_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = self.profile_editor.as_mut() else {
+            return;
+        };
+
+        let can_delete = self.local.profiles.len() > 1;
+        let mut open = true;
+        let mut save = false;
+        let mut delete = false;
+        let mut cancel = false;
+        egui::Window::new("Profile")
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                egui::Grid::new("profile_editor_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name");
+                        ui.text_edit_singleline(&mut editor.name);
+                        ui.end_row();
+                        ui.label("API base URL");
+                        ui.text_edit_singleline(&mut editor.api_base_url);
+                        ui.end_row();
+                    });
+                ui.label(
+                    egui::RichText::new("Each profile keeps its own login; switching does not log the others out.")
+                        .small()
+                        .weak(),
+                );
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!editor.name.trim().is_empty(), egui::Button::new("Save"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                    let label = if editor.confirm_delete {
+                        "Really delete?"
+                    } else {
+                        "Delete"
+                    };
+                    if ui.add_enabled(can_delete, egui::Button::new(label)).clicked() {
+                        if editor.confirm_delete {
+                            delete = true;
+                        } else {
+                            editor.confirm_delete = true;
+                        }
+                    }
+                });
+            });
+
+        if save {
+            let editor = self.profile_editor.take().unwrap_or_default();
+            let Some(idx) = self.local.profile_index(&editor.id) else {
+                return;
+            };
+            let profile = &mut self.local.profiles[idx];
+            let url = editor.api_base_url.trim().to_string();
+            let url_changed = profile.api_base_url != url;
+            profile.name = editor.name.trim().to_string();
+            profile.api_base_url = url;
+            if url_changed && editor.id == self.local.active_profile {
+                self.api_health = None;
+                self.api_health_task = None;
+                self.api_health_last_checked = None;
+            }
+            self.save_local();
+        } else if delete {
+            let editor = self.profile_editor.take().unwrap_or_default();
+            self.delete_profile(&editor.id);
+        } else if cancel || !open {
+            self.profile_editor = None;
+        }
+    }
+}
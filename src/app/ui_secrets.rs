@@ -0,0 +1,115 @@
+use eframe::egui;
+
+use crate::storage::EncryptedFileStore;
+
+use super::{state::TwitchDeskApp, types::Screen};
+
+impl TwitchDeskApp {
+    /// Passphrase prompt for the encrypted secrets file (no OS keyring available).
+    pub(crate) fn ui_secrets_unlock_dialog(&mut self, ctx: &egui::Context) {
+        if self.local_state_recovery.is_some() {
+            return;
+        }
+        let Some(unlock) = self.secrets_unlock.as_mut() else {
+            return;
+        };
+
+        let mut submit = false;
+        let mut skip = false;
+        let mut reset = false;
+        let title = if unlock.existing {
+            "Unlock saved session"
+        } else {
+            "Protect saved session"
+        };
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                ui.label("No OS keyring is available, so your session token is kept in an encrypted file.");
+                if unlock.existing {
+                    ui.label("Enter your passphrase to unlock it.");
+                } else {
+                    ui.label("Choose a passphrase to encrypt it. You will be asked for it on every start.");
+                }
+                ui.add_space(8.0);
+
+                let resp = ui.add(
+                    egui::TextEdit::singleline(&mut unlock.passphrase)
+                        .hint_text("Passphrase")
+                        .password(true)
+                        .desired_width(f32::INFINITY),
+                );
+                if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    submit = true;
+                }
+                if !unlock.error.is_empty() {
+                    ui.label(
+                        egui::RichText::new(&unlock.error)
+                            .small()
+                            .color(ui.visuals().error_fg_color),
+                    );
+                }
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let label = if unlock.existing { "Unlock" } else { "Save passphrase" };
+                    if ui
+                        .add_enabled(!unlock.passphrase.is_empty(), egui::Button::new(label))
+                        .clicked()
+                    {
+                        submit = true;
+                    }
+                    if ui.button("Don't remember session").clicked() {
+                        skip = true;
+                    }
+                });
+                if unlock.existing {
+                    ui.add_space(4.0);
+                    if ui
+                        .small_button("Forgot passphrase (discard saved session)")
+                        .clicked()
+                    {
+                        reset = true;
+                    }
+                }
+            });
+
+        if reset {
+            match EncryptedFileStore::reset() {
+                Ok(()) => {
+                    unlock.existing = false;
+                    unlock.passphrase.clear();
+                    unlock.error.clear();
+                }
+                Err(e) => unlock.error = format!("{e:#}"),
+            }
+        } else if skip {
+            self.secrets_unlock = None;
+            self.status = "Session token is kept in memory only until you quit.".to_string();
+        } else if submit && !unlock.passphrase.is_empty() {
+            match EncryptedFileStore::open(&unlock.passphrase) {
+                Ok(store) => {
+                    self.secrets_unlock = None;
+                    self.secrets = Some(Box::new(store));
+                    self.secrets_unlocked();
+                }
+                Err(e) => {
+                    unlock.passphrase.clear();
+                    unlock.error = format!("{e:#}");
+                }
+            }
+        }
+    }
+
+    fn secrets_unlocked(&mut self) {
+        let had_token = self.has_access_token();
+        self.load_access_tokens();
+        if !had_token && self.has_access_token() && self.screen == Screen::Login {
+            self.load_user_config_from_api();
+            self.start_transition(Screen::Dashboard);
+        }
+    }
+}
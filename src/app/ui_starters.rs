@@ -0,0 +1,137 @@
+use eframe::egui;
+
+use super::{
+    jobs::JobKind,
+    state::TwitchDeskApp,
+    thumbnails::{ui_thumbnail, Thumbnail},
+};
+
+/// Width of the rendered starter preview in the window.
+const THUMBNAIL_WIDTH: f32 = 320.0;
+
+impl TwitchDeskApp {
+    /// "New from starter" window: pick a bundled or team starter and create a template from it.
+    pub(crate) fn ui_templates_starters_window(&mut self, ctx: &egui::Context) {
+        if !self.starters.open {
+            return;
+        }
+        let busy = self.is_busy(JobKind::Templates);
+        let mut open = true;
+        let mut select = None;
+        let mut reload = false;
+        let mut rerender = None;
+        let mut create = false;
+        egui::Window::new("New from starter")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([720.0, 460.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Team starters folder");
+                    if ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.local.starters_dir)
+                                .hint_text("Optional shared folder")
+                                .desired_width(320.0),
+                        )
+                        .changed()
+                    {
+                        self.schedule_local_save();
+                    }
+                    if ui.button("Reload").clicked() {
+                        reload = true;
+                    }
+                });
+                ui.label(
+                    egui::RichText::new(
+                        "Sub-folders with an index.html (and an optional starter.json with a name and description) \
+                         and .twitchdesk.zip bundles show up below the built-in starters.",
+                    )
+                    .small()
+                    .weak(),
+                );
+                for error in &self.starters.team_errors {
+                    ui.label(
+                        egui::RichText::new(error)
+                            .small()
+                            .color(ui.visuals().error_fg_color),
+                    );
+                }
+                ui.separator();
+
+                ui.horizontal_top(|ui| {
+                    ui.allocate_ui_with_layout(
+                        egui::vec2(220.0, ui.available_height()),
+                        egui::Layout::top_down(egui::Align::Min),
+                        |ui| {
+                            egui::ScrollArea::vertical().id_salt("starters_list").show(ui, |ui| {
+                                let mut team_heading = false;
+                                for starter in &self.starters.starters {
+                                    if starter.path.is_some() && !team_heading {
+                                        team_heading = true;
+                                        ui.add_space(4.0);
+                                        ui.label(egui::RichText::new("Team").strong());
+                                    }
+                                    let selected = self.starters.selected.as_deref() == Some(starter.id.as_str());
+                                    if ui.selectable_label(selected, &starter.name).clicked() {
+                                        select = Some(starter.id.clone());
+                                    }
+                                }
+                            });
+                        },
+                    );
+                    ui.separator();
+
+                    ui.vertical(|ui| {
+                        let Some(starter) = self.starters.selected().cloned() else {
+                            ui.label("No starter selected");
+                            return;
+                        };
+                        ui.heading(&starter.name);
+                        if !starter.description.is_empty() {
+                            ui.label(&starter.description);
+                        }
+                        if let Some(path) = &starter.path {
+                            ui.label(egui::RichText::new(path.display().to_string()).small().weak());
+                        }
+                        ui.add_space(6.0);
+
+                        let thumb = self.starters_thumbnail(ctx, &starter);
+                        ui_thumbnail(ui, &thumb, THUMBNAIL_WIDTH);
+                        if matches!(thumb, Thumbnail::Failed(_)) && ui.small_button("Try again").clicked() {
+                            rerender = Some(starter.clone());
+                        }
+
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Template name");
+                            ui.add(egui::TextEdit::singleline(&mut self.starters.name).desired_width(200.0));
+                            if ui.add_enabled(!busy, egui::Button::new("Create")).clicked() {
+                                create = true;
+                            }
+                            if busy {
+                                ui.spinner();
+                            }
+                        });
+                        self.ui_field_error(ui, JobKind::Templates, "name");
+                    });
+                });
+            });
+
+        if let Some(id) = select {
+            self.starters_select(&id);
+        }
+        if reload {
+            self.starters_reload();
+        }
+        if let Some(starter) = rerender {
+            self.starters_rerender_thumbnail(&starter);
+        }
+        if create {
+            self.starters_create();
+        }
+        if !open {
+            self.starters.open = false;
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use eframe::egui;
+
+use crate::models::TemplateListItem;
+use crate::templates::{compare_version_labels, TemplateSort};
+
+use super::{jobs::JobKind, state::TwitchDeskApp, thumbnails::ui_thumbnail, unsaved::GuardedAction};
+
+/// Thumbnail widths in the template list.
+const TEMPLATE_THUMBNAIL_WIDTH: f32 = 96.0;
+const VERSION_THUMBNAIL_WIDTH: f32 = 64.0;
+
+impl TwitchDeskApp {
+    /// "Your templates": one row per template with a preview of its published (or newest)
+    /// version; the open template lists its versions underneath.
+    pub(crate) fn ui_templates_list(&mut self, ui: &mut egui::Ui) {
+        self.ui_templates_list_search(ui);
+        ui.add_space(4.0);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if self.account.templates_list.is_empty() {
+                ui.label("No templates yet");
+                return;
+            }
+            let visible = self.templates_visible_list();
+            if visible.is_empty() {
+                ui.label("No templates match");
+                return;
+            }
+            for t in visible {
+                if self.templates_is_trashed(&t.id, None) {
+                    continue;
+                }
+                let selected = self
+                    .account
+                    .templates_selected_template_id
+                    .as_deref()
+                    .map(|id| id == t.id)
+                    .unwrap_or(false);
+                ui.horizontal(|ui| {
+                    let newest = t.versions.iter().max_by(|a, b| compare_version_labels(&a.version, &b.version));
+                    let shown = t.versions.iter().find(|v| v.is_published).or(newest);
+                    let thumb = shown.and_then(|v| self.version_thumbnail(ui.ctx(), &t.id, &t.name, v));
+                    if let Some(thumb) = thumb {
+                        ui_thumbnail(ui, &thumb, TEMPLATE_THUMBNAIL_WIDTH);
+                    }
+                    ui.vertical(|ui| {
+                        if ui.selectable_label(selected, t.name.clone()).clicked() {
+                            self.templates_guard(GuardedAction::SelectTemplate(t.id.clone()));
+                        }
+                        if !t.tags.is_empty() {
+                            ui.label(egui::RichText::new(t.tags.join(" · ")).small().weak());
+                        }
+                    });
+                });
+                if selected {
+                    self.ui_templates_list_versions(ui, &t);
+                }
+            }
+        });
+    }
+
+    /// Search box, sort, tag and "unpublished changes" filters above the list.
+    fn ui_templates_list_search(&mut self, ui: &mut egui::Ui) {
+        let busy = self.is_busy(JobKind::Templates);
+        let mut load_contents = false;
+        let search = &mut self.account.templates_search;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut search.query)
+                    .hint_text("Search templates")
+                    .desired_width(150.0),
+            );
+            let contents = ui
+                .checkbox(&mut search.in_contents, "Contents")
+                .on_hover_text("Also search the HTML, CSS and JS of loaded versions");
+            if contents.changed() && search.in_contents {
+                load_contents = true;
+            }
+        });
+        let tags = self.templates_all_tags();
+        let search = &mut self.account.templates_search;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("templates_sort")
+                .selected_text(search.sort.label())
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for sort in TemplateSort::ALL {
+                        ui.selectable_value(&mut search.sort, sort, sort.label());
+                    }
+                });
+            egui::ComboBox::from_id_salt("templates_tag_filter")
+                .selected_text(search.tag.as_deref().unwrap_or("All tags"))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut search.tag, None, "All tags");
+                    for tag in tags {
+                        ui.selectable_value(&mut search.tag, Some(tag.clone()), tag);
+                    }
+                });
+            ui.checkbox(&mut search.unpublished_only, "Unpublished")
+                .on_hover_text("Only templates whose latest version isn't the published one");
+        });
+
+        let (loaded, all) = self.account.templates_search.loaded_count(&self.account.templates_list);
+        let wants_contents = self.account.templates_search.in_contents || self.account.templates_search.unpublished_only;
+        if wants_contents && loaded < all {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("{loaded} of {all} versions loaded")).small().weak());
+                if ui.add_enabled(!busy, egui::Button::new("Load all").small()).clicked() {
+                    load_contents = true;
+                }
+            });
+        }
+        if self.account.templates_search.is_filtering() {
+            let shown = self.templates_visible_list().len();
+            ui.label(
+                egui::RichText::new(format!("{shown} of {} templates", self.account.templates_list.len()))
+                    .small()
+                    .weak(),
+            );
+        }
+        if load_contents {
+            self.templates_load_all_contents();
+        }
+    }
+
+    fn ui_templates_list_versions(&mut self, ui: &mut egui::Ui, template: &TemplateListItem) {
+        ui.indent(("templates_list_versions", &template.id), |ui| {
+            for v in self.account.templates_versions.clone() {
+                if self.templates_is_trashed(&template.id, Some(&v.version)) {
+                    continue;
+                }
+                ui.horizontal(|ui| {
+                    if let Some(thumb) = self.version_thumbnail(ui.ctx(), &template.id, &template.name, &v) {
+                        ui_thumbnail(ui, &thumb, VERSION_THUMBNAIL_WIDTH);
+                    }
+                    let current = self.account.templates_selected_version.as_deref() == Some(v.version.as_str());
+                    let tag = if v.is_published { " (published)" } else { "" };
+                    if ui
+                        .selectable_label(current, format!("{}{tag}", v.version))
+                        .clicked()
+                        && !current
+                    {
+                        self.templates_guard(GuardedAction::LoadVersion {
+                            template_id: template.id.clone(),
+                            version: v.version.clone(),
+                        });
+                    }
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,174 @@
+use eframe::egui;
+
+use super::{
+    state::TwitchDeskApp,
+    thumbnails::ui_thumbnail,
+    trash::{TemplateConfirm, TrashTarget, UNDO_WINDOW},
+};
+
+/// Width of each before/after preview in the publish confirmation.
+const SNAPSHOT_WIDTH: f32 = 240.0;
+
+impl TwitchDeskApp {
+    /// "Deleted … Undo" rows for deletes still in their undo window.
+    pub(crate) fn ui_templates_trash_bar(&mut self, ui: &mut egui::Ui) {
+        let items: Vec<(usize, String)> = self
+            .templates_trash_visible()
+            .map(|(i, item)| {
+                let secs = item.remaining().as_secs() + 1;
+                (i, format!("Deleted {} ({secs}s)", item.target.describe()))
+            })
+            .collect();
+        let mut undo = None;
+        for (i, what) in items {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(what).color(ui.visuals().warn_fg_color));
+                if ui.small_button("Undo").clicked() {
+                    undo = Some(i);
+                }
+            });
+        }
+        if let Some(i) = undo {
+            self.templates_trash_undo(i);
+        }
+    }
+
+    pub(crate) fn ui_templates_confirm_dialog(&mut self, ctx: &egui::Context) {
+        let Some(confirm) = self.account.templates_confirm.clone() else {
+            return;
+        };
+
+        let (title, message, button) = match &confirm {
+            TemplateConfirm::Delete(target @ TrashTarget::Template { .. }) => (
+                "Delete template",
+                format!("Delete {} and all its versions?", target.describe()),
+                "Delete",
+            ),
+            TemplateConfirm::Delete(target @ TrashTarget::Version { .. }) => {
+                ("Delete version", format!("Delete {}?", target.describe()), "Delete")
+            }
+            TemplateConfirm::Unpublish {
+                template_name, version, ..
+            } => (
+                "Unpublish version",
+                format!("Unpublish version {version} of {template_name}? Overlays using the published URL stop working until another version is published."),
+                "Unpublish",
+            ),
+            TemplateConfirm::Publish {
+                template_name,
+                version,
+                previous,
+                ..
+            } => (
+                "Publish version",
+                match previous {
+                    Some(p) if p == version => format!("Version {version} of {template_name} is already published. Publish it again?"),
+                    Some(p) => format!("Publish version {version} of {template_name}? It replaces version {p} on the published URL."),
+                    None => format!("Publish version {version} of {template_name}?"),
+                },
+                "Publish",
+            ),
+            TemplateConfirm::DeleteAsset { version, name, .. } => (
+                "Delete asset",
+                format!("Delete {name} from version {version}? Pages that load it will get a broken link. This can't be undone."),
+                "Delete",
+            ),
+        };
+        let loses_edits = match &confirm {
+            TemplateConfirm::Delete(TrashTarget::Template { template_id, .. }) => {
+                self.account.templates_selected_template_id.as_deref() == Some(template_id)
+            }
+            TemplateConfirm::Delete(TrashTarget::Version {
+                template_id, version, ..
+            }) => {
+                self.account.templates_selected_template_id.as_deref() == Some(template_id)
+                    && self.account.templates_selected_version.as_deref() == Some(version)
+            }
+            TemplateConfirm::Unpublish { .. }
+            | TemplateConfirm::DeleteAsset { .. }
+            | TemplateConfirm::Publish { .. } => false,
+        } && self.templates_is_dirty();
+
+        let mut ok = false;
+        let mut cancel = false;
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(message);
+                if matches!(confirm, TemplateConfirm::Delete(_)) {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "You can undo this for {} seconds.",
+                            UNDO_WINDOW.as_secs()
+                        ))
+                        .weak(),
+                    );
+                }
+                if let TemplateConfirm::Publish {
+                    template_id,
+                    template_name,
+                    version,
+                    previous,
+                } = &confirm
+                {
+                    self.ui_publish_snapshots(ui, template_id, template_name, version, previous.as_deref());
+                }
+                if loses_edits {
+                    ui.label(
+                        egui::RichText::new("Your unsaved edits to this version will be lost.")
+                            .color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ok = ui.button(button).clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if ok {
+            self.templates_confirmed();
+        } else if cancel {
+            self.account.templates_confirm = None;
+        }
+    }
+
+    /// "Before" (published now) and "After" (the version being published) previews.
+    fn ui_publish_snapshots(
+        &mut self,
+        ui: &mut egui::Ui,
+        template_id: &str,
+        template_name: &str,
+        version: &str,
+        previous: Option<&str>,
+    ) {
+        let summary = |v: &str| self.account.templates_versions.iter().find(|s| s.version == v).cloned();
+        let before = previous.and_then(summary);
+        let after = summary(version);
+        ui.add_space(8.0);
+        ui.horizontal_top(|ui| {
+            for (label, summary) in [("Before", before), ("After", after)] {
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new(label).strong());
+                    let thumb = summary
+                        .as_ref()
+                        .and_then(|s| self.version_thumbnail(ui.ctx(), template_id, template_name, s));
+                    match (&summary, thumb) {
+                        (None, _) => {
+                            ui.label(egui::RichText::new("Nothing published yet").weak());
+                        }
+                        (Some(_), None) => {
+                            ui.label(egui::RichText::new("No preview (username unknown)").weak());
+                        }
+                        (Some(s), Some(thumb)) => {
+                            ui_thumbnail(ui, &thumb, SNAPSHOT_WIDTH);
+                            ui.label(egui::RichText::new(format!("Version {}", s.version)).small().weak());
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
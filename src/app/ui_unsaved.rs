@@ -0,0 +1,47 @@
+use eframe::egui;
+
+use super::state::TwitchDeskApp;
+
+impl TwitchDeskApp {
+    pub(crate) fn ui_unsaved_changes_dialog(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.templates_unsaved_prompt.clone() else {
+            return;
+        };
+
+        let files = self
+            .templates_dirty_files()
+            .iter()
+            .map(|f| f.file_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let can_save = self.account.templates_selected_version.is_some();
+        let mut save = false;
+        let mut discard = false;
+        let mut cancel = false;
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Save your template changes {}?", action.describe()));
+                ui.label(egui::RichText::new(format!("Modified: {files}")).weak());
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    save = ui.add_enabled(can_save, egui::Button::new("Save")).clicked();
+                    discard = ui.button("Discard").clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+            });
+
+        if save {
+            self.templates_unsaved_prompt = None;
+            self.templates_save_current_version_then(Some(action));
+        } else if discard {
+            self.templates_unsaved_prompt = None;
+            self.templates_discard_edits();
+            self.run_guarded_action(action);
+        } else if cancel {
+            self.templates_unsaved_prompt = None;
+        }
+    }
+}
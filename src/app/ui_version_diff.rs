@@ -0,0 +1,198 @@
+use eframe::egui;
+
+use crate::templates::{DiffHunk, DiffLine, DiffSide, DiffTag, TemplateFile};
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+fn removed_bg() -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(0xFF, 0x4D, 0x6D, 40)
+}
+
+fn added_bg() -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(0x00, 0xF5, 0xD4, 32)
+}
+
+fn line_text(line: &DiffLine, prefix: bool) -> egui::RichText {
+    let (marker, bg) = match line.tag {
+        DiffTag::Equal => (' ', egui::Color32::TRANSPARENT),
+        DiffTag::Removed => ('-', removed_bg()),
+        DiffTag::Added => ('+', added_bg()),
+    };
+    let text = if prefix {
+        format!("{marker} {}", line.content())
+    } else {
+        line.content().to_string()
+    };
+    egui::RichText::new(text).monospace().background_color(bg)
+}
+
+fn line_number(n: Option<usize>) -> egui::RichText {
+    egui::RichText::new(n.map(|n| n.to_string()).unwrap_or_default())
+        .monospace()
+        .weak()
+}
+
+/// Pair removed/added runs of a hunk into side-by-side rows.
+fn side_by_side_rows(hunk: &DiffHunk) -> Vec<(Option<&DiffLine>, Option<&DiffLine>)> {
+    type Row<'a> = (Option<&'a DiffLine>, Option<&'a DiffLine>);
+    fn flush<'a>(rows: &mut Vec<Row<'a>>, removed: &mut Vec<&'a DiffLine>, added: &mut Vec<&'a DiffLine>) {
+        for i in 0..removed.len().max(added.len()) {
+            rows.push((removed.get(i).copied(), added.get(i).copied()));
+        }
+        removed.clear();
+        added.clear();
+    }
+
+    let mut rows = Vec::new();
+    let mut removed: Vec<&DiffLine> = Vec::new();
+    let mut added: Vec<&DiffLine> = Vec::new();
+    for line in &hunk.lines {
+        match line.tag {
+            DiffTag::Equal => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push((Some(line), Some(line)));
+            }
+            DiffTag::Removed => removed.push(line),
+            DiffTag::Added => added.push(line),
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+impl TwitchDeskApp {
+    /// "Compare versions" window: unified or side-by-side diff per file, with hunk copy.
+    pub(crate) fn ui_templates_diff_window(&mut self, ctx: &egui::Context) {
+        let versions: Vec<String> = self.account.templates_versions.iter().map(|v| v.version.clone()).collect();
+        let busy = self.jobs.is_busy(JobKind::Templates);
+        let Some(diff) = self.account.templates_diff.as_mut() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut reload = false;
+        let mut apply: Option<(usize, DiffSide)> = None;
+        egui::Window::new("Compare versions")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([900.0, 560.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (label, salt, value) in [
+                        ("Old", "templates_diff_old", &mut diff.old_version),
+                        ("New", "templates_diff_new", &mut diff.new_version),
+                    ] {
+                        ui.label(label);
+                        egui::ComboBox::from_id_salt(salt)
+                            .selected_text(value.clone())
+                            .show_ui(ui, |ui| {
+                                for v in &versions {
+                                    if ui.selectable_value(value, v.clone(), v).changed() {
+                                        reload = true;
+                                    }
+                                }
+                            });
+                    }
+                    if ui.button("Swap").clicked() {
+                        std::mem::swap(&mut diff.old_version, &mut diff.new_version);
+                        reload = true;
+                    }
+                    ui.separator();
+                    ui.selectable_value(&mut diff.side_by_side, true, "Side by side");
+                    ui.selectable_value(&mut diff.side_by_side, false, "Unified");
+                    if busy {
+                        ui.spinner();
+                    }
+                });
+
+                let changed = diff.changed_files();
+                ui.horizontal(|ui| {
+                    for file in TemplateFile::ALL {
+                        let mark = if changed.contains(&file) { " •" } else { "" };
+                        if ui
+                            .selectable_label(diff.file == file, format!("{}{mark}", file.file_name()))
+                            .clicked()
+                        {
+                            diff.set_file(file);
+                        }
+                    }
+                });
+                ui.separator();
+
+                if !diff.is_loaded() {
+                    ui.label("Loading…");
+                    return;
+                }
+                if diff.hunks().is_empty() {
+                    ui.label(format!("{} is identical in both versions.", diff.file.file_name()));
+                    return;
+                }
+
+                let (old_label, new_label) = (diff.old_version.clone(), diff.new_version.clone());
+                egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                    for (i, hunk) in diff.hunks().iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(hunk.header()).monospace().weak());
+                            if ui
+                                .small_button(format!("Use {old_label}"))
+                                .on_hover_text("Copy the old side of this hunk into the editor")
+                                .clicked()
+                            {
+                                apply = Some((i, DiffSide::Old));
+                            }
+                            if ui
+                                .small_button(format!("Use {new_label}"))
+                                .on_hover_text("Copy the new side of this hunk into the editor")
+                                .clicked()
+                            {
+                                apply = Some((i, DiffSide::New));
+                            }
+                        });
+
+                        let grid = egui::Grid::new(("templates_diff_hunk", i)).spacing([8.0, 0.0]);
+                        if diff.side_by_side {
+                            grid.num_columns(4).show(ui, |ui| {
+                                for (old, new) in side_by_side_rows(hunk) {
+                                    for (line, side) in [(old, DiffSide::Old), (new, DiffSide::New)] {
+                                        match line {
+                                            Some(l) => {
+                                                let n = match side {
+                                                    DiffSide::Old => l.old_line,
+                                                    DiffSide::New => l.new_line,
+                                                };
+                                                ui.label(line_number(n));
+                                                ui.label(line_text(l, false));
+                                            }
+                                            None => {
+                                                ui.label("");
+                                                ui.label("");
+                                            }
+                                        }
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        } else {
+                            grid.num_columns(3).show(ui, |ui| {
+                                for line in &hunk.lines {
+                                    ui.label(line_number(line.old_line));
+                                    ui.label(line_number(line.new_line));
+                                    ui.label(line_text(line, true));
+                                    ui.end_row();
+                                }
+                            });
+                        }
+                        ui.add_space(8.0);
+                    }
+                });
+            });
+
+        if !open {
+            self.account.templates_diff = None;
+        } else if reload {
+            self.templates_diff_load();
+        } else if let Some((i, side)) = apply {
+            self.templates_diff_apply_hunk(i, side);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use eframe::egui;
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+impl TwitchDeskApp {
+    /// Workspace folder controls in the template editor (mirror, watch, push/pull).
+    pub(crate) fn ui_templates_workspace(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Workspace folder")
+            .id_salt("templates_workspace")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Folder");
+                    let resp = ui.add(
+                        egui::TextEdit::singleline(&mut self.local.profile_mut().workspace_dir)
+                            .hint_text("Absolute folder path")
+                            .desired_width(280.0),
+                    );
+                    if resp.lost_focus() {
+                        self.save_local();
+                    }
+                    if ui.button("Mirror selected version").clicked() {
+                        self.workspace_mirror();
+                    }
+                    self.ui_job_spinner(ui, JobKind::Workspace);
+                });
+
+                let Some(link) = self.account.workspace.as_mut() else {
+                    ui.label(
+                        egui::RichText::new("Writes <folder>/<template>/<version>/{index.html,style.css,overlay.js} for editing in an external editor.")
+                            .small()
+                            .weak(),
+                    );
+                    return;
+                };
+
+                let path = link.dir.display().to_string();
+                ui.horizontal(|ui| {
+                    ui.label(format!("Linked: {} v{}", link.template_name, link.version));
+                    ui.monospace(&path);
+                    if ui.small_button("Copy path").clicked() {
+                        ui.output_mut(|o| o.copied_text = path.clone());
+                    }
+                });
+
+                let mut push = false;
+                let mut pull = false;
+                let mut force_pull = false;
+                let mut unlink = false;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut link.watch, "Watch and sync automatically");
+                    let busy = self.jobs.is_busy(JobKind::Workspace);
+                    push = ui.add_enabled(!busy, egui::Button::new("Push")).clicked();
+                    pull = ui.add_enabled(!busy, egui::Button::new("Pull")).clicked();
+                    force_pull = ui
+                        .add_enabled(!busy, egui::Button::new("Force pull"))
+                        .on_hover_text("Overwrite the folder with the server copy")
+                        .clicked();
+                    unlink = ui.button("Stop syncing").clicked();
+                });
+
+                if push {
+                    self.workspace_push();
+                } else if pull || force_pull {
+                    self.workspace_pull(force_pull);
+                } else if unlink {
+                    self.workspace_unlink();
+                }
+            });
+    }
+}
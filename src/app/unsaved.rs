@@ -0,0 +1,134 @@
+use eframe::egui;
+
+use crate::templates::{TemplateFile, TemplateSources};
+
+use super::{profiles::AccountState, state::TwitchDeskApp, types::View};
+
+/// Something that replaces or leaves the template editor buffers; asks first when
+/// they have unsaved edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GuardedAction {
+    SelectTemplate(String),
+    LoadVersion { template_id: String, version: String },
+    Publish,
+    CreateVersion,
+    DuplicateTemplate,
+    OpenView(View),
+    Logout,
+    CloseWindow,
+}
+
+impl GuardedAction {
+    /// Prompt title suffix, e.g. "before switching versions".
+    pub(crate) fn describe(&self) -> &'static str {
+        match self {
+            GuardedAction::SelectTemplate(_) => "before opening another template",
+            GuardedAction::LoadVersion { .. } => "before switching versions",
+            GuardedAction::Publish => "before publishing",
+            GuardedAction::CreateVersion => "before creating a new version",
+            GuardedAction::DuplicateTemplate => "before duplicating the template",
+            GuardedAction::OpenView(_) => "before leaving the editor",
+            GuardedAction::Logout => "before logging out",
+            GuardedAction::CloseWindow => "before quitting",
+        }
+    }
+}
+
+impl AccountState {
+    /// Files whose editor buffer differs from the last loaded or saved server copy.
+    fn templates_dirty_files(&self) -> Vec<TemplateFile> {
+        let Some(loaded) = &self.templates_loaded else {
+            return vec![];
+        };
+        let current = TemplateSources {
+            index_html: self.templates_index_html.clone(),
+            style_css: self.templates_style_css.clone(),
+            overlay_js: self.templates_overlay_js.clone(),
+        };
+        TemplateFile::ALL
+            .into_iter()
+            .filter(|f| current.get(*f) != loaded.get(*f))
+            .collect()
+    }
+}
+
+impl TwitchDeskApp {
+    /// Files whose editor buffer differs from the last loaded or saved server copy.
+    pub(crate) fn templates_dirty_files(&self) -> Vec<TemplateFile> {
+        self.account.templates_dirty_files()
+    }
+
+    pub(crate) fn templates_is_dirty(&self) -> bool {
+        !self.templates_dirty_files().is_empty()
+    }
+
+    /// Run `action` now, or ask Save / Discard / Cancel first if it would drop unsaved edits.
+    pub(crate) fn templates_guard(&mut self, action: GuardedAction) {
+        let keeps_editor = matches!(
+            action,
+            GuardedAction::OpenView(view) if view == View::Templates || self.account.active_view != View::Templates
+        );
+        if keeps_editor || !self.templates_is_dirty() {
+            self.run_guarded_action(action);
+        } else {
+            self.templates_unsaved_prompt = Some(action);
+        }
+    }
+
+    pub(crate) fn run_guarded_action(&mut self, action: GuardedAction) {
+        match action {
+            GuardedAction::SelectTemplate(id) => self.templates_select_template(&id),
+            GuardedAction::LoadVersion { template_id, version } => {
+                self.templates_load_version(&template_id, &version)
+            }
+            GuardedAction::Publish => self.templates_publish_current_version(),
+            GuardedAction::CreateVersion => self.templates_create_version_from_current(),
+            GuardedAction::DuplicateTemplate => self.templates_duplicate_template(),
+            GuardedAction::OpenView(view) => self.open_view(view),
+            GuardedAction::Logout => self.logout(),
+            GuardedAction::CloseWindow => {
+                // Edits of another profile may still be waiting.
+                if !self.templates_guard_close() {
+                    self.close_confirmed = true;
+                }
+            }
+        }
+    }
+
+    /// Reset the editor buffers to the last loaded server copy.
+    pub(crate) fn templates_discard_edits(&mut self) {
+        if let Some(loaded) = self.account.templates_loaded.clone() {
+            self.set_editor_sources(loaded);
+        }
+    }
+
+    /// Called every frame: hold a window close while the editor has unsaved edits, and
+    /// close for real once the user confirmed.
+    pub(crate) fn tick_close_guard(&mut self, ctx: &egui::Context) {
+        if self.close_confirmed {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            return;
+        }
+        if ctx.input(|i| i.viewport().close_requested()) && self.templates_guard_close() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        }
+    }
+
+    /// Ask about unsaved template edits before quitting: the active profile's first, then
+    /// each other profile's (switching to it). `false` if no profile has any.
+    fn templates_guard_close(&mut self) -> bool {
+        if !self.templates_is_dirty() {
+            let Some(id) = self
+                .profile_caches
+                .iter()
+                .find(|(_, cache)| !cache.account.templates_dirty_files().is_empty())
+                .map(|(id, _)| id.clone())
+            else {
+                return false;
+            };
+            self.switch_profile(&id);
+        }
+        self.templates_unsaved_prompt = Some(GuardedAction::CloseWindow);
+        true
+    }
+}
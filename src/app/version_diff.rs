@@ -0,0 +1,191 @@
+use tracing::warn;
+
+use crate::templates::{
+    apply_hunk, diff_hunks, diff_lines, DiffHunk, DiffSide, TemplateFile, TemplateSources,
+    DIFF_CONTEXT,
+};
+
+use super::{
+    jobs::JobKind,
+    state::{TemplatesEditorTab, TwitchDeskApp},
+};
+
+/// Comparison of two versions of the selected template.
+pub(crate) struct VersionDiff {
+    pub(crate) template_id: String,
+    pub(crate) old_version: String,
+    pub(crate) new_version: String,
+    pub(crate) file: TemplateFile,
+    pub(crate) side_by_side: bool,
+
+    /// Both sides, once fetched.
+    sources: Option<(TemplateSources, TemplateSources)>,
+    /// Hunks of `file`, recomputed when the file or the sources change.
+    hunks: Vec<DiffHunk>,
+}
+
+impl VersionDiff {
+    pub(crate) fn is_loaded(&self) -> bool {
+        self.sources.is_some()
+    }
+
+    pub(crate) fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    /// Files that differ between the two versions.
+    pub(crate) fn changed_files(&self) -> Vec<TemplateFile> {
+        let Some((old, new)) = &self.sources else {
+            return vec![];
+        };
+        TemplateFile::ALL
+            .into_iter()
+            .filter(|f| old.get(*f) != new.get(*f))
+            .collect()
+    }
+
+    pub(crate) fn set_file(&mut self, file: TemplateFile) {
+        self.file = file;
+        self.recompute();
+    }
+
+    fn recompute(&mut self) {
+        self.hunks = match &self.sources {
+            Some((old, new)) => diff_hunks(&diff_lines(old.get(self.file), new.get(self.file)), DIFF_CONTEXT),
+            None => vec![],
+        };
+    }
+}
+
+impl TemplatesEditorTab {
+    pub(crate) fn file(self) -> TemplateFile {
+        match self {
+            TemplatesEditorTab::Html => TemplateFile::Html,
+            TemplatesEditorTab::Css => TemplateFile::Css,
+            TemplatesEditorTab::Js => TemplateFile::Js,
+        }
+    }
+
+    pub(crate) fn for_file(file: TemplateFile) -> Self {
+        match file {
+            TemplateFile::Html => TemplatesEditorTab::Html,
+            TemplateFile::Css => TemplatesEditorTab::Css,
+            TemplateFile::Js => TemplatesEditorTab::Js,
+        }
+    }
+}
+
+impl TwitchDeskApp {
+    /// Open the diff window comparing the published version (or the previous one) with the selected version.
+    pub(crate) fn templates_open_diff(&mut self) {
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        let Some(new_version) = self.account.templates_selected_version.clone() else {
+            self.account.templates_status = "Select a version first".to_string();
+            return;
+        };
+        let old_version = self
+            .account
+            .templates_versions
+            .iter()
+            .find(|v| v.is_published && v.version != new_version)
+            .or_else(|| self.account.templates_versions.iter().find(|v| v.version != new_version))
+            .map(|v| v.version.clone())
+            .unwrap_or_else(|| new_version.clone());
+
+        self.account.templates_diff = Some(VersionDiff {
+            template_id,
+            old_version,
+            new_version,
+            file: self.account.templates_editor_tab.file(),
+            side_by_side: true,
+            sources: None,
+            hunks: vec![],
+        });
+        self.templates_diff_load();
+    }
+
+    /// Fetch both sides of the open diff.
+    pub(crate) fn templates_diff_load(&mut self) {
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+        let Some(diff) = self.account.templates_diff.as_mut() else {
+            return;
+        };
+        diff.sources = None;
+        diff.hunks.clear();
+
+        let (id, old_ver, new_ver) = (diff.template_id.clone(), diff.old_version.clone(), diff.new_version.clone());
+        let key = (id.clone(), old_ver.clone(), new_ver.clone());
+        self.spawn_job(
+            JobKind::Templates,
+            async move {
+                let old = api.template_version(&id, &old_ver).await?;
+                let new = api.template_version(&id, &new_ver).await?;
+                Ok((old, new))
+            },
+            move |app, result| {
+                // Ignore results for a comparison the user has since changed or closed.
+                let Some(diff) = app.account.templates_diff.as_mut().filter(|d| {
+                    (&d.template_id, &d.old_version, &d.new_version) == (&key.0, &key.1, &key.2)
+                }) else {
+                    return;
+                };
+                match result {
+                    Ok((old, new)) => {
+                        diff.sources = Some((TemplateSources::from(&old), TemplateSources::from(&new)));
+                        diff.recompute();
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "version diff load failed");
+                        app.account.templates_status = format!("Compare versions failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    /// Copy one side of a hunk into the editor buffer of the diffed file.
+    pub(crate) fn templates_diff_apply_hunk(&mut self, index: usize, take: DiffSide) {
+        let Some(diff) = &self.account.templates_diff else {
+            return;
+        };
+        if self.account.templates_selected_template_id.as_deref() != Some(&diff.template_id) {
+            self.account.templates_status = "The editor shows another template.".to_string();
+            return;
+        }
+        let Some(hunk) = diff.hunks.get(index).cloned() else {
+            return;
+        };
+        let file = diff.file;
+        let version = match take {
+            DiffSide::Old => diff.old_version.clone(),
+            DiffSide::New => diff.new_version.clone(),
+        };
+
+        let mut sources = self.editor_sources();
+        match apply_hunk(sources.get(file), &hunk, take) {
+            Ok(Some(updated)) => {
+                *sources.get_mut(file) = updated;
+                self.set_editor_sources(sources);
+                self.account.templates_editor_tab = TemplatesEditorTab::for_file(file);
+                self.account.templates_status = format!(
+                    "Copied {} from version {version} into {} (not saved yet).",
+                    hunk.header(),
+                    file.file_name()
+                );
+            }
+            Ok(None) => {
+                self.account.templates_status = format!("{} already matches version {version}.", file.file_name());
+            }
+            Err(e) => self.account.templates_status = format!("Copy hunk failed: {e:#}"),
+        }
+    }
+}
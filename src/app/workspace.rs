@@ -0,0 +1,372 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::api::ApiError;
+use crate::models::TemplateVersionResponse;
+use crate::templates::{
+    read_workspace_meta, read_workspace_version, workspace_stamps, workspace_version_dir,
+    workspace_version_exists, write_workspace_meta, write_workspace_version, FileStamps,
+    TemplateSources, WorkspaceMeta,
+};
+
+use super::{jobs::JobKind, state::TwitchDeskApp};
+
+/// How often linked files are checked for external edits.
+const LOCAL_POLL: Duration = Duration::from_secs(1);
+/// How often the server copy is checked for changes made elsewhere.
+const REMOTE_POLL: Duration = Duration::from_secs(15);
+
+/// A template version mirrored to a folder on disk.
+pub(crate) struct WorkspaceLink {
+    pub(crate) template_id: String,
+    pub(crate) template_name: String,
+    pub(crate) version: String,
+    pub(crate) dir: PathBuf,
+    /// Push local edits and pull remote changes automatically.
+    pub(crate) watch: bool,
+
+    /// Contents known to match both the folder and the server at the last sync.
+    synced: TemplateSources,
+    remote_updated_at: String,
+    stamps: FileStamps,
+    last_local_check: Instant,
+    last_remote_check: Instant,
+}
+
+impl WorkspaceLink {
+    fn is(&self, template_id: &str, version: &str, dir: &Path) -> bool {
+        self.template_id == template_id && self.version == version && self.dir == dir
+    }
+
+    fn meta(&self) -> WorkspaceMeta {
+        WorkspaceMeta {
+            template_id: self.template_id.clone(),
+            template_name: self.template_name.clone(),
+            version: self.version.clone(),
+            remote_updated_at: self.remote_updated_at.clone(),
+        }
+    }
+}
+
+impl TwitchDeskApp {
+    pub(crate) fn editor_sources(&self) -> TemplateSources {
+        TemplateSources {
+            index_html: self.account.templates_index_html.clone(),
+            style_css: self.account.templates_style_css.clone(),
+            overlay_js: self.account.templates_overlay_js.clone(),
+        }
+    }
+
+    pub(crate) fn set_editor_sources(&mut self, sources: TemplateSources) {
+        self.account.templates_index_html = sources.index_html;
+        self.account.templates_style_css = sources.style_css;
+        self.account.templates_overlay_js = sources.overlay_js;
+    }
+
+    /// Show new server contents in the editor if it displays the linked version
+    /// and has no in-app edits on top of the previous sync.
+    fn workspace_refresh_editor(&mut self, previous: &TemplateSources, current: &TemplateSources) {
+        let Some(link) = &self.account.workspace else {
+            return;
+        };
+        let same_version = self.account.templates_selected_template_id.as_deref() == Some(&link.template_id)
+            && self.account.templates_selected_version.as_deref() == Some(&link.version);
+        if same_version && self.editor_sources() == *previous {
+            self.account.templates_loaded_updated_at = link.remote_updated_at.clone();
+            self.set_editor_sources(current.clone());
+            self.account.templates_loaded = Some(current.clone());
+        }
+    }
+
+    /// Write the selected version (server copy) into `<workspace>/<template>/<version>` and link it.
+    pub(crate) fn workspace_mirror(&mut self) {
+        let root = self.local.profile().workspace_dir.trim().to_string();
+        if root.is_empty() {
+            self.account.templates_status = "Set a workspace folder first".to_string();
+            return;
+        }
+        let Some(template_id) = self.account.templates_selected_template_id.clone() else {
+            self.account.templates_status = "Select a template first".to_string();
+            return;
+        };
+        let Some(version) = self.account.templates_selected_version.clone() else {
+            self.account.templates_status = "Select a version first".to_string();
+            return;
+        };
+        let template_name = self
+            .account
+            .templates_selected_template_name
+            .clone()
+            .unwrap_or_else(|| template_id.clone());
+
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let dir = workspace_version_dir(Path::new(&root), &template_name, &version);
+        let id = template_id.clone();
+        let ver = version.clone();
+        self.spawn_job(
+            JobKind::Workspace,
+            async move { api.template_version(&id, &ver).await },
+            move |app, result| match result {
+                Ok(v) => app.workspace_link(template_id, template_name, dir, v),
+                Err(e) => {
+                    warn!(error = ?e, "workspace mirror failed");
+                    app.account.templates_status = format!("Mirror to workspace failed: {e:#}");
+                }
+            },
+        );
+    }
+
+    fn workspace_link(
+        &mut self,
+        template_id: String,
+        template_name: String,
+        dir: PathBuf,
+        remote: TemplateVersionResponse,
+    ) {
+        let server = TemplateSources::from(&remote);
+        let mut link = WorkspaceLink {
+            template_id,
+            template_name,
+            version: remote.version.clone(),
+            dir,
+            watch: true,
+            synced: server.clone(),
+            remote_updated_at: remote.updated_at.clone(),
+            stamps: Default::default(),
+            last_local_check: Instant::now(),
+            last_remote_check: Instant::now(),
+        };
+
+        // Re-linking a folder that already has files: only keep them if they were
+        // edited on top of the current server copy (those edits get pushed).
+        let local = workspace_version_exists(&link.dir)
+            .then(|| read_workspace_version(&link.dir).ok())
+            .flatten();
+        let status = match local {
+            Some(local) if local != server => {
+                let based_on_server = read_workspace_meta(&link.dir)
+                    .map(|m| m.remote_updated_at == remote.updated_at)
+                    .unwrap_or(false);
+                if !based_on_server {
+                    link.watch = false;
+                    format!(
+                        "{} has local changes and the server copy changed too. Push to upload the files or Pull to overwrite them.",
+                        link.dir.display()
+                    )
+                } else {
+                    format!("Linked {}; pushing local changes.", link.dir.display())
+                }
+            }
+            _ => match write_workspace_version(&link.dir, &server, &link.meta()) {
+                Ok(()) => format!("Mirrored version {} to {}.", link.version, link.dir.display()),
+                Err(e) => {
+                    self.account.templates_status = format!("Mirror to workspace failed: {e:#}");
+                    return;
+                }
+            },
+        };
+
+        // When watching, `stamps` stays empty so the next tick reads the files and pushes
+        // edits, if any.
+        if !link.watch {
+            link.stamps = workspace_stamps(&link.dir);
+        }
+        self.account.workspace = Some(link);
+        self.account.templates_status = status;
+    }
+
+    pub(crate) fn workspace_unlink(&mut self) {
+        if let Some(link) = self.account.workspace.take() {
+            self.account.templates_status = format!("Stopped syncing {}.", link.dir.display());
+        }
+    }
+
+    /// Upload the folder contents with `PUT /api/templates/{id}/versions/{version}`.
+    pub(crate) fn workspace_push(&mut self) {
+        let Some(link) = &self.account.workspace else {
+            return;
+        };
+        // Taken before reading, so edits made while the push runs are pushed next.
+        let stamps = workspace_stamps(&link.dir);
+        let local = match read_workspace_version(&link.dir) {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = format!("Read workspace failed: {e:#}");
+                return;
+            }
+        };
+        if local == link.synced {
+            if let Some(link) = self.account.workspace.as_mut() {
+                link.stamps = stamps;
+            }
+            self.account.templates_status = "Workspace is in sync.".to_string();
+            return;
+        }
+
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let (template_id, version, dir) = (link.template_id.clone(), link.version.clone(), link.dir.clone());
+        let req = local.to_update_request();
+        let base = link.remote_updated_at.clone();
+        let (id, ver) = (template_id.clone(), version.clone());
+        self.spawn_job(
+            JobKind::Workspace,
+            async move { api.template_version_update(&id, &ver, &req, Some(&base)).await },
+            move |app, result| {
+                let Some(link) = app.account.workspace.as_mut().filter(|l| l.is(&template_id, &version, &dir)) else {
+                    return;
+                };
+                match result {
+                    Ok(v) => {
+                        let previous = std::mem::replace(&mut link.synced, local.clone());
+                        link.remote_updated_at = v.updated_at;
+                        link.stamps = stamps;
+                        let meta = link.meta();
+                        if let Err(e) = write_workspace_meta(&link.dir, &meta) {
+                            warn!(error = ?e, "write workspace meta failed");
+                        }
+                        app.workspace_refresh_editor(&previous, &local);
+                        app.account.templates_status = format!("Pushed workspace changes to version {}.", v.version);
+                    }
+                    Err(ApiError::Conflict(msg)) => {
+                        link.watch = false;
+                        app.account.templates_status = format!("Push rejected, the version changed on the server: {msg}. Pull or push again.");
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "workspace push failed");
+                        app.account.templates_status = format!("Push failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    /// Download the server copy into the folder. Without `force`, local edits that were
+    /// not pushed yet are kept and the pull is refused.
+    pub(crate) fn workspace_pull(&mut self, force: bool) {
+        self.workspace_fetch_remote(force, false);
+    }
+
+    fn workspace_fetch_remote(&mut self, force: bool, quiet: bool) {
+        let Some(link) = &self.account.workspace else {
+            return;
+        };
+        let api = match self.api() {
+            Ok(v) => v,
+            Err(e) => {
+                self.account.templates_status = e.to_string();
+                return;
+            }
+        };
+
+        let (template_id, version, dir) = (link.template_id.clone(), link.version.clone(), link.dir.clone());
+        let (id, ver) = (template_id.clone(), version.clone());
+        self.spawn_job(
+            JobKind::Workspace,
+            async move { api.template_version(&id, &ver).await },
+            move |app, result| match result {
+                Ok(v) => app.workspace_apply_remote(&template_id, &version, &dir, v, force, quiet),
+                Err(e) => {
+                    warn!(error = ?e, "workspace pull failed");
+                    if !quiet {
+                        app.account.templates_status = format!("Pull failed: {e:#}");
+                    }
+                }
+            },
+        );
+    }
+
+    fn workspace_apply_remote(
+        &mut self,
+        template_id: &str,
+        version: &str,
+        dir: &Path,
+        remote: TemplateVersionResponse,
+        force: bool,
+        quiet: bool,
+    ) {
+        let Some(link) = self.account.workspace.as_mut().filter(|l| l.is(template_id, version, dir)) else {
+            return;
+        };
+        if !force && remote.updated_at == link.remote_updated_at {
+            if !quiet {
+                self.account.templates_status = "Workspace is up to date with the server.".to_string();
+            }
+            return;
+        }
+
+        let server = TemplateSources::from(&remote);
+        let local = read_workspace_version(&link.dir).ok();
+        if !force && local.as_ref().is_some_and(|l| *l != link.synced && *l != server) {
+            link.watch = false;
+            self.account.templates_status =
+                "Both the workspace files and the server copy changed. Push to keep the files or force Pull to take the server copy."
+                    .to_string();
+            return;
+        }
+
+        link.remote_updated_at = remote.updated_at.clone();
+        let previous = std::mem::replace(&mut link.synced, server.clone());
+        if let Err(e) = write_workspace_version(&link.dir, &server, &link.meta()) {
+            self.account.templates_status = format!("Write workspace failed: {e:#}");
+            return;
+        }
+        link.stamps = workspace_stamps(&link.dir);
+        if force {
+            link.watch = true;
+        }
+        self.workspace_refresh_editor(&previous, &server);
+        self.account.templates_status = format!("Pulled version {} into the workspace.", remote.version);
+    }
+
+    /// Called every frame: push external edits and pick up remote changes while watching.
+    pub(crate) fn tick_workspace(&mut self, ctx: &egui::Context) {
+        let Some(link) = self.account.workspace.as_mut() else {
+            return;
+        };
+        if !link.watch {
+            return;
+        }
+        ctx.request_repaint_after(LOCAL_POLL);
+        if self.jobs.is_busy(JobKind::Workspace) {
+            return;
+        }
+
+        if link.last_local_check.elapsed() >= LOCAL_POLL {
+            link.last_local_check = Instant::now();
+            // `stamps` only move on once the files are pushed (or match the last sync), so a
+            // failed push is tried again on the next check.
+            let stamps = workspace_stamps(&link.dir);
+            if stamps != link.stamps {
+                let changed = read_workspace_version(&link.dir)
+                    .map(|local| local != link.synced)
+                    .unwrap_or(false);
+                if changed {
+                    self.workspace_push();
+                    return;
+                }
+                link.stamps = stamps;
+            }
+        }
+
+        if link.last_remote_check.elapsed() >= REMOTE_POLL {
+            link.last_remote_check = Instant::now();
+            self.workspace_fetch_remote(false, true);
+        }
+    }
+}
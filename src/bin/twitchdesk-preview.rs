@@ -1,22 +1,52 @@
 #![cfg_attr(all(windows, not(debug_assertions)), windows_subsystem = "windows")]
 
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
 use tao::{
+    dpi::{LogicalPosition, LogicalSize},
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event_loop::{ControlFlow, EventLoopBuilder, EventLoopProxy},
     window::WindowBuilder,
 };
 
 #[cfg(target_os = "linux")]
-use tao::platform::unix::WindowExtUnix;
+use tao::platform::unix::{WindowBuilderExtUnix, WindowExtUnix};
+#[cfg(target_os = "windows")]
+use tao::platform::windows::WindowBuilderExtWindows;
 
 use wry::WebViewBuilder;
 
 #[cfg(target_os = "linux")]
 use wry::WebViewBuilderExtUnix;
 
+/// DOM event the page receives for each mock event (the event JSON is its `detail`).
+const MOCK_EVENT_NAME: &str = "twitchdesk:event";
+
+/// Page size and settle time for `--screenshot` unless `--size` / `--delay-ms` say otherwise.
+const SCREENSHOT_SIZE: (u32, u32) = (640, 360);
+const SCREENSHOT_DELAY_MS: u64 = 1500;
+/// Largest `--size` accepted in either direction.
+const MAX_SCREENSHOT_SIDE: u32 = 4096;
+/// Give up if the webview never delivers the snapshot.
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(15);
+
+enum UserEvent {
+    /// One mock event (JSON object) from the desktop app.
+    MockEvent(serde_json::Value),
+    /// The native snapshot was written to the `--screenshot` file (or failed).
+    Snapshot(Result<(), String>),
+}
+
 fn main() {
     let mut url: Option<String> = None;
     let mut auto_close_ms: Option<u64> = None;
+    let mut events_addr: Option<String> = None;
+    let mut screenshot: Option<PathBuf> = None;
+    let mut size = SCREENSHOT_SIZE;
+    let mut delay_ms = SCREENSHOT_DELAY_MS;
 
     let mut args = std::env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -31,6 +61,38 @@ fn main() {
                     std::process::exit(2);
                 }));
             }
+            "--size" => {
+                let v = args.next().unwrap_or_else(|| {
+                    eprintln!("Missing value for --size");
+                    std::process::exit(2);
+                });
+                size = parse_size(&v).unwrap_or_else(|| {
+                    eprintln!("Invalid --size value: {v} (expected WIDTHxHEIGHT, e.g. 1280x720)");
+                    std::process::exit(2);
+                });
+            }
+            "--delay-ms" => {
+                let v = args.next().unwrap_or_else(|| {
+                    eprintln!("Missing value for --delay-ms");
+                    std::process::exit(2);
+                });
+                delay_ms = v.parse::<u64>().unwrap_or_else(|_| {
+                    eprintln!("Invalid --delay-ms value: {v}");
+                    std::process::exit(2);
+                });
+            }
+            "--screenshot" => {
+                screenshot = Some(PathBuf::from(args.next().unwrap_or_else(|| {
+                    eprintln!("Missing value for --screenshot");
+                    std::process::exit(2);
+                })));
+            }
+            "--events" => {
+                events_addr = Some(args.next().unwrap_or_else(|| {
+                    eprintln!("Missing value for --events");
+                    std::process::exit(2);
+                }));
+            }
             _ => {
                 if url.is_none() {
                     url = Some(arg);
@@ -43,48 +105,284 @@ fn main() {
     }
 
     let url = url.unwrap_or_else(|| {
-        eprintln!("Usage: twitchdesk-preview <url> [--auto-close-ms <ms>]");
+        eprintln!(
+            "Usage: twitchdesk-preview <url> [--auto-close-ms <ms>] [--events <addr>] [--screenshot <out.png> [--size WxH] [--delay-ms <ms>]]"
+        );
         std::process::exit(2);
     });
 
-    let event_loop = EventLoop::new();
-    let window = WindowBuilder::new()
-        .with_title("TwitchDesk Preview")
-        .build(&event_loop)
-        .expect("create window");
+    let event_loop = EventLoopBuilder::<UserEvent>::with_user_event().build();
+    if let Some(addr) = events_addr {
+        let proxy = event_loop.create_proxy();
+        std::thread::spawn(move || receive_mock_events(&addr, proxy));
+    }
 
+    let mut window = WindowBuilder::new().with_title("TwitchDesk Preview");
+    if screenshot.is_some() {
+        // Hidden windows may not be painted (and can't be snapshotted), so the window is
+        // shown, but out of the way: undecorated, unfocused, behind everything and moved
+        // off screen where the window system allows it.
+        let (w, h) = size;
+        window = window
+            .with_inner_size(LogicalSize::new(w as f64, h as f64))
+            .with_position(LogicalPosition::new(-10_000.0, -10_000.0))
+            .with_decorations(false)
+            .with_focused(false)
+            .with_always_on_bottom(true);
+        #[cfg(any(target_os = "linux", target_os = "windows"))]
+        {
+            window = window.with_skip_taskbar(true);
+        }
+    }
+    let window = window.build(&event_loop).expect("create window");
+
+    let snapshot_proxy = event_loop.create_proxy();
     let builder = WebViewBuilder::new().with_url(&url);
 
     #[cfg(not(target_os = "linux"))]
-    let _webview = builder.build(&window).expect("build webview");
+    let webview = builder.build(&window).expect("build webview");
 
     // On Linux, using GTK build supports Wayland too.
     #[cfg(target_os = "linux")]
-    let _webview = builder
+    let webview = builder
         .build_gtk(window.gtk_window())
         .expect("build gtk webview");
 
-    let start = std::time::Instant::now();
+    let start = Instant::now();
+    let mut capture_sent = false;
 
     event_loop.run(move |event, _, control_flow| {
-        if let Some(ms) = auto_close_ms {
-            let deadline = start + std::time::Duration::from_millis(ms);
-            *control_flow = ControlFlow::WaitUntil(deadline);
-        } else {
-            *control_flow = ControlFlow::Wait;
-        }
+        let mut deadline = auto_close_ms.map(|ms| start + Duration::from_millis(ms));
 
         if let Some(ms) = auto_close_ms {
-            if start.elapsed() >= std::time::Duration::from_millis(ms) {
+            if start.elapsed() >= Duration::from_millis(ms) {
                 *control_flow = ControlFlow::Exit;
                 return;
             }
         }
 
-        if let Event::WindowEvent { event, .. } = event {
-            if matches!(event, WindowEvent::CloseRequested) {
-                *control_flow = ControlFlow::Exit;
+        if screenshot.is_some() {
+            let capture_at = start + Duration::from_millis(delay_ms);
+            let give_up_at = capture_at + SCREENSHOT_TIMEOUT;
+            let now = Instant::now();
+            if now >= give_up_at {
+                eprintln!("Screenshot timed out");
+                *control_flow = ControlFlow::ExitWithCode(1);
+                return;
+            }
+            if !capture_sent && now >= capture_at {
+                capture_sent = true;
+                if let Some(out) = &screenshot {
+                    snapshot::take(&webview, out.clone(), snapshot_proxy.clone());
+                }
+            }
+            deadline = Some(if capture_sent { give_up_at } else { capture_at });
+        }
+
+        *control_flow = match deadline {
+            Some(at) => ControlFlow::WaitUntil(at),
+            None => ControlFlow::Wait,
+        };
+
+        match event {
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => *control_flow = ControlFlow::Exit,
+            Event::UserEvent(UserEvent::MockEvent(detail)) => {
+                let script = format!(
+                    "window.dispatchEvent(new CustomEvent({}, {{ detail: {detail} }}));",
+                    serde_json::Value::from(MOCK_EVENT_NAME)
+                );
+                if let Err(e) = webview.evaluate_script(&script) {
+                    eprintln!("Mock event failed: {e}");
+                }
+            }
+            Event::UserEvent(UserEvent::Snapshot(result)) => {
+                *control_flow = match result {
+                    Ok(()) => ControlFlow::Exit,
+                    Err(e) => {
+                        eprintln!("Screenshot failed: {e}");
+                        ControlFlow::ExitWithCode(1)
+                    }
+                };
             }
+            _ => {}
         }
     });
 }
+
+/// `1280x720` → `(1280, 720)`.
+fn parse_size(value: &str) -> Option<(u32, u32)> {
+    let (w, h) = value.trim().split_once(['x', 'X'])?;
+    let (w, h) = (w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?);
+    let valid = |side: u32| (1..=MAX_SCREENSHOT_SIDE).contains(&side);
+    (valid(w) && valid(h)).then_some((w, h))
+}
+
+/// Forward mock events sent by the desktop app (one JSON object per line) to the event loop.
+fn receive_mock_events(addr: &str, proxy: EventLoopProxy<UserEvent>) {
+    let stream = match TcpStream::connect(addr) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Mock events unavailable ({addr}): {e}");
+            return;
+        }
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(detail) if detail.is_object() => {
+                if proxy.send_event(UserEvent::MockEvent(detail)).is_err() {
+                    return;
+                }
+            }
+            _ => eprintln!("Ignoring malformed mock event"),
+        }
+    }
+}
+
+/// Native webview snapshots. The image comes from the platform webview (what the window
+/// shows, media and web fonts included), never from the page, so page scripts can't
+/// fake or block it. Each `take` writes a PNG to `out` and reports through `proxy`.
+#[cfg(target_os = "linux")]
+mod snapshot {
+    use std::path::PathBuf;
+
+    use tao::event_loop::EventLoopProxy;
+    use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    use super::UserEvent;
+
+    /// WebKitGTK `webkit_web_view_get_snapshot`.
+    pub(super) fn take(webview: &wry::WebView, out: PathBuf, proxy: EventLoopProxy<UserEvent>) {
+        webview.webview().snapshot(
+            SnapshotRegion::Visible,
+            SnapshotOptions::NONE,
+            None::<&webkit2gtk::gio::Cancellable>,
+            move |result| {
+                let saved = result.map_err(|e| e.to_string()).and_then(|surface| {
+                    let image = cairo::ImageSurface::try_from(surface)
+                        .map_err(|_| "snapshot is not an image surface".to_string())?;
+                    let mut file = std::fs::File::create(&out)
+                        .map_err(|e| format!("write {}: {e}", out.display()))?;
+                    image
+                        .write_to_png(&mut file)
+                        .map_err(|e| format!("write {}: {e}", out.display()))
+                });
+                let _ = proxy.send_event(UserEvent::Snapshot(saved));
+            },
+        );
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod snapshot {
+    use std::path::{Path, PathBuf};
+
+    use block2::RcBlock;
+    use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+    use objc2_foundation::{NSDictionary, NSError};
+    use tao::event_loop::EventLoopProxy;
+    use wry::WebViewExtMacOS;
+
+    use super::UserEvent;
+
+    /// WKWebView `takeSnapshotWithConfiguration:completionHandler:` (visible bounds).
+    pub(super) fn take(webview: &wry::WebView, out: PathBuf, proxy: EventLoopProxy<UserEvent>) {
+        let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
+            // SAFETY: WebKit passes either a valid image or a valid error (or null).
+            let saved = match unsafe { (image.as_ref(), error.as_ref()) } {
+                (Some(image), _) => write_png(image, &out),
+                (None, Some(error)) => Err(error.localizedDescription().to_string()),
+                (None, None) => Err("webview returned no image".to_string()),
+            };
+            let _ = proxy.send_event(UserEvent::Snapshot(saved));
+        });
+        // SAFETY: called on the main thread, which owns the webview.
+        unsafe {
+            webview
+                .webview()
+                .takeSnapshotWithConfiguration_completionHandler(None, &handler);
+        }
+    }
+
+    fn write_png(image: &NSImage, out: &Path) -> Result<(), String> {
+        let tiff = image
+            .TIFFRepresentation()
+            .ok_or_else(|| "snapshot has no bitmap".to_string())?;
+        let bitmap = NSBitmapImageRep::imageRepWithData(&tiff)
+            .ok_or_else(|| "snapshot has no bitmap".to_string())?;
+        // SAFETY: an empty property dictionary is valid for every file type.
+        let png = unsafe {
+            bitmap.representationUsingType_properties(
+                NSBitmapImageFileType::PNG,
+                &NSDictionary::new(),
+            )
+        }
+        .ok_or_else(|| "encode PNG failed".to_string())?;
+        std::fs::write(out, png.to_vec()).map_err(|e| format!("write {}: {e}", out.display()))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod snapshot {
+    use std::path::PathBuf;
+
+    use tao::event_loop::EventLoopProxy;
+    use webview2_com::{
+        CapturePreviewCompletedHandler,
+        Microsoft::Web::WebView2::Win32::COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+    };
+    use windows::core::HSTRING;
+    use windows::Win32::{
+        Storage::FileSystem::FILE_ATTRIBUTE_NORMAL,
+        System::Com::{IStream, STGM_CREATE, STGM_WRITE},
+        UI::Shell::SHCreateStreamOnFileEx,
+    };
+    use wry::WebViewExtWindows;
+
+    use super::UserEvent;
+
+    /// WebView2 `ICoreWebView2::CapturePreview`, streamed straight into `out`.
+    pub(super) fn take(webview: &wry::WebView, out: PathBuf, proxy: EventLoopProxy<UserEvent>) {
+        let fail = |proxy: &EventLoopProxy<UserEvent>, e: String| {
+            let _ = proxy.send_event(UserEvent::Snapshot(Err(e)));
+        };
+        // SAFETY: COM calls on the UI thread that created the webview.
+        let started = unsafe {
+            let core = match webview.controller().CoreWebView2() {
+                Ok(core) => core,
+                Err(e) => return fail(&proxy, e.message()),
+            };
+            let stream = match SHCreateStreamOnFileEx(
+                &HSTRING::from(out.as_os_str()),
+                (STGM_CREATE | STGM_WRITE).0,
+                FILE_ATTRIBUTE_NORMAL.0,
+                true,
+                None::<&IStream>,
+            ) {
+                Ok(stream) => stream,
+                Err(e) => return fail(&proxy, format!("write {}: {}", out.display(), e.message())),
+            };
+            let done = proxy.clone();
+            let handler_stream = stream.clone();
+            core.CapturePreview(
+                COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+                &stream,
+                &CapturePreviewCompletedHandler::create(Box::new(move |result| {
+                    // Releasing the last reference closes the file.
+                    drop(handler_stream);
+                    let _ = done.send_event(UserEvent::Snapshot(result.map_err(|e| e.message())));
+                    Ok(())
+                })),
+            )
+        };
+        if let Err(e) = started {
+            fail(&proxy, e.message());
+        }
+    }
+}
@@ -1,10 +1,14 @@
 #![cfg_attr(all(windows, not(debug_assertions)), windows_subsystem = "windows")]
 
+mod api;
 mod app;
 mod models;
 mod storage;
+mod templates;
 mod update;
 mod preview;
+mod preview_server;
+mod mock_events;
 mod loading;
 
 fn main() -> anyhow::Result<()> {
@@ -0,0 +1,156 @@
+//! Local channel that delivers synthetic overlay events to preview windows. Windows
+//! opened with `--events <addr>` connect to it, and every event is written to each of
+//! them as one line of JSON.
+
+use std::net::SocketAddr;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use crate::models::{MockEvent, MockSequenceStep};
+
+/// Preview helper argument that names the address to connect to.
+const EVENTS_ARG: &str = "--events";
+/// Events buffered per window before slow ones start missing events.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Sends events to every connected preview window. Cheap to clone.
+#[derive(Clone)]
+pub(crate) struct MockEventSender {
+    tx: broadcast::Sender<Arc<str>>,
+}
+
+impl MockEventSender {
+    /// Returns how many windows the event went to.
+    pub(crate) fn send(&self, event: &MockEvent) -> Result<usize> {
+        let line: Arc<str> = serde_json::to_string(event).context("encode event")?.into();
+        Ok(self.tx.send(line).unwrap_or(0))
+    }
+}
+
+pub(crate) struct MockEventHub {
+    addr: SocketAddr,
+    sender: MockEventSender,
+    clients: Arc<AtomicUsize>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockEventHub {
+    /// Bind to a random port on 127.0.0.1 and start accepting windows on `rt`.
+    pub(crate) fn start(rt: &tokio::runtime::Runtime) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).context("bind mock event channel")?;
+        listener
+            .set_nonblocking(true)
+            .context("configure mock event channel")?;
+        let addr = listener.local_addr().context("mock event channel address")?;
+
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let sender = MockEventSender { tx: tx.clone() };
+        let clients = Arc::new(AtomicUsize::new(0));
+        let counter = clients.clone();
+        let task = rt.spawn(async move {
+            let listener = match TcpListener::from_std(listener) {
+                Ok(l) => l,
+                Err(e) => {
+                    warn!(error = ?e, "mock event listener failed");
+                    return;
+                }
+            };
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let rx = tx.subscribe();
+                        let counter = counter.clone();
+                        counter.fetch_add(1, Ordering::Relaxed);
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_window(stream, rx).await {
+                                debug!(error = ?e, "preview window disconnected");
+                            }
+                            counter.fetch_sub(1, Ordering::Relaxed);
+                        });
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "mock event accept failed");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            sender,
+            clients,
+            task,
+        })
+    }
+
+    /// Arguments for the preview helper so the window connects to this hub.
+    pub(crate) fn preview_args(&self) -> Vec<String> {
+        vec![EVENTS_ARG.to_string(), self.addr.to_string()]
+    }
+
+    /// Preview windows currently connected.
+    pub(crate) fn connected(&self) -> usize {
+        self.clients.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn sender(&self) -> MockEventSender {
+        self.sender.clone()
+    }
+}
+
+impl Drop for MockEventHub {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_window(stream: TcpStream, mut rx: broadcast::Receiver<Arc<str>>) -> std::io::Result<()> {
+    let (mut read, mut write) = stream.into_split();
+    let mut buf = [0u8; 256];
+    loop {
+        tokio::select! {
+            line = rx.recv() => match line {
+                Ok(line) => {
+                    write.write_all(line.as_bytes()).await?;
+                    write.write_all(b"\n").await?;
+                }
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    debug!(skipped = n, "preview window fell behind on mock events");
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            // The window never writes; a read returning means it went away.
+            n = read.read(&mut buf) => {
+                if n? == 0 {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Send `steps` in order, waiting each step's delay first. `progress` counts the
+/// events sent so far.
+pub(crate) async fn replay_sequence(
+    sender: MockEventSender,
+    steps: Vec<MockSequenceStep>,
+    progress: Arc<AtomicUsize>,
+) {
+    for step in steps {
+        tokio::time::sleep(Duration::from_millis(step.delay_ms)).await;
+        if let Err(e) = sender.send(&step.event) {
+            warn!(error = ?e, "replay mock event failed");
+        }
+        progress.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -100,6 +100,16 @@ pub struct TemplateCreateRequest {
     pub name: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateRenameRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTagsRequest {
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateDuplicateRequest {
     pub new_name: String,
@@ -129,6 +139,12 @@ pub struct TemplateVersionSummary {
     pub version: String,
     pub is_published: bool,
     pub updated_at: String,
+    /// When the version was last published, if it ever was.
+    #[serde(default)]
+    pub published_at: Option<String>,
+    /// Username that last published it.
+    #[serde(default)]
+    pub published_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +153,9 @@ pub struct TemplateListItem {
     pub name: String,
     pub updated_at: String,
     pub versions: Vec<TemplateVersionSummary>,
+    /// User-defined labels for finding the template again.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +169,9 @@ pub struct TemplateDetailResponse {
     pub name: String,
     pub updated_at: String,
     pub versions: Vec<TemplateVersionSummary>,
+    /// User-defined labels for finding the template again.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -163,6 +185,22 @@ pub struct TemplateVersionResponse {
     pub overlay_js: String,
 }
 
+/// A binary file (image, font, sound) stored with a template version. Pages load it
+/// from `assets/<name>` relative to the overlay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateAsset {
+    pub name: String,
+    pub size: u64,
+    pub mime_type: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateAssetsResponse {
+    pub assets: Vec<TemplateAsset>,
+}
+
 // -------------------------------
 // AI Alerts
 // -------------------------------
@@ -1,33 +1,319 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use super::UserConfig;
 
+/// A named API connection (backend + account). The bearer token itself lives in the
+/// secret store under `token_ref`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LocalClientState {
+pub struct ApiProfile {
+    pub id: String,
+    pub name: String,
     pub api_base_url: String,
     pub username: Option<String>,
+    /// Secret-store key for this profile's bearer token.
+    pub token_ref: String,
+    /// Root folder for template workspaces (`<dir>/<template>/<version>/`).
+    #[serde(default)]
+    pub workspace_dir: String,
+    /// Template parameter values chosen in the editor, by template id then parameter name.
+    #[serde(default)]
+    pub template_params: BTreeMap<String, BTreeMap<String, String>>,
+
+    /// Lives in the secret store; only read here to migrate old plaintext state files.
+    #[serde(default, skip_serializing)]
     pub access_token: Option<String>,
+}
+
+impl ApiProfile {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, api_base_url: impl Into<String>) -> Self {
+        let id = id.into();
+        Self {
+            token_ref: format!("access-token:{id}"),
+            id,
+            name: name.into(),
+            api_base_url: api_base_url.into(),
+            username: None,
+            workspace_dir: String::new(),
+            template_params: BTreeMap::new(),
+            access_token: None,
+        }
+    }
+
+    /// The profile every install starts with. Uses the token key of single-account builds
+    /// so an already stored session keeps working.
+    fn initial(api_base_url: String) -> Self {
+        Self {
+            token_ref: "access-token".to_string(),
+            ..Self::new("default", "Default", api_base_url)
+        }
+    }
+
+    pub fn has_access_token(&self) -> bool {
+        self.access_token
+            .as_ref()
+            .map(|t| !t.is_empty())
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalClientState {
+    /// On-disk format version; see `storage::LOCAL_STATE_SCHEMA_VERSION`.
+    #[serde(default)]
+    pub schema_version: u32,
+
+    #[serde(default)]
+    pub profiles: Vec<ApiProfile>,
+    #[serde(default)]
+    pub active_profile: String,
+
+    /// Per-attempt API request timeout.
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Retries for idempotent API requests (GET/PUT/DELETE) on transient failures.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Saved events of the mock event injector.
+    #[serde(default)]
+    pub mock_event_presets: Vec<MockEventPreset>,
+    /// Events the mock event injector replays in order.
+    #[serde(default)]
+    pub mock_event_sequence: Vec<MockSequenceStep>,
+    /// Shared folder with team starter templates for "New from starter".
+    #[serde(default)]
+    pub starters_dir: String,
 
     // Keep config in memory only; it is stored server-side via the API.
+    // Belongs to the active profile.
     #[serde(skip)]
     pub user_cfg: UserConfig,
 }
 
 impl Default for LocalClientState {
     fn default() -> Self {
-        // NOTE: This is not a true secret (clients must know where to connect),
-        // but keeping it out of the repo avoids publishing infrastructure details.
-        let api_base_url = std::env::var("TWITCHDESK_API_BASE_URL")
-            .ok()
-            .filter(|v| !v.trim().is_empty())
-            .or_else(|| option_env!("TWITCHDESK_API_BASE_URL").map(|v| v.to_string()))
-            .unwrap_or_else(|| "https://api.twitchdesk.com".to_string());
-
+        let profile = ApiProfile::initial(default_api_base_url());
         Self {
-            api_base_url,
-            username: None,
-            access_token: None,
+            schema_version: crate::storage::LOCAL_STATE_SCHEMA_VERSION,
+            active_profile: profile.id.clone(),
+            profiles: vec![profile],
+            request_timeout_secs: default_request_timeout_secs(),
+            max_retries: default_max_retries(),
+            mock_event_presets: vec![],
+            mock_event_sequence: vec![],
+            starters_dir: String::new(),
             user_cfg: UserConfig::default(),
         }
     }
 }
+
+impl LocalClientState {
+    /// Make sure an active profile exists.
+    pub fn normalize(&mut self) {
+        if self.profiles.is_empty() {
+            self.profiles.push(ApiProfile::initial(default_api_base_url()));
+        }
+        if self.profile_index(&self.active_profile).is_none() {
+            self.active_profile = self.profiles[0].id.clone();
+        }
+    }
+
+    pub fn profile_index(&self, id: &str) -> Option<usize> {
+        self.profiles.iter().position(|p| p.id == id)
+    }
+
+    /// The active profile. `normalize` guarantees there is one.
+    pub fn profile(&self) -> &ApiProfile {
+        let idx = self.profile_index(&self.active_profile).unwrap_or(0);
+        &self.profiles[idx]
+    }
+
+    pub fn profile_mut(&mut self) -> &mut ApiProfile {
+        let idx = self.profile_index(&self.active_profile).unwrap_or(0);
+        &mut self.profiles[idx]
+    }
+
+    /// An id not used by any profile yet.
+    pub fn next_profile_id(&self) -> String {
+        (1..)
+            .map(|n| format!("profile-{n}"))
+            .find(|id| self.profile_index(id).is_none())
+            .unwrap_or_default()
+    }
+}
+
+/// Unsaved editor contents autosaved to the drafts folder, so they survive a crash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draft {
+    pub profile: String,
+    /// `updated_at` of the server copy the edits started from (empty for a new alert).
+    pub base_updated_at: String,
+    /// Unix seconds when the draft was written.
+    pub saved_at: u64,
+    #[serde(flatten)]
+    pub content: DraftContent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DraftContent {
+    Template {
+        template_id: String,
+        template_name: String,
+        version: String,
+        index_html: String,
+        style_css: String,
+        overlay_js: String,
+    },
+    AiAlert {
+        /// `None` for an alert that was never created.
+        alert_id: Option<String>,
+        name: String,
+        prompt: String,
+    },
+}
+
+impl DraftContent {
+    /// One draft per template version / alert; used as the file name.
+    pub fn key(&self) -> String {
+        match self {
+            DraftContent::Template {
+                template_id,
+                version,
+                ..
+            } => format!("template-{template_id}-{version}"),
+            DraftContent::AiAlert { alert_id, .. } => {
+                format!("alert-{}", alert_id.as_deref().unwrap_or("new"))
+            }
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            DraftContent::Template {
+                template_name,
+                version,
+                ..
+            } => format!("Template \"{template_name}\" version {version}"),
+            DraftContent::AiAlert { alert_id: None, name, .. } => format!("New AI alert \"{name}\""),
+            DraftContent::AiAlert { name, .. } => format!("AI alert \"{name}\""),
+        }
+    }
+}
+
+/// Synthetic overlay event sent to preview windows. Overlays receive it as the `detail`
+/// of a `twitchdesk:event` DOM event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MockEvent {
+    Follow { user: String },
+    Sub { user: String },
+    Resub { user: String, months: u32, message: String },
+    Cheer { user: String, bits: u32, message: String },
+    Raid { user: String, viewers: u32 },
+    Chat { user: String, message: String },
+    AiAlert { user: String, text: String },
+}
+
+impl MockEvent {
+    /// One event of each kind, with sample values.
+    pub fn samples() -> [MockEvent; 7] {
+        let user = || "mock_viewer".to_string();
+        [
+            MockEvent::Follow { user: user() },
+            MockEvent::Sub { user: user() },
+            MockEvent::Resub {
+                user: user(),
+                months: 12,
+                message: "A whole year!".to_string(),
+            },
+            MockEvent::Cheer {
+                user: user(),
+                bits: 500,
+                message: "Cheer500 great stream".to_string(),
+            },
+            MockEvent::Raid {
+                user: user(),
+                viewers: 42,
+            },
+            MockEvent::Chat {
+                user: user(),
+                message: "Hello chat!".to_string(),
+            },
+            MockEvent::AiAlert {
+                user: user(),
+                text: "mock_viewer just summoned the hype train!".to_string(),
+            },
+        ]
+    }
+
+    pub fn kind_label(&self) -> &'static str {
+        match self {
+            MockEvent::Follow { .. } => "Follow",
+            MockEvent::Sub { .. } => "Sub",
+            MockEvent::Resub { .. } => "Resub",
+            MockEvent::Cheer { .. } => "Cheer",
+            MockEvent::Raid { .. } => "Raid",
+            MockEvent::Chat { .. } => "Chat message",
+            MockEvent::AiAlert { .. } => "AI alert",
+        }
+    }
+
+    pub fn user(&self) -> &str {
+        match self {
+            MockEvent::Follow { user }
+            | MockEvent::Sub { user }
+            | MockEvent::Resub { user, .. }
+            | MockEvent::Cheer { user, .. }
+            | MockEvent::Raid { user, .. }
+            | MockEvent::Chat { user, .. }
+            | MockEvent::AiAlert { user, .. } => user,
+        }
+    }
+
+    /// One-line description for lists.
+    pub fn summary(&self) -> String {
+        match self {
+            MockEvent::Follow { user } => format!("{user} followed"),
+            MockEvent::Sub { user } => format!("{user} subscribed"),
+            MockEvent::Resub { user, months, .. } => format!("{user} resubscribed ({months} months)"),
+            MockEvent::Cheer { user, bits, .. } => format!("{user} cheered {bits} bits"),
+            MockEvent::Raid { user, viewers } => format!("{user} raided with {viewers} viewers"),
+            MockEvent::Chat { user, message } => format!("{user}: {message}"),
+            MockEvent::AiAlert { text, .. } => format!("AI alert: {text}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MockEventPreset {
+    pub name: String,
+    pub event: MockEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MockSequenceStep {
+    /// Pause before this event is sent.
+    pub delay_ms: u64,
+    pub event: MockEvent,
+}
+
+fn default_api_base_url() -> String {
+    // NOTE: This is not a true secret (clients must know where to connect),
+    // but keeping it out of the repo avoids publishing infrastructure details.
+    std::env::var("TWITCHDESK_API_BASE_URL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(|| option_env!("TWITCHDESK_API_BASE_URL").map(|v| v.to_string()))
+        .unwrap_or_else(|| "https://api.twitchdesk.com".to_string())
+}
+
+fn default_request_timeout_secs() -> u64 {
+    15
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
@@ -1,28 +1,15 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{Context, Result};
 
-pub(crate) fn open_preview(url: &str) -> Result<()> {
-    open_preview_with_args(url, &[])
-}
+/// Helper argument that snapshots the page into a PNG instead of showing it.
+const SCREENSHOT_ARG: &str = "--screenshot";
+const SIZE_ARG: &str = "--size";
+const DELAY_ARG: &str = "--delay-ms";
 
 pub(crate) fn open_preview_with_args(url: &str, extra_args: &[String]) -> Result<()> {
-    let current_exe = std::env::current_exe().context("get current exe")?;
-    let exe_dir = current_exe
-        .parent()
-        .context("resolve exe directory")?
-        .to_path_buf();
-
-    let preview_exe_name = if std::env::consts::OS == "windows" {
-        "twitchdesk-preview.exe"
-    } else {
-        "twitchdesk-preview"
-    };
-
-    let preview_exe = resolve_preview_exe(&exe_dir, preview_exe_name)?;
-
-    let mut cmd = Command::new(preview_exe);
+    let mut cmd = Command::new(preview_exe()?);
     cmd.arg(url);
     cmd.args(extra_args);
     cmd.spawn().context("launch preview helper")?;
@@ -31,6 +18,39 @@ pub(crate) fn open_preview_with_args(url: &str, extra_args: &[String]) -> Result
 }
 
 pub(crate) fn open_preview_with_args_blocking(url: &str, extra_args: &[String]) -> Result<()> {
+    let mut cmd = Command::new(preview_exe()?);
+    cmd.arg(url);
+    cmd.args(extra_args);
+
+    let mut child = cmd.spawn().context("launch preview helper")?;
+    child.wait().context("wait for preview helper")?;
+    Ok(())
+}
+
+/// Render `url` in an out-of-the-way `size` window, wait `delay_ms` for it to settle and
+/// write a PNG snapshot of it to `out`. Blocks until the helper has exited.
+pub(crate) fn capture_screenshot(url: &str, out: &Path, size: (u32, u32), delay_ms: u64) -> Result<()> {
+    let output = Command::new(preview_exe()?)
+        .arg(url)
+        .arg(SCREENSHOT_ARG)
+        .arg(out)
+        .arg(SIZE_ARG)
+        .arg(format!("{}x{}", size.0, size.1))
+        .arg(DELAY_ARG)
+        .arg(delay_ms.to_string())
+        .output()
+        .context("launch preview helper")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("screenshot failed: {}", stderr.trim());
+    }
+    if !out.is_file() {
+        anyhow::bail!("screenshot failed: helper wrote no image");
+    }
+    Ok(())
+}
+
+fn preview_exe() -> Result<PathBuf> {
     let current_exe = std::env::current_exe().context("get current exe")?;
     let exe_dir = current_exe
         .parent()
@@ -43,18 +63,10 @@ pub(crate) fn open_preview_with_args_blocking(url: &str, extra_args: &[String])
         "twitchdesk-preview"
     };
 
-    let preview_exe = resolve_preview_exe(&exe_dir, preview_exe_name)?;
-
-    let mut cmd = Command::new(preview_exe);
-    cmd.arg(url);
-    cmd.args(extra_args);
-
-    let mut child = cmd.spawn().context("launch preview helper")?;
-    child.wait().context("wait for preview helper")?;
-    Ok(())
+    resolve_preview_exe(&exe_dir, preview_exe_name)
 }
 
-fn resolve_preview_exe(exe_dir: &std::path::Path, preview_exe_name: &str) -> Result<PathBuf> {
+fn resolve_preview_exe(exe_dir: &Path, preview_exe_name: &str) -> Result<PathBuf> {
     // Release installs should place the helper next to the main app.
     let candidate = exe_dir.join(preview_exe_name);
     let preview_exe = if candidate.exists() {
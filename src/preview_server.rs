@@ -0,0 +1,341 @@
+//! Localhost HTTP server that serves the template editor buffers, so unsaved edits can
+//! be previewed without a round trip through the API. Pages reload themselves (or just
+//! their stylesheets) over server-sent events whenever the buffers change. Script errors
+//! in the page are posted back so the editor can point at the offending line. Assets of
+//! the loaded version are served under `assets/`, as on the backend.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, warn};
+
+use crate::templates::{TemplateFile, ASSETS_DIR};
+
+const LIVE_PATH: &str = "/__twitchdesk/live";
+const ERROR_PATH: &str = "/__twitchdesk/error";
+const MAX_ERROR_BODY: usize = 16 * 1024;
+
+/// Wait for typing to settle before telling pages to reload.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(250);
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+(() => {
+  const es = new EventSource("/__twitchdesk/live");
+  es.addEventListener("css", () => {
+    document.querySelectorAll('link[rel="stylesheet"]').forEach((l) => {
+      const u = new URL(l.href);
+      u.searchParams.set("v", Date.now());
+      l.href = u.toString();
+    });
+  });
+  es.addEventListener("reload", () => location.reload());
+})();
+</script>"#;
+
+/// Reports uncaught script errors. Kept on one line so it doesn't shift the line
+/// numbers of `index.html`.
+const ERROR_REPORT_SCRIPT: &str = r#"<script>window.addEventListener("error", (e) => { fetch("/__twitchdesk/error", { method: "POST", headers: { "Content-Type": "application/json" }, body: JSON.stringify({ source: e.filename || "", line: e.lineno || 0, column: e.colno || 0, message: e.message || String(e.error) }) }).catch(() => {}); });</script>"#;
+
+/// Uncaught script error reported by a preview page.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub(crate) struct PreviewError {
+    /// Script URL (the page URL for inline scripts).
+    pub source: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl PreviewError {
+    /// Template file the error points into, if it came from one.
+    pub(crate) fn file(&self) -> Option<TemplateFile> {
+        let path = self
+            .source
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]))
+            .unwrap_or_default();
+        match path.split(['?', '#']).next().unwrap_or_default() {
+            "/" | "/index.html" => Some(TemplateFile::Html),
+            "/overlay.js" => Some(TemplateFile::Js),
+            _ => None,
+        }
+    }
+}
+
+/// Asset file served next to the page.
+#[derive(Debug, Clone)]
+pub(crate) struct PreviewAsset {
+    pub mime_type: String,
+    pub bytes: Arc<[u8]>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PreviewPage {
+    index_html: String,
+    style_css: String,
+    overlay_js: String,
+    assets: Arc<BTreeMap<String, PreviewAsset>>,
+    revision: u64,
+    /// Last revision that changed more than the stylesheet (needs a full reload).
+    full_revision: u64,
+}
+
+pub(crate) struct PreviewServer {
+    addr: SocketAddr,
+    tx: watch::Sender<PreviewPage>,
+    errors: mpsc::UnboundedReceiver<PreviewError>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl PreviewServer {
+    /// Bind to a random port on 127.0.0.1 and start serving on `rt`.
+    pub(crate) fn start(rt: &tokio::runtime::Runtime) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).context("bind preview server")?;
+        listener
+            .set_nonblocking(true)
+            .context("configure preview server")?;
+        let addr = listener.local_addr().context("preview server address")?;
+
+        let (tx, rx) = watch::channel(PreviewPage::default());
+        let (errors_tx, errors) = mpsc::unbounded_channel();
+        let task = rt.spawn(async move {
+            let listener = match TcpListener::from_std(listener) {
+                Ok(l) => l,
+                Err(e) => {
+                    warn!(error = ?e, "preview server listener failed");
+                    return;
+                }
+            };
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let rx = rx.clone();
+                        let errors_tx = errors_tx.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, rx, errors_tx).await {
+                                debug!(error = ?e, "preview connection closed");
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        warn!(error = ?e, "preview server accept failed");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { addr, tx, errors, task })
+    }
+
+    /// Page URL with `query` (empty or starting with `?`). The query string is passed
+    /// through untouched, so templates that read `?mock=true` or their parameters behave
+    /// as they do on the backend.
+    pub(crate) fn url(&self, query: &str) -> String {
+        format!("http://{}/{query}", self.addr)
+    }
+
+    /// Publish the current editor buffers; connected pages reload if anything changed.
+    /// Returns `true` when pages will do a full reload (not just a stylesheet swap).
+    pub(crate) fn sync(&self, index_html: &str, style_css: &str, overlay_js: &str) -> bool {
+        let mut full_reload = false;
+        self.tx.send_if_modified(|page| {
+            let css_changed = page.style_css != style_css;
+            let other_changed = page.index_html != index_html || page.overlay_js != overlay_js;
+            if !css_changed && !other_changed {
+                return false;
+            }
+            page.revision += 1;
+            if other_changed {
+                full_reload = true;
+                page.full_revision = page.revision;
+                page.index_html = index_html.to_string();
+                page.overlay_js = overlay_js.to_string();
+            }
+            if css_changed {
+                page.style_css = style_css.to_string();
+            }
+            true
+        });
+        full_reload
+    }
+
+    /// Replace the served assets (by name); connected pages reload.
+    pub(crate) fn set_assets(&self, assets: BTreeMap<String, PreviewAsset>) {
+        self.tx.send_modify(|page| {
+            page.revision += 1;
+            page.full_revision = page.revision;
+            page.assets = Arc::new(assets);
+        });
+    }
+
+    /// Script errors reported by preview pages since the last call.
+    pub(crate) fn take_errors(&mut self) -> Vec<PreviewError> {
+        let mut errors = vec![];
+        while let Ok(e) = self.errors.try_recv() {
+            errors.push(e);
+        }
+        errors
+    }
+}
+
+impl Drop for PreviewServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    rx: watch::Receiver<PreviewPage>,
+    errors: mpsc::UnboundedSender<PreviewError>,
+) -> std::io::Result<()> {
+    let mut head = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&chunk[..n]);
+        if head.len() > 16 * 1024 {
+            return respond(&mut stream, "431 Request Header Fields Too Large", "text/plain", b"").await;
+        }
+    }
+
+    let head_end = head.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(head.len()) + 4;
+    let mut body = head.split_off(head_end.min(head.len()));
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or("/");
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+
+    if method == "POST" && path == ERROR_PATH {
+        let length = head
+            .lines()
+            .filter_map(|l| l.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if length > MAX_ERROR_BODY {
+            return respond(&mut stream, "413 Payload Too Large", "text/plain", b"").await;
+        }
+        while body.len() < length {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..n]);
+        }
+        body.truncate(length);
+        return match serde_json::from_slice::<PreviewError>(&body) {
+            Ok(error) => {
+                let _ = errors.send(error);
+                respond(&mut stream, "204 No Content", "text/plain", b"").await
+            }
+            Err(_) => respond(&mut stream, "400 Bad Request", "text/plain", b"").await,
+        };
+    }
+
+    if method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", b"").await;
+    }
+
+    let (content_type, body) = match path {
+        LIVE_PATH => return stream_live_reload(stream, rx).await,
+        "/" | "/index.html" => ("text/html; charset=utf-8", render_index(&rx.borrow())),
+        "/style.css" => ("text/css; charset=utf-8", rx.borrow().style_css.clone()),
+        "/overlay.js" => ("text/javascript; charset=utf-8", rx.borrow().overlay_js.clone()),
+        _ => {
+            let asset = path
+                .strip_prefix('/')
+                .and_then(|p| p.strip_prefix(ASSETS_DIR))
+                .and_then(|p| p.strip_prefix('/'))
+                .and_then(|name| urlencoding::decode(name).ok())
+                .and_then(|name| rx.borrow().assets.get(name.as_ref()).cloned());
+            return match asset {
+                Some(asset) => respond(&mut stream, "200 OK", &asset.mime_type, &asset.bytes).await,
+                None => respond(&mut stream, "404 Not Found", "text/plain", b"Not found").await,
+            };
+        }
+    };
+    respond(&mut stream, "200 OK", content_type, body.as_bytes()).await
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.shutdown().await
+}
+
+async fn stream_live_reload(mut stream: TcpStream, mut rx: watch::Receiver<PreviewPage>) -> std::io::Result<()> {
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: keep-alive\r\n\r\n: connected\n\n")
+        .await?;
+
+    let mut notified = rx.borrow_and_update().revision;
+    loop {
+        tokio::select! {
+            changed = rx.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+                tokio::time::sleep(RELOAD_DEBOUNCE).await;
+                let (revision, full_revision) = {
+                    let page = rx.borrow_and_update();
+                    (page.revision, page.full_revision)
+                };
+                let event = if full_revision > notified { "reload" } else { "css" };
+                notified = revision;
+                stream
+                    .write_all(format!("event: {event}\ndata: {revision}\n\n").as_bytes())
+                    .await?;
+            }
+            _ = tokio::time::sleep(KEEPALIVE) => {
+                stream.write_all(b": ping\n\n").await?;
+            }
+        }
+    }
+}
+
+/// `index.html` with the stylesheet/script linked (unless it already references them),
+/// the error reporter ahead of the first script and the live-reload client appended.
+fn render_index(page: &PreviewPage) -> String {
+    let mut html = page.index_html.clone();
+    if !html.contains("style.css") {
+        insert_before(&mut html, "</head>", r#"<link rel="stylesheet" href="style.css">"#, false);
+    }
+    let mut tail = String::new();
+    match html.to_ascii_lowercase().find("<script") {
+        Some(idx) => html.insert_str(idx, ERROR_REPORT_SCRIPT),
+        None => tail.push_str(ERROR_REPORT_SCRIPT),
+    }
+    if !html.contains("overlay.js") {
+        tail.push_str(r#"<script src="overlay.js"></script>"#);
+    }
+    tail.push_str(LIVE_RELOAD_SCRIPT);
+    insert_before(&mut html, "</body>", &tail, true);
+    html
+}
+
+/// Insert `extra` before the last `tag` (case-insensitive); otherwise append or prepend.
+fn insert_before(html: &mut String, tag: &str, extra: &str, append_if_missing: bool) {
+    match html.to_ascii_lowercase().rfind(tag) {
+        Some(idx) => html.insert_str(idx, extra),
+        None if append_if_missing => html.push_str(extra),
+        None => html.insert_str(0, extra),
+    }
+}
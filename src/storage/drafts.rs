@@ -0,0 +1,61 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use tracing::warn;
+
+use crate::models::Draft;
+
+use super::{local_state_path, write_file_atomic};
+
+/// `drafts/` next to `local-state.json`.
+pub fn drafts_dir() -> Result<PathBuf> {
+    let dir = local_state_path()?.with_file_name("drafts");
+    fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+    Ok(dir)
+}
+
+fn draft_path(profile: &str, key: &str) -> Result<PathBuf> {
+    let name: String = format!("{profile}--{key}")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    Ok(drafts_dir()?.join(format!("{name}.json")))
+}
+
+pub fn save_draft(draft: &Draft) -> Result<()> {
+    let path = draft_path(&draft.profile, &draft.content.key())?;
+    let raw = serde_json::to_string_pretty(draft)?;
+    write_file_atomic(&path, raw.as_bytes())
+}
+
+pub fn delete_draft(profile: &str, key: &str) -> Result<()> {
+    let path = draft_path(profile, key)?;
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Drafts left behind for `profile`. Unreadable files are skipped (and logged).
+pub fn load_drafts(profile: &str) -> Result<Vec<Draft>> {
+    let dir = drafts_dir()?;
+    let mut drafts = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let parsed = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|raw| Ok(serde_json::from_str::<Draft>(&raw)?));
+        match parsed {
+            Ok(d) if d.profile == profile => drafts.push(d),
+            Ok(_) => {}
+            Err(e) => warn!(error = ?e, path = %path.display(), "skipping unreadable draft"),
+        }
+    }
+    drafts.sort_by_key(|d| std::cmp::Reverse(d.saved_at));
+    Ok(drafts)
+}
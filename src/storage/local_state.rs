@@ -1,10 +1,25 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
 
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use regex::Regex;
+use serde_json::{json, Value};
 
 use crate::models::LocalClientState;
 
+/// Version written to `local-state.json`. Bump it and add a step to [`MIGRATIONS`]
+/// whenever the on-disk shape changes.
+pub const LOCAL_STATE_SCHEMA_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n]` upgrades a schema `n + 1` document to schema `n + 2`.
+/// Files written before versioning have no `schema_version` and count as schema 1.
+const MIGRATIONS: &[fn(&mut Value) -> Result<()>] = &[migrate_v1_to_v2];
+
 pub fn local_state_path() -> Result<PathBuf> {
     let proj = ProjectDirs::from("com", "TwitchDesk", "TwitchDesk")
         .ok_or_else(|| anyhow::anyhow!("Could not determine local data directory"))?;
@@ -13,20 +28,271 @@ pub fn local_state_path() -> Result<PathBuf> {
     Ok(dir.join("local-state.json"))
 }
 
+/// Copy of the last state file that parsed fine, refreshed on every save.
+pub fn local_state_backup_path() -> Result<PathBuf> {
+    Ok(local_state_path()?.with_extension("json.bak"))
+}
+
 pub fn load_local_state() -> Result<LocalClientState> {
     let path = local_state_path()?;
     if !path.exists() {
         return Ok(LocalClientState::default());
     }
-    let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
-    let st = serde_json::from_str::<LocalClientState>(&raw)
-        .with_context(|| format!("parse {}", path.display()))?;
-    Ok(st)
+    read_local_state(&path)
+}
+
+pub fn load_local_state_backup() -> Result<LocalClientState> {
+    let path = local_state_backup_path()?;
+    if !path.exists() {
+        anyhow::bail!("No backup at {}", path.display());
+    }
+    read_local_state(&path)
 }
 
 pub fn save_local_state(st: &LocalClientState) -> Result<PathBuf> {
     let path = local_state_path()?;
+
+    // Keep the previous file as backup, but never replace a good backup with a broken file.
+    // Tokens stay out of it: the previous file may still hold one that is being moved to
+    // the secret store right now.
+    if path.exists() && read_local_state(&path).is_ok() {
+        let backup = local_state_backup_path()?;
+        let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        write_file_atomic(&backup, redact_access_tokens(&raw).as_bytes())?;
+    }
+
     let raw = serde_json::to_string_pretty(st)?;
-    fs::write(&path, raw).with_context(|| format!("write {}", path.display()))?;
+    write_file_atomic(&path, raw.as_bytes())?;
     Ok(path)
 }
+
+/// Move an unreadable state file out of the way (kept for inspection) so a fresh one can be written.
+pub fn set_aside_local_state() -> Result<Option<PathBuf>> {
+    let path = local_state_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let aside = path.with_file_name(format!("local-state.broken-{ts}.json"));
+    let raw = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+    let raw = redact_access_tokens(&String::from_utf8_lossy(&raw));
+    write_file_atomic(&aside, raw.as_bytes())?;
+    fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+    Ok(Some(aside))
+}
+
+/// An `"access_token"` key with its (possibly truncated) string value.
+static ACCESS_TOKEN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""access_token"(\s*):(\s*)"(?:[^"\\]|\\.)*"?"#).expect("valid regex"));
+
+/// `raw` with the value of every `"access_token"` key replaced by `null`. Works on text
+/// rather than parsed JSON so files that no longer parse are covered too.
+fn redact_access_tokens(raw: &str) -> String {
+    ACCESS_TOKEN_RE
+        .replace_all(raw, r#""access_token"${1}:${2}null"#)
+        .into_owned()
+}
+
+/// Write to a temp file in the same directory, flush it to disk, then rename over `path`,
+/// so a crash mid-write leaves either the old or the new file, never a truncated one.
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+
+    let mut f = fs::File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+    f.write_all(data)
+        .and_then(|_| f.sync_all())
+        .with_context(|| format!("write {}", tmp.display()))?;
+    drop(f);
+
+    fs::rename(&tmp, path).with_context(|| format!("rename {} to {}", tmp.display(), path.display()))
+}
+
+fn read_local_state(path: &Path) -> Result<LocalClientState> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let value = serde_json::from_str::<Value>(&raw)
+        .with_context(|| format!("parse {}", path.display()))?;
+    let value = migrate(value).with_context(|| format!("migrate {}", path.display()))?;
+    let mut st = serde_json::from_value::<LocalClientState>(value)
+        .with_context(|| format!("parse {}", path.display()))?;
+    st.normalize();
+    Ok(st)
+}
+
+fn migrate(mut value: Value) -> Result<Value> {
+    if !value.is_object() {
+        anyhow::bail!("expected a JSON object");
+    }
+
+    let mut version = match value.get("schema_version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow::anyhow!("invalid schema_version"))?,
+    };
+    if version == 0 {
+        anyhow::bail!("invalid schema_version 0");
+    }
+    if version > LOCAL_STATE_SCHEMA_VERSION {
+        anyhow::bail!(
+            "schema_version {version} was written by a newer TwitchDesk (this build supports up to {LOCAL_STATE_SCHEMA_VERSION})"
+        );
+    }
+
+    while version < LOCAL_STATE_SCHEMA_VERSION {
+        let step = MIGRATIONS[(version - 1) as usize];
+        step(&mut value).with_context(|| format!("schema {version} -> {}", version + 1))?;
+        version += 1;
+        value["schema_version"] = json!(version);
+    }
+    Ok(value)
+}
+
+/// v2: the single account (`api_base_url`, `username`, `access_token`) becomes the first profile.
+fn migrate_v1_to_v2(value: &mut Value) -> Result<()> {
+    let obj = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("expected a JSON object"))?;
+
+    let api_base_url = obj.remove("api_base_url");
+    let username = obj.remove("username");
+    // A plaintext token is carried along in memory only and moved to the secret store on startup.
+    let access_token = obj.remove("access_token");
+
+    if obj.get("profiles").map(|p| p.is_array()).unwrap_or(false) {
+        return Ok(());
+    }
+
+    let mut profile = json!({
+        "id": "default",
+        "name": "Default",
+        "username": username.unwrap_or(Value::Null),
+        "access_token": access_token.unwrap_or(Value::Null),
+        // Key used before profiles existed, so an already stored token keeps working.
+        "token_ref": "access-token",
+    });
+    match api_base_url {
+        Some(Value::String(url)) if !url.trim().is_empty() => profile["api_base_url"] = json!(url),
+        _ => profile["api_base_url"] = json!(LocalClientState::default().profile().api_base_url),
+    }
+
+    obj.insert("profiles".to_string(), json!([profile]));
+    obj.insert("active_profile".to_string(), json!("default"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A state file in a fresh temp directory.
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("twitchdesk-local-state-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("local-state.json");
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    const V1: &str = r#"{
+        "api_base_url": "https://api.example.com",
+        "username": "alice",
+        "access_token": "secret-token",
+        "request_timeout_secs": 30
+    }"#;
+
+    #[test]
+    fn migrates_v1_account_into_first_profile() {
+        let value = migrate(serde_json::from_str(V1).unwrap()).unwrap();
+        assert_eq!(value["schema_version"], json!(LOCAL_STATE_SCHEMA_VERSION));
+        assert_eq!(value["active_profile"], json!("default"));
+        assert_eq!(
+            value["profiles"],
+            json!([{
+                "id": "default",
+                "name": "Default",
+                "api_base_url": "https://api.example.com",
+                "username": "alice",
+                "access_token": "secret-token",
+                "token_ref": "access-token",
+            }])
+        );
+        assert!(value.get("api_base_url").is_none());
+        assert!(value.get("access_token").is_none());
+    }
+
+    #[test]
+    fn v1_without_api_url_gets_the_default() {
+        let value = migrate(json!({ "api_base_url": "  " })).unwrap();
+        assert_eq!(
+            value["profiles"][0]["api_base_url"],
+            json!(LocalClientState::default().profile().api_base_url)
+        );
+        assert_eq!(value["profiles"][0]["username"], Value::Null);
+    }
+
+    #[test]
+    fn reads_a_v1_file() {
+        let path = write_temp("v1", V1);
+        let st = read_local_state(&path).unwrap();
+        let profile = st.profile();
+        assert_eq!(profile.id, "default");
+        assert_eq!(profile.username.as_deref(), Some("alice"));
+        assert_eq!(profile.api_base_url, "https://api.example.com");
+        assert_eq!(profile.access_token.as_deref(), Some("secret-token"));
+        assert_eq!(st.request_timeout_secs, 30);
+    }
+
+    #[test]
+    fn current_version_is_left_alone() {
+        let doc = json!({
+            "schema_version": LOCAL_STATE_SCHEMA_VERSION,
+            "profiles": [],
+            "active_profile": "",
+        });
+        assert_eq!(migrate(doc.clone()).unwrap(), doc);
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let future = LOCAL_STATE_SCHEMA_VERSION + 1;
+        let err = migrate(json!({ "schema_version": future })).unwrap_err();
+        assert!(format!("{err:#}").contains("newer TwitchDesk"), "{err:#}");
+
+        assert!(migrate(json!({ "schema_version": 0 })).is_err());
+        assert!(migrate(json!({ "schema_version": "2" })).is_err());
+        assert!(migrate(json!([])).is_err());
+    }
+
+    #[test]
+    fn unreadable_file_leaves_the_backup_usable() {
+        let broken = write_temp("broken", r#"{ "profiles": ["#);
+        assert!(read_local_state(&broken).is_err());
+
+        // What `save_local_state` keeps as backup of a v1 file.
+        let backup = write_temp("backup", &redact_access_tokens(V1));
+        let st = read_local_state(&backup).unwrap();
+        assert_eq!(st.profile().username.as_deref(), Some("alice"));
+        assert_eq!(st.profile().access_token, None);
+    }
+
+    #[test]
+    fn redacts_access_tokens() {
+        assert_eq!(
+            redact_access_tokens(r#"{"access_token":"abc","username":"alice"}"#),
+            r#"{"access_token":null,"username":"alice"}"#
+        );
+        assert_eq!(
+            redact_access_tokens(r#"[{"access_token" : "a\"b\\"}, {"access_token": null}]"#),
+            r#"[{"access_token" : null}, {"access_token": null}]"#
+        );
+        // Cut off mid-token, as in a truncated file.
+        assert_eq!(redact_access_tokens(r#"{"access_token": "abc"#), r#"{"access_token": null"#);
+        assert_eq!(redact_access_tokens(r#"{"token_ref":"access-token"}"#), r#"{"token_ref":"access-token"}"#);
+    }
+}
@@ -1,3 +1,7 @@
+mod drafts;
 mod local_state;
+mod secrets;
 
+pub use drafts::*;
 pub use local_state::*;
+pub use secrets::*;
@@ -0,0 +1,267 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{local_state_path, write_file_atomic};
+
+const KEYRING_SERVICE: &str = "TwitchDesk";
+
+/// Where secrets (bearer tokens) live. Never store them in `local-state.json`.
+pub trait SecretStore {
+    /// Human readable backend name for the status line.
+    fn backend_name(&self) -> &'static str;
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    fn set(&self, key: &str, value: &str) -> Result<()>;
+    fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Result of trying to open the preferred secret store.
+pub enum SecretStoreOpen {
+    Ready(Box<dyn SecretStore>),
+    /// No OS keyring available; the encrypted file needs a passphrase
+    /// (`existing` tells whether a file already exists to unlock).
+    NeedsPassphrase { existing: bool },
+}
+
+/// Prefer the OS keyring; fall back to the encrypted file (unlocked via
+/// `TWITCHDESK_SECRETS_PASSPHRASE` if set, otherwise by prompting the user).
+pub fn open_secret_store() -> SecretStoreOpen {
+    if let Some(store) = KeyringStore::probe() {
+        return SecretStoreOpen::Ready(Box::new(store));
+    }
+
+    let existing = encrypted_secrets_path()
+        .map(|p| p.exists())
+        .unwrap_or(false);
+
+    if let Some(pass) = std::env::var("TWITCHDESK_SECRETS_PASSPHRASE")
+        .ok()
+        .filter(|v| !v.is_empty())
+    {
+        match EncryptedFileStore::open(&pass) {
+            Ok(store) => return SecretStoreOpen::Ready(Box::new(store)),
+            Err(e) => tracing::warn!("encrypted secrets unlock from env failed: {e:#}"),
+        }
+    }
+
+    SecretStoreOpen::NeedsPassphrase { existing }
+}
+
+// -------------------------------
+// OS keyring (Secret Service on Linux, Keychain on macOS, Credential Manager on Windows)
+// -------------------------------
+
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// Returns `None` when the platform keyring is unreachable (e.g. no Secret Service on D-Bus).
+    pub fn probe() -> Option<Self> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, "probe").ok()?;
+        match entry.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Some(Self),
+            Err(e) => {
+                tracing::info!("OS keyring unavailable: {e}");
+                None
+            }
+        }
+    }
+
+    fn entry(key: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, key).context("open keyring entry")
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn backend_name(&self) -> &'static str {
+        "OS keyring"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match Self::entry(key)?.get_password() {
+            Ok(v) => Ok(Some(v)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("read keyring entry"),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        Self::entry(key)?
+            .set_password(value)
+            .context("write keyring entry")
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        match Self::entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("delete keyring entry"),
+        }
+    }
+}
+
+// -------------------------------
+// Encrypted file fallback
+// -------------------------------
+
+/// Known plaintext used to tell a wrong passphrase from a corrupt file.
+const CHECK_PLAINTEXT: &str = "twitchdesk-secrets";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedValue {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecretsFile {
+    version: u32,
+    salt: String,
+    check: SealedValue,
+    #[serde(default)]
+    entries: BTreeMap<String, SealedValue>,
+}
+
+pub fn encrypted_secrets_path() -> Result<PathBuf> {
+    Ok(local_state_path()?.with_file_name("secrets.enc.json"))
+}
+
+/// Secrets encrypted with ChaCha20-Poly1305 under an Argon2id key derived from a passphrase.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+}
+
+impl EncryptedFileStore {
+    /// Unlock the existing file, or create a new one protected by `passphrase`.
+    pub fn open(passphrase: &str) -> Result<Self> {
+        if passphrase.is_empty() {
+            anyhow::bail!("passphrase must not be empty");
+        }
+
+        let path = encrypted_secrets_path()?;
+        if path.exists() {
+            let file = read_secrets_file(&path)?;
+            let salt = from_hex(&file.salt).context("parse salt")?;
+            let cipher = derive_cipher(passphrase, &salt)?;
+            let check = open_value(&cipher, &file.check)
+                .map_err(|_| anyhow::anyhow!("wrong passphrase"))?;
+            if check != CHECK_PLAINTEXT {
+                anyhow::bail!("wrong passphrase");
+            }
+            return Ok(Self { path, cipher });
+        }
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = derive_cipher(passphrase, &salt)?;
+        let file = SecretsFile {
+            version: 1,
+            salt: to_hex(&salt),
+            check: seal_value(&cipher, CHECK_PLAINTEXT)?,
+            entries: BTreeMap::new(),
+        };
+        write_secrets_file(&path, &file)?;
+        Ok(Self { path, cipher })
+    }
+
+    /// Delete the encrypted file (forgotten passphrase); any saved session is lost.
+    pub fn reset() -> Result<()> {
+        let path = encrypted_secrets_path()?;
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn backend_name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let file = read_secrets_file(&self.path)?;
+        file.entries
+            .get(key)
+            .map(|v| open_value(&self.cipher, v))
+            .transpose()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        let mut file = read_secrets_file(&self.path)?;
+        file.entries
+            .insert(key.to_string(), seal_value(&self.cipher, value)?);
+        write_secrets_file(&self.path, &file)
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        let mut file = read_secrets_file(&self.path)?;
+        if file.entries.remove(key).is_some() {
+            write_secrets_file(&self.path, &file)?;
+        }
+        Ok(())
+    }
+}
+
+fn derive_cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("derive key: {e}"))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn seal_value(cipher: &ChaCha20Poly1305, plaintext: &str) -> Result<SealedValue> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow::anyhow!("encrypt secret"))?;
+    Ok(SealedValue {
+        nonce: to_hex(&nonce),
+        ciphertext: to_hex(&ciphertext),
+    })
+}
+
+fn open_value(cipher: &ChaCha20Poly1305, sealed: &SealedValue) -> Result<String> {
+    let nonce = from_hex(&sealed.nonce).context("parse nonce")?;
+    if nonce.len() != 12 {
+        anyhow::bail!("invalid nonce length");
+    }
+    let ciphertext = from_hex(&sealed.ciphertext).context("parse ciphertext")?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| anyhow::anyhow!("decrypt secret"))?;
+    String::from_utf8(plaintext).context("secret is not utf-8")
+}
+
+fn read_secrets_file(path: &Path) -> Result<SecretsFile> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("parse {}", path.display()))
+}
+
+fn write_secrets_file(path: &Path, file: &SecretsFile) -> Result<()> {
+    let raw = serde_json::to_string_pretty(file)?;
+    write_file_atomic(path, raw.as_bytes())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        anyhow::bail!("odd hex length");
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).context("invalid hex"))
+        .collect()
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Largest file accepted for upload.
+pub(crate) const MAX_ASSET_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Folder assets are served from, relative to the overlay page.
+pub(crate) const ASSETS_DIR: &str = "assets";
+
+/// File types an asset can have, by extension.
+const ASSET_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("mp3", "audio/mpeg"),
+    ("ogg", "audio/ogg"),
+    ("wav", "audio/wav"),
+    ("webm", "video/webm"),
+    ("mp4", "video/mp4"),
+];
+
+/// MIME type for an asset file name, `None` if the type is not accepted.
+pub(crate) fn asset_mime_type(name: &str) -> Option<&'static str> {
+    let (_, ext) = name.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    ASSET_TYPES.iter().find(|(e, _)| *e == ext).map(|(_, mime)| *mime)
+}
+
+/// Extensions accepted for upload, for messages.
+pub(crate) fn asset_extensions() -> String {
+    ASSET_TYPES.iter().map(|(e, _)| *e).collect::<Vec<_>>().join(", ")
+}
+
+/// Asset name for an uploaded file: its file name, which must have an accepted type and
+/// must not be hidden.
+pub(crate) fn asset_name(path: &Path) -> Result<String> {
+    let name = path
+        .file_name()
+        .context("path has no file name")?
+        .to_string_lossy()
+        .trim()
+        .to_string();
+    if name.starts_with('.') || name.chars().any(|c| c.is_control() || c == '/' || c == '\\') {
+        anyhow::bail!("{name:?} is not a valid asset name");
+    }
+    if asset_mime_type(&name).is_none() {
+        anyhow::bail!("unsupported file type (use {})", asset_extensions());
+    }
+    Ok(name)
+}
+
+/// URL of an asset relative to the overlay page, for `src`/`url()` in the template.
+pub(crate) fn asset_url(name: &str) -> String {
+    format!("{ASSETS_DIR}/{}", urlencoding::encode(name))
+}
+
+/// `1.2 MB` style size for the asset list.
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
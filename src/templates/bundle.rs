@@ -0,0 +1,176 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use super::{TemplateFile, TemplateSources};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const BUNDLE_FORMAT: &str = "twitchdesk-template";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+/// Refuse bundle entries larger than this (a template file is a few KB).
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Suggested file extension for exported bundles.
+pub(crate) const BUNDLE_EXTENSION: &str = "twitchdesk.zip";
+
+/// A template with all its versions, as stored in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TemplateBundle {
+    pub name: String,
+    pub versions: Vec<BundleVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BundleVersion {
+    pub version: String,
+    pub is_published: bool,
+    pub updated_at: String,
+    pub sources: TemplateSources,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    format_version: u32,
+    name: String,
+    versions: Vec<ManifestVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestVersion {
+    version: String,
+    is_published: bool,
+    #[serde(default)]
+    updated_at: String,
+    /// Folder inside the zip holding this version's files.
+    dir: String,
+}
+
+/// `manifest.json` + `versions/<n>/{index.html,style.css,overlay.js}`.
+pub(crate) fn write_template_bundle(path: &Path, bundle: &TemplateBundle) -> Result<()> {
+    let tmp = path.with_extension("zip.tmp");
+    let file = File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+    let written = write_bundle_zip(file, bundle).and_then(|()| {
+        std::fs::rename(&tmp, path).with_context(|| format!("rename {} to {}", tmp.display(), path.display()))
+    });
+    if written.is_err() {
+        // Don't leave a partial bundle next to the target.
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+fn write_bundle_zip(file: File, bundle: &TemplateBundle) -> Result<()> {
+    let mut manifest = Manifest {
+        format: BUNDLE_FORMAT.to_string(),
+        format_version: BUNDLE_FORMAT_VERSION,
+        name: bundle.name.clone(),
+        versions: vec![],
+    };
+
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    // Version labels may contain anything, so folders are numbered and the manifest maps them.
+    for (i, v) in bundle.versions.iter().enumerate() {
+        let dir = format!("versions/{}", i + 1);
+        for f in TemplateFile::ALL {
+            zip.start_file(format!("{dir}/{}", f.file_name()), options)?;
+            zip.write_all(v.sources.get(f).as_bytes())?;
+        }
+        manifest.versions.push(ManifestVersion {
+            version: v.version.clone(),
+            is_published: v.is_published,
+            updated_at: v.updated_at.clone(),
+            dir,
+        });
+    }
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?.sync_all()?;
+    Ok(())
+}
+
+pub(crate) fn read_template_bundle(path: &Path) -> Result<TemplateBundle> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut zip = ZipArchive::new(file).context("open zip")?;
+
+    let manifest: Manifest = serde_json::from_str(&read_entry(&mut zip, MANIFEST_FILE)?)
+        .context("parse manifest.json")?;
+    if manifest.format != BUNDLE_FORMAT {
+        anyhow::bail!("not a TwitchDesk template bundle");
+    }
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        anyhow::bail!(
+            "bundle format {} is newer than this build supports ({BUNDLE_FORMAT_VERSION})",
+            manifest.format_version
+        );
+    }
+    if manifest.name.trim().is_empty() {
+        anyhow::bail!("bundle has no template name");
+    }
+    if manifest.versions.is_empty() {
+        anyhow::bail!("bundle has no versions");
+    }
+
+    let mut versions = Vec::with_capacity(manifest.versions.len());
+    for v in manifest.versions {
+        if v.version.trim().is_empty() {
+            anyhow::bail!("bundle has a version without a label");
+        }
+        if versions.iter().any(|x: &BundleVersion| x.version == v.version) {
+            anyhow::bail!("bundle lists version {} twice", v.version);
+        }
+        let mut sources = TemplateSources::default();
+        for f in TemplateFile::ALL {
+            let name = format!("{}/{}", v.dir.trim_end_matches('/'), f.file_name());
+            *sources.get_mut(f) = read_entry(&mut zip, &name)?;
+        }
+        versions.push(BundleVersion {
+            version: v.version,
+            is_published: v.is_published,
+            updated_at: v.updated_at,
+            sources,
+        });
+    }
+
+    Ok(TemplateBundle {
+        name: manifest.name,
+        versions,
+    })
+}
+
+fn read_entry(zip: &mut ZipArchive<File>, name: &str) -> Result<String> {
+    let entry = zip
+        .by_name(name)
+        .with_context(|| format!("bundle is missing {name}"))?;
+    if entry.size() > MAX_ENTRY_BYTES {
+        anyhow::bail!("{name} is too large");
+    }
+    let mut out = String::new();
+    entry
+        .take(MAX_ENTRY_BYTES)
+        .read_to_string(&mut out)
+        .with_context(|| format!("read {name}"))?;
+    Ok(out)
+}
+
+/// `name`, or `name (2)`, `name (3)`, … — the first one not in `taken` (case-insensitive).
+pub(crate) fn unique_template_name(name: &str, taken: &[String]) -> String {
+    let name = name.trim();
+    let is_taken = |candidate: &str| taken.iter().any(|t| t.trim().eq_ignore_ascii_case(candidate));
+    if !is_taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{name} ({n})"))
+        .find(|c| !is_taken(c))
+        .unwrap_or_else(|| name.to_string())
+}
@@ -0,0 +1,382 @@
+use anyhow::Result;
+
+/// Lines of unchanged context kept around each hunk.
+pub(crate) const DIFF_CONTEXT: usize = 3;
+
+/// Above this many LCS cells the middle of the file is shown as one replaced block.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffTag {
+    Equal,
+    Removed,
+    Added,
+}
+
+/// One line of a diff; `text` keeps its line ending so hunks can be applied verbatim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffLine {
+    pub tag: DiffTag,
+    /// 1-based line number in the old / new text.
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+impl DiffLine {
+    /// Line contents without the trailing line ending.
+    pub(crate) fn content(&self) -> &str {
+        self.text.trim_end_matches(['\n', '\r'])
+    }
+}
+
+/// Which side of a diff to take when applying a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffSide {
+    Old,
+    New,
+}
+
+/// A run of changes with surrounding context, like a `@@` block of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffHunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    pub(crate) fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        )
+    }
+
+    /// The hunk's lines as they appear on `side` (context included).
+    fn side_lines(&self, side: DiffSide) -> Vec<&str> {
+        let skip = match side {
+            DiffSide::Old => DiffTag::Added,
+            DiffSide::New => DiffTag::Removed,
+        };
+        self.lines
+            .iter()
+            .filter(|l| l.tag != skip)
+            .map(|l| l.text.as_str())
+            .collect()
+    }
+
+    /// 0-based line where this hunk starts on `side`.
+    fn side_start(&self, side: DiffSide) -> usize {
+        // Empty sides use the line *before* the hunk as start (see `diff_hunks`).
+        let (start, len) = match side {
+            DiffSide::Old => (self.old_start, self.old_len),
+            DiffSide::New => (self.new_start, self.new_len),
+        };
+        if len == 0 {
+            start
+        } else {
+            start - 1
+        }
+    }
+}
+
+/// Line-level diff of `old` against `new` (longest common subsequence).
+pub(crate) fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = old.split_inclusive('\n').collect();
+    let b: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut tags = Vec::with_capacity(a.len().max(b.len()));
+    tags.extend(std::iter::repeat_n(DiffTag::Equal, prefix));
+    tags.extend(lcs_tags(a_mid, b_mid));
+    tags.extend(std::iter::repeat_n(DiffTag::Equal, suffix));
+
+    let (mut i, mut j) = (0, 0);
+    tags.into_iter()
+        .map(|tag| {
+            let (old_line, new_line, text) = match tag {
+                DiffTag::Equal => {
+                    i += 1;
+                    j += 1;
+                    (Some(i), Some(j), a[i - 1])
+                }
+                DiffTag::Removed => {
+                    i += 1;
+                    (Some(i), None, a[i - 1])
+                }
+                DiffTag::Added => {
+                    j += 1;
+                    (None, Some(j), b[j - 1])
+                }
+            };
+            DiffLine {
+                tag,
+                old_line,
+                new_line,
+                text: text.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn lcs_tags(a: &[&str], b: &[&str]) -> Vec<DiffTag> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 || (n + 1) * (m + 1) > MAX_LCS_CELLS {
+        let mut tags = vec![DiffTag::Removed; n];
+        tags.extend(std::iter::repeat_n(DiffTag::Added, m));
+        return tags;
+    }
+
+    // len[i][j] = LCS length of a[i..] and b[j..]
+    let width = m + 1;
+    let mut len = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            len[i * width + j] = if a[i] == b[j] {
+                len[(i + 1) * width + j + 1] + 1
+            } else {
+                len[(i + 1) * width + j].max(len[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut tags = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            tags.push(DiffTag::Equal);
+            i += 1;
+            j += 1;
+        } else if len[(i + 1) * width + j] >= len[i * width + j + 1] {
+            tags.push(DiffTag::Removed);
+            i += 1;
+        } else {
+            tags.push(DiffTag::Added);
+            j += 1;
+        }
+    }
+    tags.extend(std::iter::repeat_n(DiffTag::Removed, n - i));
+    tags.extend(std::iter::repeat_n(DiffTag::Added, m - j));
+    tags
+}
+
+/// Group a diff into hunks with `context` unchanged lines around each change.
+pub(crate) fn diff_hunks(lines: &[DiffLine], context: usize) -> Vec<DiffHunk> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.tag != DiffTag::Equal)
+        .map(|(i, _)| i)
+        .collect();
+
+    // Ranges of `lines` to show, merged when their context overlaps.
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for idx in changed {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let slice = &lines[start..end];
+            // Line before the hunk on each side, so empty sides get the usual `-N,0` header.
+            let old_before = lines[..start].iter().filter(|l| l.tag != DiffTag::Added).count();
+            let new_before = lines[..start].iter().filter(|l| l.tag != DiffTag::Removed).count();
+            let old_len = slice.iter().filter(|l| l.tag != DiffTag::Added).count();
+            let new_len = slice.iter().filter(|l| l.tag != DiffTag::Removed).count();
+            DiffHunk {
+                old_start: old_before + usize::from(old_len > 0),
+                old_len,
+                new_start: new_before + usize::from(new_len > 0),
+                new_len,
+                lines: slice.to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Replace the other side of `hunk` in `buffer` with its `take` side.
+///
+/// The hunk is located by content (nearest match to its original position), so it still
+/// applies when the buffer was edited elsewhere. Fails if the buffer no longer contains
+/// the region, and returns `Ok(None)` if it already matches `take`.
+pub(crate) fn apply_hunk(buffer: &str, hunk: &DiffHunk, take: DiffSide) -> Result<Option<String>> {
+    let from_side = match take {
+        DiffSide::Old => DiffSide::New,
+        DiffSide::New => DiffSide::Old,
+    };
+    let from = hunk.side_lines(from_side);
+    let to = hunk.side_lines(take);
+    let lines: Vec<&str> = buffer.split_inclusive('\n').collect();
+
+    if from.is_empty() && !to.is_empty() && find_block(&lines, &to, hunk.side_start(take)).is_some() {
+        return Ok(None);
+    }
+    let Some(at) = find_block(&lines, &from, hunk.side_start(from_side)) else {
+        if find_block(&lines, &to, hunk.side_start(take)).is_some() {
+            return Ok(None);
+        }
+        anyhow::bail!("the editor no longer contains the lines around this change");
+    };
+    if from == to {
+        return Ok(None);
+    }
+
+    let mut out = String::with_capacity(buffer.len());
+    for line in &lines[..at] {
+        out.push_str(line);
+    }
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    let rest = &lines[at + from.len()..];
+    for line in &to {
+        out.push_str(line);
+    }
+    if !rest.is_empty() && !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    for line in rest {
+        out.push_str(line);
+    }
+    Ok(Some(out))
+}
+
+/// Index where `block` occurs in `lines` (ignoring line endings), closest to `near`.
+fn find_block(lines: &[&str], block: &[&str], near: usize) -> Option<usize> {
+    let eol = |s: &str| s.trim_end_matches(['\n', '\r']).to_string();
+    if block.is_empty() {
+        return Some(near.min(lines.len()));
+    }
+    if block.len() > lines.len() {
+        return None;
+    }
+    (0..=lines.len() - block.len())
+        .filter(|&i| {
+            lines[i..i + block.len()]
+                .iter()
+                .zip(block)
+                .all(|(l, b)| eol(l) == eol(b))
+        })
+        .min_by_key(|&i| i.abs_diff(near))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(lines: &[DiffLine]) -> Vec<DiffTag> {
+        lines.iter().map(|l| l.tag).collect()
+    }
+
+    fn hunks(old: &str, new: &str, context: usize) -> Vec<DiffHunk> {
+        diff_hunks(&diff_lines(old, new), context)
+    }
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+
+    #[test]
+    fn identical_texts_have_no_hunks() {
+        let lines = diff_lines(OLD, OLD);
+        assert!(lines.iter().all(|l| l.tag == DiffTag::Equal));
+        assert!(diff_hunks(&lines, DIFF_CONTEXT).is_empty());
+    }
+
+    #[test]
+    fn changed_line_numbers_both_sides() {
+        use DiffTag::*;
+        let lines = diff_lines("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(tags(&lines), [Equal, Removed, Added, Equal, Added]);
+        assert_eq!((lines[1].old_line, lines[1].new_line), (Some(2), None));
+        assert_eq!((lines[2].old_line, lines[2].new_line), (None, Some(2)));
+        assert_eq!((lines[3].old_line, lines[3].new_line), (Some(3), Some(3)));
+        assert_eq!(lines[4].content(), "d");
+    }
+
+    #[test]
+    fn missing_final_newline_is_a_change() {
+        use DiffTag::*;
+        let lines = diff_lines("a\nb", "a\nb\n");
+        assert_eq!(tags(&lines), [Equal, Removed, Added]);
+        assert_eq!(lines[1].text, "b");
+        assert_eq!(lines[2].text, "b\n");
+    }
+
+    #[test]
+    fn hunk_headers() {
+        let new = OLD.replace("e\n", "E\n");
+        let h = hunks(OLD, &new, DIFF_CONTEXT);
+        assert_eq!(h.len(), 1);
+        assert_eq!(h[0].header(), "@@ -2,7 +2,7 @@");
+
+        // Pure insertion / deletion without context: the empty side names the line before.
+        assert_eq!(hunks("a\nb\n", "x\na\nb\n", 0)[0].header(), "@@ -0,0 +1,1 @@");
+        assert_eq!(hunks("a\nb\n", "a\n", 0)[0].header(), "@@ -2,1 +1,0 @@");
+    }
+
+    #[test]
+    fn nearby_changes_share_a_hunk() {
+        let close = OLD.replace("c\n", "C\n").replace("f\n", "F\n");
+        assert_eq!(hunks(OLD, &close, DIFF_CONTEXT).len(), 1);
+        let far = OLD.replace("a\n", "A\n").replace("j\n", "J\n");
+        let h = hunks(OLD, &far, 1);
+        assert_eq!(h.iter().map(DiffHunk::header).collect::<Vec<_>>(), ["@@ -1,2 +1,2 @@", "@@ -9,2 +9,2 @@"]);
+    }
+
+    #[test]
+    fn apply_takes_either_side() {
+        let new = OLD.replace("e\n", "E\nE2\n");
+        let hunk = &hunks(OLD, &new, DIFF_CONTEXT)[0];
+        assert_eq!(apply_hunk(OLD, hunk, DiffSide::New).unwrap().as_deref(), Some(new.as_str()));
+        assert_eq!(apply_hunk(&new, hunk, DiffSide::Old).unwrap().as_deref(), Some(OLD));
+    }
+
+    #[test]
+    fn apply_finds_the_hunk_after_edits_elsewhere() {
+        let new = OLD.replace("h\n", "H\n");
+        let hunk = &hunks(OLD, &new, 1)[0];
+        let edited = format!("added\nlines\n{OLD}");
+        let applied = apply_hunk(&edited, hunk, DiffSide::New).unwrap().unwrap();
+        assert_eq!(applied, format!("added\nlines\n{new}"));
+    }
+
+    #[test]
+    fn apply_twice_is_a_no_op() {
+        let new = OLD.replace("e\n", "E\n");
+        let hunk = &hunks(OLD, &new, DIFF_CONTEXT)[0];
+        assert_eq!(apply_hunk(&new, hunk, DiffSide::New).unwrap(), None);
+
+        let inserted = "x\na\nb\n";
+        let hunk = &hunks("a\nb\n", inserted, 0)[0];
+        assert_eq!(apply_hunk(inserted, hunk, DiffSide::New).unwrap(), None);
+    }
+
+    #[test]
+    fn apply_fails_when_the_region_is_gone() {
+        let new = OLD.replace("e\n", "E\n");
+        let hunk = &hunks(OLD, &new, DIFF_CONTEXT)[0];
+        assert!(apply_hunk("something else\n", hunk, DiffSide::New).is_err());
+    }
+
+    #[test]
+    fn apply_keeps_lines_apart_without_final_newline() {
+        let hunk = &hunks("a\nb", "a\nb\nc", 0)[0];
+        assert_eq!(apply_hunk("a\nb", hunk, DiffSide::New).unwrap().as_deref(), Some("a\nb\nc"));
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+
+/// Search options of the editor's find bar.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct FindQuery {
+    pub pattern: String,
+    pub regex: bool,
+    pub match_case: bool,
+}
+
+impl FindQuery {
+    /// `None` for an empty pattern.
+    pub(crate) fn compile(&self) -> Result<Option<Regex>> {
+        if self.pattern.is_empty() {
+            return Ok(None);
+        }
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.match_case)
+            .multi_line(true)
+            .build()
+            .map(Some)
+            .context("invalid regular expression")
+    }
+
+    /// Replacement text; `$1`/`${name}` expand only in regex mode.
+    fn expand(&self, re: &Regex, text: &str, range: &Range<usize>, replacement: &str) -> String {
+        if !self.regex {
+            return replacement.to_string();
+        }
+        let mut out = String::new();
+        if let Some(caps) = re.captures_at(text, range.start) {
+            caps.expand(replacement, &mut out);
+        }
+        out
+    }
+}
+
+/// Byte ranges of the non-empty matches of `re` in `text`.
+pub(crate) fn find_matches(re: &Regex, text: &str) -> Vec<Range<usize>> {
+    re.find_iter(text)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.range())
+        .collect()
+}
+
+/// Replace the match at `range` (as returned by [`find_matches`]).
+pub(crate) fn replace_match(
+    query: &FindQuery,
+    re: &Regex,
+    text: &mut String,
+    range: Range<usize>,
+    replacement: &str,
+) {
+    let with = query.expand(re, text, &range, replacement);
+    text.replace_range(range, &with);
+}
+
+/// Replace every match (the same ones [`find_matches`] returns); returns how many were
+/// replaced.
+pub(crate) fn replace_all(query: &FindQuery, re: &Regex, text: &mut String, replacement: &str) -> usize {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for caps in re.captures_iter(text) {
+        let m = caps.get(0).expect("group 0 is always set");
+        if m.is_empty() {
+            continue;
+        }
+        out.push_str(&text[last..m.start()]);
+        if query.regex {
+            caps.expand(replacement, &mut out);
+        } else {
+            out.push_str(replacement);
+        }
+        last = m.end();
+        count += 1;
+    }
+    if count > 0 {
+        out.push_str(&text[last..]);
+        *text = out;
+    }
+    count
+}
+
+/// Char offset of 1-based `line`/`column` (clamped to the text).
+pub(crate) fn char_offset(text: &str, line: usize, column: usize) -> usize {
+    let mut offset = 0;
+    for (i, l) in text.split('\n').enumerate() {
+        if i + 1 == line.max(1) {
+            return offset + l.chars().count().min(column.saturating_sub(1));
+        }
+        offset += l.chars().count() + 1;
+    }
+    text.chars().count()
+}
+
+/// Char range of the 1-based `line`, without its line ending.
+pub(crate) fn line_char_range(text: &str, line: usize) -> Range<usize> {
+    let start = char_offset(text, line, 1);
+    let len = text
+        .split('\n')
+        .nth(line.max(1) - 1)
+        .map(|l| l.trim_end_matches('\r').chars().count())
+        .unwrap_or(0);
+    start..start + len
+}
+
+pub(crate) fn byte_to_char(text: &str, byte: usize) -> usize {
+    text.get(..byte).map(|t| t.chars().count()).unwrap_or_else(|| text.chars().count())
+}
+
+pub(crate) fn char_to_byte(text: &str, char_idx: usize) -> usize {
+    text.char_indices().nth(char_idx).map(|(b, _)| b).unwrap_or(text.len())
+}
@@ -0,0 +1,373 @@
+use std::ops::Range;
+
+use super::TemplateFile;
+
+/// What a span of source code is, for syntax colouring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Text,
+    Keyword,
+    String,
+    Comment,
+    Number,
+    /// HTML tag name or CSS selector.
+    Tag,
+    /// HTML attribute name or CSS property.
+    Attribute,
+    Punct,
+}
+
+/// A coloured span; `range` is in bytes. Spans are sorted and do not overlap; gaps are text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Token {
+    pub range: Range<usize>,
+    pub kind: TokenKind,
+}
+
+pub(crate) fn highlight(file: TemplateFile, text: &str) -> Vec<Token> {
+    let mut out = Vec::new();
+    match file {
+        TemplateFile::Html => highlight_html(text, 0, &mut out),
+        TemplateFile::Css => highlight_css(text, 0, &mut out),
+        TemplateFile::Js => highlight_js(text, 0, &mut out),
+    }
+    out
+}
+
+const JS_KEYWORDS: &[&str] = &[
+    "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete",
+    "do", "else", "export", "extends", "false", "finally", "for", "function", "if", "import", "in",
+    "instanceof", "let", "new", "null", "of", "return", "static", "super", "switch", "this", "throw", "true",
+    "try", "typeof", "undefined", "var", "void", "while", "with", "yield",
+];
+
+/// After these words a `/` starts a regular expression.
+const JS_REGEX_AFTER: &[&str] = &[
+    "return", "typeof", "instanceof", "in", "of", "new", "delete", "void", "throw", "case", "do", "else",
+    "yield", "await",
+];
+
+/// Byte length of the char at `i`.
+fn char_len(text: &str, i: usize) -> usize {
+    text[i..].chars().next().map(char::len_utf8).unwrap_or(1)
+}
+
+/// End of a quoted string starting at `start` (past the closing quote, or the line end).
+fn string_end(text: &str, start: usize, multiline: bool) -> usize {
+    let bytes = text.as_bytes();
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if !multiline => return i,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    text.len()
+}
+
+fn find_from(text: &str, start: usize, needle: &str) -> Option<usize> {
+    text.get(start..)?.find(needle).map(|i| start + i)
+}
+
+fn push(out: &mut Vec<Token>, offset: usize, range: Range<usize>, kind: TokenKind) {
+    if !range.is_empty() && kind != TokenKind::Text {
+        out.push(Token {
+            range: range.start + offset..range.end + offset,
+            kind,
+        });
+    }
+}
+
+fn highlight_js(text: &str, offset: usize, out: &mut Vec<Token>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut regex_allowed = true;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        if bytes[i..].starts_with(b"//") {
+            i = find_from(text, i, "\n").unwrap_or(text.len());
+            push(out, offset, start..i, TokenKind::Comment);
+            continue;
+        }
+        if bytes[i..].starts_with(b"/*") {
+            i = find_from(text, i + 2, "*/").map(|e| e + 2).unwrap_or(text.len());
+            push(out, offset, start..i, TokenKind::Comment);
+            continue;
+        }
+        match c {
+            b'"' | b'\'' | b'`' => {
+                i = string_end(text, i, c == b'`');
+                push(out, offset, start..i, TokenKind::String);
+                regex_allowed = false;
+            }
+            b'/' if regex_allowed => {
+                i += 1;
+                let mut in_class = false;
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    match bytes[i] {
+                        b'\\' => i += 1,
+                        b'[' => in_class = true,
+                        b']' => in_class = false,
+                        b'/' if !in_class => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                while i < bytes.len() && bytes[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                push(out, offset, start..i, TokenKind::String);
+                regex_allowed = false;
+            }
+            b'0'..=b'9' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.' || bytes[i] == b'_') {
+                    i += 1;
+                }
+                push(out, offset, start..i, TokenKind::Number);
+                regex_allowed = false;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' || c == b'$' || c >= 0x80 => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'$' || bytes[i] >= 0x80)
+                {
+                    i += 1;
+                }
+                let word = &text[start..i];
+                if JS_KEYWORDS.contains(&word) {
+                    push(out, offset, start..i, TokenKind::Keyword);
+                }
+                regex_allowed = JS_REGEX_AFTER.contains(&word);
+            }
+            _ => {
+                i += char_len(text, i);
+                push(out, offset, start..i, TokenKind::Punct);
+                regex_allowed = !matches!(c, b')' | b']');
+            }
+        }
+    }
+}
+
+fn highlight_css(text: &str, offset: usize, out: &mut Vec<Token>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    // Whether the current statement is a selector / at-rule prelude (ends in `{`) rather
+    // than a declaration, and whether we are past a declaration's `:`.
+    let mut selector = css_statement_is_selector(bytes, 0);
+    let mut in_value = false;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        if bytes[i..].starts_with(b"/*") {
+            i = find_from(text, i + 2, "*/").map(|e| e + 2).unwrap_or(text.len());
+            push(out, offset, start..i, TokenKind::Comment);
+            continue;
+        }
+        match c {
+            b'"' | b'\'' => {
+                i = string_end(text, i, false);
+                push(out, offset, start..i, TokenKind::String);
+            }
+            b'@' => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+                    i += 1;
+                }
+                push(out, offset, start..i, TokenKind::Keyword);
+            }
+            b'{' | b'}' | b';' => {
+                i += 1;
+                push(out, offset, start..i, TokenKind::Punct);
+                selector = css_statement_is_selector(bytes, i);
+                in_value = false;
+            }
+            b':' if !selector && !in_value => {
+                i += 1;
+                push(out, offset, start..i, TokenKind::Punct);
+                in_value = true;
+            }
+            b'0'..=b'9' | b'#' if in_value => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.' || bytes[i] == b'%') {
+                    i += 1;
+                }
+                push(out, offset, start..i, TokenKind::Number);
+            }
+            _ => {
+                let word_ends = |i: usize| {
+                    bytes[i].is_ascii_whitespace()
+                        || matches!(bytes[i], b'{' | b'}' | b';' | b'"' | b'\'')
+                        || (bytes[i] == b':' && !selector && !in_value)
+                        || bytes[i..].starts_with(b"/*")
+                };
+                while i < bytes.len() && !word_ends(i) {
+                    i += 1;
+                }
+                i = i.max(start + char_len(text, start));
+                let kind = if selector {
+                    TokenKind::Tag
+                } else if in_value {
+                    TokenKind::Text
+                } else {
+                    TokenKind::Attribute
+                };
+                push(out, offset, start..i, kind);
+            }
+        }
+    }
+}
+
+/// The statement starting at `from` ends in `{` (a selector or block at-rule).
+fn css_statement_is_selector(bytes: &[u8], from: usize) -> bool {
+    bytes[from..]
+        .iter()
+        .find(|b| matches!(b, b'{' | b'}' | b';'))
+        .is_some_and(|b| *b == b'{')
+}
+
+fn highlight_html(text: &str, offset: usize, out: &mut Vec<Token>) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        if bytes[i..].starts_with(b"<!--") {
+            i = find_from(text, i + 4, "-->").map(|e| e + 3).unwrap_or(text.len());
+            push(out, offset, start..i, TokenKind::Comment);
+            continue;
+        }
+        if bytes[i] != b'<' {
+            i = find_from(text, i, "<").unwrap_or(text.len());
+            continue;
+        }
+
+        // `<`, `</`, `<!`
+        i += 1;
+        if i < bytes.len() && matches!(bytes[i], b'/' | b'!' | b'?') {
+            i += 1;
+        }
+        push(out, offset, start..i, TokenKind::Punct);
+        let name_start = i;
+        while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+            i += 1;
+        }
+        let name = text[name_start..i].to_ascii_lowercase();
+        push(out, offset, name_start..i, TokenKind::Tag);
+        let closing = text[start..].starts_with("</");
+
+        // Attributes up to `>`.
+        while i < bytes.len() && bytes[i] != b'>' {
+            let s = i;
+            match bytes[i] {
+                b'"' | b'\'' => {
+                    let q = bytes[i];
+                    i = find_from(text, i + 1, if q == b'"' { "\"" } else { "'" })
+                        .map(|e| e + 1)
+                        .unwrap_or(text.len());
+                    push(out, offset, s..i, TokenKind::String);
+                }
+                b'=' | b'/' => {
+                    i += 1;
+                    push(out, offset, s..i, TokenKind::Punct);
+                }
+                c if c.is_ascii_whitespace() => i += 1,
+                _ => {
+                    while i < bytes.len()
+                        && !bytes[i].is_ascii_whitespace()
+                        && !matches!(bytes[i], b'=' | b'>' | b'"' | b'\'')
+                        && !bytes[i..].starts_with(b"/>")
+                    {
+                        i += 1;
+                    }
+                    i = i.max(s + char_len(text, s));
+                    push(out, offset, s..i, TokenKind::Attribute);
+                }
+            }
+        }
+        if i < bytes.len() {
+            push(out, offset, i..i + 1, TokenKind::Punct);
+            i += 1;
+        }
+
+        // Embedded script / style.
+        if !closing && (name == "script" || name == "style") {
+            let end_tag = format!("</{name}");
+            let end = text[i..]
+                .to_ascii_lowercase()
+                .find(&end_tag)
+                .map(|e| i + e)
+                .unwrap_or(text.len());
+            if name == "script" {
+                highlight_js(&text[i..end], offset + i, out);
+            } else {
+                highlight_css(&text[i..end], offset + i, out);
+            }
+            i = end;
+        }
+    }
+}
+
+/// Brackets inside strings and comments are skipped when matching.
+fn in_literal(tokens: &[Token], byte: usize) -> bool {
+    let idx = tokens.partition_point(|t| t.range.end <= byte);
+    tokens
+        .get(idx)
+        .is_some_and(|t| t.range.contains(&byte) && matches!(t.kind, TokenKind::String | TokenKind::Comment))
+}
+
+/// Byte offsets of the bracket next to `cursor` (a byte offset; the char before it is
+/// checked first) and its partner.
+pub(crate) fn matching_bracket(text: &str, tokens: &[Token], cursor: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let candidates = [cursor.checked_sub(1), Some(cursor)];
+    for at in candidates.into_iter().flatten() {
+        let Some(&c) = bytes.get(at) else {
+            continue;
+        };
+        if in_literal(tokens, at) {
+            continue;
+        }
+        let (open, close, forward) = match c {
+            b'(' => (b'(', b')', true),
+            b'[' => (b'[', b']', true),
+            b'{' => (b'{', b'}', true),
+            b')' => (b'(', b')', false),
+            b']' => (b'[', b']', false),
+            b'}' => (b'{', b'}', false),
+            _ => continue,
+        };
+        let mut depth = 0usize;
+        let positions: Box<dyn Iterator<Item = usize>> = if forward {
+            Box::new(at..bytes.len())
+        } else {
+            Box::new((0..=at).rev())
+        };
+        for i in positions {
+            let b = bytes[i];
+            if (b != open && b != close) || in_literal(tokens, i) {
+                continue;
+            }
+            if (b == open) == forward {
+                depth += 1;
+            } else {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((at, i));
+                }
+            }
+        }
+        return None;
+    }
+    None
+}
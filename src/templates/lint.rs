@@ -0,0 +1,895 @@
+use super::{TemplateFile, TemplateSources};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LintSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem found by [`lint_template`]. Lines and columns are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LintDiagnostic {
+    pub file: TemplateFile,
+    pub line: usize,
+    pub column: usize,
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+impl LintDiagnostic {
+    fn new(file: TemplateFile, at: Pos, severity: LintSeverity, message: impl Into<String>) -> Self {
+        Self {
+            file,
+            line: at.line,
+            column: at.column,
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+pub(crate) fn lint_has_errors(diagnostics: &[LintDiagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == LintSeverity::Error)
+}
+
+/// Cheap structural checks that catch overlays OBS would render broken: unbalanced HTML
+/// tags, missing `style.css`/`overlay.js` references, CSS syntax errors and unbalanced or
+/// unterminated JS tokens. Not a full parser; valid code should never be flagged.
+pub(crate) fn lint_template(sources: &TemplateSources) -> Vec<LintDiagnostic> {
+    let mut out = lint_html(sources);
+    out.extend(lint_css(&sources.style_css));
+    out.extend(lint_js(&sources.overlay_js));
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Pos {
+    line: usize,
+    column: usize,
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    idx: usize,
+    at: Pos,
+}
+
+impl Scanner {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            idx: 0,
+            at: Pos { line: 1, column: 1 },
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.idx).copied()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.idx + n).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    fn starts_with_ignore_case(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i).is_some_and(|p| p.eq_ignore_ascii_case(&c)))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.idx += 1;
+        if c == '\n' {
+            self.at.line += 1;
+            self.at.column = 1;
+        } else {
+            self.at.column += 1;
+        }
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if !self.starts_with(s) {
+            return false;
+        }
+        for _ in s.chars() {
+            self.bump();
+        }
+        true
+    }
+
+    /// Advance past the next `s`; `false` (at the end) if there is none.
+    fn skip_past(&mut self, s: &str) -> bool {
+        while self.peek().is_some() {
+            if self.eat(s) {
+                return true;
+            }
+            self.bump();
+        }
+        false
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.peek().filter(|c| f(*c)) {
+            out.push(c);
+            self.bump();
+        }
+        out
+    }
+}
+
+// -------------------------------
+// HTML
+// -------------------------------
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track",
+    "wbr",
+];
+/// Elements whose end tag may be left out.
+const OPTIONAL_END: &[&str] = &[
+    "html", "head", "body", "p", "li", "dt", "dd", "option", "optgroup", "tr", "td", "th", "thead", "tbody",
+    "tfoot", "colgroup", "caption", "rt", "rp",
+];
+/// Elements whose content is not markup.
+const RAW_TEXT: &[&str] = &["script", "style", "textarea", "title"];
+
+/// `value` points at `file` (`style.css`, `./style.css?v=2`, `https://…/style.css`).
+fn references(value: &str, file: &str) -> bool {
+    let path = value.split(['?', '#']).next().unwrap_or_default().trim();
+    path == file || path.ends_with(&format!("/{file}"))
+}
+
+fn lint_html(sources: &TemplateSources) -> Vec<LintDiagnostic> {
+    let file = TemplateFile::Html;
+    let mut out = Vec::new();
+    let mut s = Scanner::new(&sources.index_html);
+    let mut open: Vec<(String, Pos)> = Vec::new();
+    let mut linked_css = false;
+    let mut linked_js = false;
+
+    while let Some(c) = s.peek() {
+        let start = s.at;
+        if s.starts_with("<!--") {
+            if !s.skip_past("-->") {
+                out.push(LintDiagnostic::new(file, start, LintSeverity::Error, "comment is never closed"));
+            }
+            continue;
+        }
+        if s.starts_with("<!") || s.starts_with("<?") {
+            s.skip_past(">");
+            continue;
+        }
+        if s.starts_with("</") && s.peek_at(2).is_some_and(|c| c.is_ascii_alphabetic()) {
+            s.eat("</");
+            let name = s.take_while(|c| c.is_ascii_alphanumeric() || c == '-').to_ascii_lowercase();
+            if !s.skip_past(">") {
+                out.push(LintDiagnostic::new(file, start, LintSeverity::Error, format!("</{name} is never closed with '>'")));
+                break;
+            }
+            match open.iter().rposition(|(n, _)| *n == name) {
+                Some(i) => {
+                    for (unclosed, at) in open.drain(i..).skip(1) {
+                        if !OPTIONAL_END.contains(&unclosed.as_str()) {
+                            out.push(LintDiagnostic::new(
+                                file,
+                                at,
+                                LintSeverity::Error,
+                                format!("<{unclosed}> is not closed before </{name}>"),
+                            ));
+                        }
+                    }
+                }
+                None if VOID_ELEMENTS.contains(&name.as_str()) || OPTIONAL_END.contains(&name.as_str()) => {}
+                None => out.push(LintDiagnostic::new(
+                    file,
+                    start,
+                    LintSeverity::Error,
+                    format!("</{name}> has no matching <{name}>"),
+                )),
+            }
+            continue;
+        }
+        if c == '<' && s.peek_at(1).is_some_and(|c| c.is_ascii_alphabetic()) {
+            s.bump();
+            let name = s.take_while(|c| c.is_ascii_alphanumeric() || c == '-').to_ascii_lowercase();
+            let mut attrs: Vec<(String, String)> = Vec::new();
+            let mut self_closing = false;
+            let mut closed = false;
+            while let Some(c) = s.peek() {
+                if c == '>' {
+                    s.bump();
+                    closed = true;
+                    break;
+                }
+                if s.eat("/>") {
+                    self_closing = true;
+                    closed = true;
+                    break;
+                }
+                if c.is_whitespace() || c == '/' {
+                    s.bump();
+                    continue;
+                }
+                let attr = s
+                    .take_while(|c| !c.is_whitespace() && !matches!(c, '=' | '>' | '/' | '"' | '\''))
+                    .to_ascii_lowercase();
+                if attr.is_empty() {
+                    // A stray quote; skip it so the loop keeps moving.
+                    s.bump();
+                    continue;
+                }
+                s.take_while(char::is_whitespace);
+                let mut value = String::new();
+                if s.eat("=") {
+                    s.take_while(char::is_whitespace);
+                    match s.peek() {
+                        Some(q @ ('"' | '\'')) => {
+                            let value_start = s.at;
+                            s.bump();
+                            value = s.take_while(|c| c != q);
+                            if s.bump().is_none() {
+                                out.push(LintDiagnostic::new(
+                                    file,
+                                    value_start,
+                                    LintSeverity::Error,
+                                    format!("attribute {attr} has an unclosed quote"),
+                                ));
+                            }
+                        }
+                        _ => value = s.take_while(|c| !c.is_whitespace() && c != '>'),
+                    }
+                }
+                attrs.push((attr, value));
+            }
+            if !closed {
+                out.push(LintDiagnostic::new(
+                    file,
+                    start,
+                    LintSeverity::Error,
+                    format!("<{name} is never closed with '>'"),
+                ));
+                break;
+            }
+
+            let attr = |n: &str| attrs.iter().find(|(a, _)| a == n).map(|(_, v)| v.as_str());
+            if name == "link" && attr("href").is_some_and(|v| references(v, TemplateFile::Css.file_name())) {
+                linked_css = true;
+            }
+            if name == "script" && attr("src").is_some_and(|v| references(v, TemplateFile::Js.file_name())) {
+                linked_js = true;
+            }
+
+            if self_closing || VOID_ELEMENTS.contains(&name.as_str()) {
+                continue;
+            }
+            open.push((name.clone(), start));
+            if RAW_TEXT.contains(&name.as_str()) {
+                let end = format!("</{name}");
+                while s.peek().is_some() && !s.starts_with_ignore_case(&end) {
+                    s.bump();
+                }
+            }
+            continue;
+        }
+        s.bump();
+    }
+
+    for (name, at) in open {
+        if !OPTIONAL_END.contains(&name.as_str()) {
+            out.push(LintDiagnostic::new(file, at, LintSeverity::Error, format!("<{name}> is never closed")));
+        }
+    }
+
+    let top = Pos { line: 1, column: 1 };
+    if !sources.style_css.trim().is_empty() && !linked_css {
+        out.push(LintDiagnostic::new(
+            file,
+            top,
+            LintSeverity::Warning,
+            "style.css is not linked; add <link rel=\"stylesheet\" href=\"style.css\">",
+        ));
+    }
+    if !sources.overlay_js.trim().is_empty() && !linked_js {
+        out.push(LintDiagnostic::new(
+            file,
+            top,
+            LintSeverity::Warning,
+            "overlay.js is not loaded; add <script src=\"overlay.js\"></script>",
+        ));
+    }
+    out
+}
+
+// -------------------------------
+// CSS
+// -------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CssBlock {
+    /// Top level, `@media`, `@supports`, `@keyframes`, …: contains rules.
+    Rules,
+    /// Style rule or `@font-face`/`@page`: contains declarations (and nested rules).
+    Declarations,
+}
+
+/// At-rules whose block contains rules rather than declarations.
+const CSS_GROUP_RULES: &[&str] = &[
+    "media",
+    "supports",
+    "container",
+    "layer",
+    "document",
+    "-moz-document",
+    "scope",
+    "starting-style",
+    "keyframes",
+    "-webkit-keyframes",
+    "-moz-keyframes",
+];
+
+fn check_declaration(text: &str, at: Pos, out: &mut Vec<LintDiagnostic>) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let error = |msg: String| LintDiagnostic::new(TemplateFile::Css, at, LintSeverity::Error, msg);
+    let Some((property, value)) = text.split_once(':') else {
+        out.push(error(format!("expected ':' in \"{}\"", short(text))));
+        return;
+    };
+    let property = property.trim();
+    if property.is_empty() {
+        out.push(error("missing property name before ':'".to_string()));
+    } else if !property
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        out.push(error(format!("invalid property name \"{}\"", short(property))));
+    } else if value.trim().is_empty() && !property.starts_with("--") {
+        out.push(error(format!("missing value for {property}")));
+    }
+}
+
+fn short(text: &str) -> String {
+    const MAX: usize = 40;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX {
+        text
+    } else {
+        format!("{}…", text.chars().take(MAX).collect::<String>())
+    }
+}
+
+fn lint_css(css: &str) -> Vec<LintDiagnostic> {
+    let file = TemplateFile::Css;
+    let mut out = Vec::new();
+    let mut s = Scanner::new(css);
+    let mut blocks: Vec<(CssBlock, Pos)> = Vec::new();
+    let mut parens: Vec<(char, Pos)> = Vec::new();
+    // Current selector, at-rule prelude or declaration, and where it starts.
+    let mut segment = String::new();
+    let mut segment_at = s.at;
+
+    while let Some(c) = s.peek() {
+        let at = s.at;
+        if s.starts_with("/*") {
+            if !s.skip_past("*/") {
+                out.push(LintDiagnostic::new(file, at, LintSeverity::Error, "comment is never closed"));
+            }
+            continue;
+        }
+        if segment.trim().is_empty() && !c.is_whitespace() {
+            segment_at = at;
+        }
+        let context = blocks.last().map(|b| b.0).unwrap_or(CssBlock::Rules);
+        match c {
+            '"' | '\'' => {
+                s.bump();
+                loop {
+                    match s.peek() {
+                        None | Some('\n') => {
+                            out.push(LintDiagnostic::new(file, at, LintSeverity::Error, "string is never closed"));
+                            break;
+                        }
+                        Some('\\') => {
+                            s.bump();
+                            s.bump();
+                        }
+                        Some(q) if q == c => {
+                            s.bump();
+                            break;
+                        }
+                        Some(_) => {
+                            s.bump();
+                        }
+                    }
+                }
+                segment.push_str("\"\"");
+                continue;
+            }
+            '(' | '[' => parens.push((c, at)),
+            ')' | ']' => {
+                let expected = if c == ')' { '(' } else { '[' };
+                if parens.pop().map(|p| p.0) != Some(expected) {
+                    out.push(LintDiagnostic::new(file, at, LintSeverity::Error, format!("unexpected '{c}'")));
+                    parens.clear();
+                }
+            }
+            // `;` and `{` inside parentheses belong to the value, e.g. `url(data:…;base64,…)`.
+            ';' | '{' if !parens.is_empty() => {}
+            '}' | '{' | ';' => {
+                for (p, p_at) in parens.drain(..) {
+                    out.push(LintDiagnostic::new(file, p_at, LintSeverity::Error, format!("'{p}' is never closed")));
+                }
+                match (c, context) {
+                    ('{', _) => {
+                        let prelude = segment.trim();
+                        let kind = if let Some(at_rule) = prelude.strip_prefix('@') {
+                            let name = at_rule
+                                .split(|c: char| c.is_whitespace() || c == '(')
+                                .next()
+                                .unwrap_or_default()
+                                .to_ascii_lowercase();
+                            if CSS_GROUP_RULES.contains(&name.as_str()) {
+                                CssBlock::Rules
+                            } else {
+                                CssBlock::Declarations
+                            }
+                        } else {
+                            if prelude.is_empty() && context == CssBlock::Rules {
+                                out.push(LintDiagnostic::new(file, at, LintSeverity::Error, "missing selector before '{'"));
+                            }
+                            CssBlock::Declarations
+                        };
+                        blocks.push((kind, at));
+                    }
+                    (';', CssBlock::Declarations) => check_declaration(&segment, segment_at, &mut out),
+                    (';', CssBlock::Rules) => {
+                        let prelude = segment.trim();
+                        if !prelude.is_empty() && !prelude.starts_with('@') {
+                            out.push(LintDiagnostic::new(
+                                file,
+                                segment_at,
+                                LintSeverity::Error,
+                                format!("expected '{{' after \"{}\"", short(prelude)),
+                            ));
+                        }
+                    }
+                    (_, _) => {
+                        match context {
+                            CssBlock::Declarations => check_declaration(&segment, segment_at, &mut out),
+                            CssBlock::Rules if !segment.trim().is_empty() => out.push(LintDiagnostic::new(
+                                file,
+                                segment_at,
+                                LintSeverity::Error,
+                                format!("expected '{{' after \"{}\"", short(&segment)),
+                            )),
+                            CssBlock::Rules => {}
+                        }
+                        if blocks.pop().is_none() {
+                            out.push(LintDiagnostic::new(file, at, LintSeverity::Error, "unexpected '}'"));
+                        }
+                    }
+                }
+                segment.clear();
+                s.bump();
+                continue;
+            }
+            _ => {}
+        }
+        segment.push(c);
+        s.bump();
+    }
+
+    for (p, at) in parens {
+        out.push(LintDiagnostic::new(file, at, LintSeverity::Error, format!("'{p}' is never closed")));
+    }
+    if !segment.trim().is_empty() {
+        match blocks.last().map(|b| b.0) {
+            Some(CssBlock::Declarations) => check_declaration(&segment, segment_at, &mut out),
+            _ => out.push(LintDiagnostic::new(
+                file,
+                segment_at,
+                LintSeverity::Error,
+                format!("expected '{{' after \"{}\"", short(&segment)),
+            )),
+        }
+    }
+    for (_, at) in blocks {
+        out.push(LintDiagnostic::new(file, at, LintSeverity::Error, "'{' is never closed"));
+    }
+    out
+}
+
+// -------------------------------
+// JavaScript
+// -------------------------------
+
+/// After these words a `/` starts a regular expression rather than a division.
+const JS_REGEX_KEYWORDS: &[&str] = &[
+    "return", "typeof", "instanceof", "in", "of", "new", "delete", "void", "throw", "case", "do", "else",
+    "yield", "await",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsOpen {
+    Paren,
+    Bracket,
+    Brace,
+    /// `${` inside a template literal; the position is the literal's backtick.
+    TemplateExpr,
+}
+
+impl JsOpen {
+    fn char(self) -> char {
+        match self {
+            JsOpen::Paren => '(',
+            JsOpen::Bracket => '[',
+            JsOpen::Brace | JsOpen::TemplateExpr => '{',
+        }
+    }
+}
+
+/// Scan template literal text up to the closing backtick (`true`) or a `${` (`false`).
+fn js_template_chunk(s: &mut Scanner, start: Pos) -> Result<bool, LintDiagnostic> {
+    loop {
+        match s.peek() {
+            None => {
+                return Err(LintDiagnostic::new(
+                    TemplateFile::Js,
+                    start,
+                    LintSeverity::Error,
+                    "template literal is never closed",
+                ))
+            }
+            Some('\\') => {
+                s.bump();
+                s.bump();
+            }
+            Some('`') => {
+                s.bump();
+                return Ok(true);
+            }
+            Some('$') if s.peek_at(1) == Some('{') => {
+                s.eat("${");
+                return Ok(false);
+            }
+            Some(_) => {
+                s.bump();
+            }
+        }
+    }
+}
+
+fn lint_js(js: &str) -> Vec<LintDiagnostic> {
+    match scan_js(js) {
+        Ok(()) => vec![],
+        Err(d) => vec![d],
+    }
+}
+
+/// Stops at the first problem; later errors are usually follow-ups of the first.
+fn scan_js(js: &str) -> Result<(), LintDiagnostic> {
+    let file = TemplateFile::Js;
+    let error = |at: Pos, msg: String| LintDiagnostic::new(file, at, LintSeverity::Error, msg);
+    let mut s = Scanner::new(js);
+    let mut open: Vec<(JsOpen, Pos)> = Vec::new();
+    // Whether a `/` here would start a regular expression.
+    let mut regex_allowed = true;
+
+    if s.starts_with("#!") {
+        s.skip_past("\n");
+    }
+
+    while let Some(c) = s.peek() {
+        let at = s.at;
+        if c.is_whitespace() {
+            s.bump();
+            continue;
+        }
+        if s.starts_with("//") {
+            s.skip_past("\n");
+            continue;
+        }
+        if s.starts_with("/*") {
+            if !s.skip_past("*/") {
+                return Err(error(at, "comment is never closed".to_string()));
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                s.bump();
+                loop {
+                    match s.peek() {
+                        None | Some('\n') => return Err(error(at, "string is never closed".to_string())),
+                        Some('\\') => {
+                            s.bump();
+                            s.bump();
+                        }
+                        Some(q) if q == c => {
+                            s.bump();
+                            break;
+                        }
+                        Some(_) => {
+                            s.bump();
+                        }
+                    }
+                }
+                regex_allowed = false;
+            }
+            '`' => {
+                s.bump();
+                if !js_template_chunk(&mut s, at)? {
+                    open.push((JsOpen::TemplateExpr, at));
+                    regex_allowed = true;
+                } else {
+                    regex_allowed = false;
+                }
+            }
+            '/' if regex_allowed => {
+                s.bump();
+                let mut in_class = false;
+                loop {
+                    match s.peek() {
+                        None | Some('\n') => {
+                            return Err(error(at, "regular expression is never closed".to_string()))
+                        }
+                        Some('\\') => {
+                            s.bump();
+                            s.bump();
+                        }
+                        Some('[') => {
+                            in_class = true;
+                            s.bump();
+                        }
+                        Some(']') => {
+                            in_class = false;
+                            s.bump();
+                        }
+                        Some('/') if !in_class => {
+                            s.bump();
+                            break;
+                        }
+                        Some(_) => {
+                            s.bump();
+                        }
+                    }
+                }
+                s.take_while(|c| c.is_ascii_alphabetic());
+                regex_allowed = false;
+            }
+            '(' | '[' | '{' => {
+                s.bump();
+                let kind = match c {
+                    '(' => JsOpen::Paren,
+                    '[' => JsOpen::Bracket,
+                    _ => JsOpen::Brace,
+                };
+                open.push((kind, at));
+                regex_allowed = true;
+            }
+            ')' | ']' | '}' => {
+                s.bump();
+                let Some((kind, open_at)) = open.pop() else {
+                    return Err(error(at, format!("unexpected '{c}'")));
+                };
+                if kind.char() != matching_open(c) {
+                    return Err(error(
+                        at,
+                        format!(
+                            "'{c}' does not match '{}' opened on line {}",
+                            kind.char(),
+                            open_at.line
+                        ),
+                    ));
+                }
+                if kind == JsOpen::TemplateExpr {
+                    if !js_template_chunk(&mut s, open_at)? {
+                        open.push((JsOpen::TemplateExpr, open_at));
+                        regex_allowed = true;
+                        continue;
+                    }
+                    regex_allowed = false;
+                } else {
+                    // `}` usually ends a block, after which a regex may start a statement.
+                    regex_allowed = c == '}';
+                }
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let word = s.take_while(|c| c.is_alphanumeric() || c == '_' || c == '$');
+                regex_allowed = JS_REGEX_KEYWORDS.contains(&word.as_str());
+            }
+            // `++`/`--` leave the state alone: postfix (`i++ / 2`) follows an operand, prefix
+            // (`++i`) still expects one.
+            '+' | '-' if s.peek_at(1) == Some(c) => {
+                s.bump();
+                s.bump();
+            }
+            _ => {
+                s.bump();
+                regex_allowed = true;
+            }
+        }
+    }
+
+    match open.pop() {
+        Some((JsOpen::TemplateExpr, at)) => Err(error(at, "template literal is never closed".to_string())),
+        Some((kind, at)) => Err(error(at, format!("'{}' is never closed", kind.char()))),
+        None => Ok(()),
+    }
+}
+
+fn matching_open(close: char) -> char {
+    match close {
+        ')' => '(',
+        ']' => '[',
+        _ => '{',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(html: &str, css: &str, js: &str) -> TemplateSources {
+        TemplateSources {
+            index_html: html.to_string(),
+            style_css: css.to_string(),
+            overlay_js: js.to_string(),
+        }
+    }
+
+    fn messages(diagnostics: Vec<LintDiagnostic>) -> Vec<String> {
+        diagnostics.into_iter().map(|d| d.message).collect()
+    }
+
+    fn js(code: &str) -> Vec<String> {
+        messages(lint_js(code))
+    }
+
+    fn css(code: &str) -> Vec<String> {
+        messages(lint_css(code))
+    }
+
+    fn html(code: &str) -> Vec<String> {
+        messages(lint_html(&sources(code, "", "")))
+    }
+
+    #[test]
+    fn division_is_not_a_regex() {
+        for code in [
+            "const a = (b + c) / 2;",
+            "const a = list[0] / 2;",
+            "const a = b / c / d;",
+            "const a = 10 / 4;",
+            "const a = this.width / 2;",
+            "i++ / 2;",
+            "x-- / y;",
+            "a = b++ / c-- / d;",
+            "const n = ++i / 2;",
+        ] {
+            assert_eq!(js(code), Vec::<String>::new(), "{code}");
+        }
+    }
+
+    #[test]
+    fn regex_after_operators_and_keywords() {
+        for code in [
+            "const re = /a\\/b[/]/gi;",
+            "if (/^!\\w+/.test(msg)) {}",
+            "function f() { return /x/; }",
+            "const parts = [/a/, /b/];",
+            "x = y ? /a/ : /b/;",
+            "const n = ++/a/.lastIndex;",
+        ] {
+            assert_eq!(js(code), Vec::<String>::new(), "{code}");
+        }
+    }
+
+    #[test]
+    fn template_literals_comments_and_strings() {
+        for code in [
+            "const s = `a ${b / 2} ${`nested ${c}`} done`;",
+            "const s = `{ ( [`;",
+            "const s = `line\nbreak \\` still`;",
+            "// a ( comment\nconst a = 1;",
+            "/* ( [ { */ const a = '(' + \"[\";",
+            "#!/usr/bin/env node\nconst a = 1;",
+        ] {
+            assert_eq!(js(code), Vec::<String>::new(), "{code}");
+        }
+    }
+
+    #[test]
+    fn js_errors() {
+        assert_eq!(js("const s = \"abc;"), ["string is never closed"]);
+        assert_eq!(js("const s = `abc"), ["template literal is never closed"]);
+        assert_eq!(js("const s = `${a"), ["template literal is never closed"]);
+        assert_eq!(js("/* abc"), ["comment is never closed"]);
+        assert_eq!(js("const r = /abc"), ["regular expression is never closed"]);
+        assert_eq!(js("foo())"), ["unexpected ')'"]);
+        assert_eq!(js("foo(\n]"), ["']' does not match '(' opened on line 1"]);
+        assert_eq!(js("foo("), ["'(' is never closed"]);
+        assert_eq!(js("if (a) {"), ["'{' is never closed"]);
+    }
+
+    #[test]
+    fn js_error_position() {
+        let d = lint_js("const a = 1;\n  foo(");
+        assert_eq!((d[0].line, d[0].column), (2, 6));
+        assert_eq!(d[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn valid_css_is_clean() {
+        let code = "@import url(\"a.css\");\n\
+                    :root { --gap: ; --accent: #f0f; }\n\
+                    .a, .b > c[data-x=\"1\"] { color: red; background: url(data:image/png;base64,AAA=) }\n\
+                    @media (max-width: 600px) { .a { margin: 0 } }\n\
+                    @keyframes pop { from { opacity: 0 } to { opacity: 1 } }\n\
+                    @font-face { font-family: X; src: url('x.woff2'); }\n\
+                    /* { */";
+        assert_eq!(css(code), Vec::<String>::new());
+    }
+
+    #[test]
+    fn css_errors() {
+        assert_eq!(css("/* abc"), ["comment is never closed"]);
+        assert_eq!(css(".a { content: \"abc; }"), ["string is never closed", "'{' is never closed"]);
+        assert_eq!(css(".a { color: red; }}"), ["unexpected '}'"]);
+        assert_eq!(css(".a { color: red)"), ["unexpected ')'", "'{' is never closed"]);
+        assert_eq!(css(".a { width: calc(1px + 2px; }"), ["'(' is never closed"]);
+        assert_eq!(css("{ color: red }"), ["missing selector before '{'"]);
+        assert_eq!(css(".a { color red; }"), ["expected ':' in \"color red\""]);
+        assert_eq!(css(".a { : red; }"), ["missing property name before ':'"]);
+        assert_eq!(css(".a { co lor: red; }"), ["invalid property name \"co lor\""]);
+        assert_eq!(css(".a { color: ; }"), ["missing value for color"]);
+        assert_eq!(css(".a color: red; }"), ["expected '{' after \".a color: red\"", "unexpected '}'"]);
+        assert_eq!(css(".a { color: red;"), ["'{' is never closed"]);
+    }
+
+    #[test]
+    fn valid_html_is_clean() {
+        let code = "<!doctype html>\n<html><head>\n\
+                    <meta charset=\"utf-8\"><link rel=\"stylesheet\" href=\"./style.css?v=2\">\n\
+                    <style>p > a { color: red }</style>\n\
+                    </head><body>\n<!-- <div> -->\n\
+                    <ul><li>one<li>two</ul><p>text<br/><img src=x>\n\
+                    <script>if (a < b && c > d) {}</script>\n\
+                    <script src=\"overlay.js\"></script>\n</body></html>";
+        let diagnostics = lint_template(&sources(code, "p {}", "const a = 1;"));
+        assert_eq!(messages(diagnostics), Vec::<String>::new());
+    }
+
+    #[test]
+    fn html_errors() {
+        assert_eq!(html("<!-- abc"), ["comment is never closed"]);
+        assert_eq!(html("<div></div"), ["</div is never closed with '>'", "<div> is never closed"]);
+        assert_eq!(html("<div"), ["<div is never closed with '>'"]);
+        assert_eq!(html("<div><span></div>"), ["<span> is not closed before </div>"]);
+        assert_eq!(html("</div>"), ["</div> has no matching <div>"]);
+        assert_eq!(html("<div>"), ["<div> is never closed"]);
+        assert_eq!(html("<div class=\"a></div>"), ["attribute class has an unclosed quote", "<div is never closed with '>'"]);
+    }
+
+    #[test]
+    fn unlinked_files_are_warnings() {
+        let diagnostics = lint_template(&sources("<p>hi</p>", "p {}", "const a = 1;"));
+        assert!(!lint_has_errors(&diagnostics));
+        assert_eq!(
+            messages(diagnostics),
+            [
+                "style.css is not linked; add <link rel=\"stylesheet\" href=\"style.css\">",
+                "overlay.js is not loaded; add <script src=\"overlay.js\"></script>",
+            ]
+        );
+    }
+}
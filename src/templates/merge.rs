@@ -0,0 +1,267 @@
+use super::{diff_lines, DiffTag};
+
+/// One side's replacement of base lines `start..end` (empty range = insertion).
+#[derive(Debug, Clone)]
+struct Change {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+}
+
+/// Which version to keep for a conflicting region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConflictChoice {
+    Local,
+    Remote,
+    /// Local lines followed by remote lines.
+    Both,
+}
+
+/// A region of a three-way merge. Lines keep their line endings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MergeChunk {
+    /// Unchanged, or changed on one side only (or identically on both).
+    Resolved(Vec<String>),
+    Conflict {
+        base: Vec<String>,
+        local: Vec<String>,
+        remote: Vec<String>,
+        choice: Option<ConflictChoice>,
+    },
+}
+
+fn changes(base: &str, other: &str) -> Vec<Change> {
+    let mut out: Vec<Change> = Vec::new();
+    let mut base_pos = 0;
+    let mut open: Option<Change> = None;
+    for line in diff_lines(base, other) {
+        match line.tag {
+            DiffTag::Equal => {
+                out.extend(open.take());
+                base_pos += 1;
+            }
+            DiffTag::Removed => {
+                let change = open.get_or_insert_with(|| Change {
+                    start: base_pos,
+                    end: base_pos,
+                    lines: vec![],
+                });
+                base_pos += 1;
+                change.end = base_pos;
+            }
+            DiffTag::Added => {
+                open.get_or_insert_with(|| Change {
+                    start: base_pos,
+                    end: base_pos,
+                    lines: vec![],
+                })
+                .lines
+                .push(line.text);
+            }
+        }
+    }
+    out.extend(open);
+    out
+}
+
+/// `base[start..end]` with `changes` (all inside the range, sorted) applied.
+fn apply_changes(base: &[&str], start: usize, end: usize, changes: &[&Change]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pos = start;
+    for c in changes {
+        out.extend(base[pos..c.start].iter().map(|s| s.to_string()));
+        out.extend(c.lines.iter().cloned());
+        pos = c.end;
+    }
+    out.extend(base[pos..end].iter().map(|s| s.to_string()));
+    out
+}
+
+/// Line-based three-way merge of `local` and `remote`, both edited from `base`.
+pub(crate) fn merge3(base: &str, local: &str, remote: &str) -> Vec<MergeChunk> {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let mut all: Vec<(bool, Change)> = changes(base, local)
+        .into_iter()
+        .map(|c| (true, c))
+        .chain(changes(base, remote).into_iter().map(|c| (false, c)))
+        .collect();
+    all.sort_by_key(|(is_local, c)| (c.start, c.end, !is_local));
+
+    let mut chunks = Vec::new();
+    let mut pos = 0;
+    let mut i = 0;
+    while i < all.len() {
+        // Changes that overlap or touch form one region.
+        let start = all[i].1.start;
+        let mut end = all[i].1.end;
+        let mut j = i + 1;
+        while j < all.len() && all[j].1.start <= end {
+            end = end.max(all[j].1.end);
+            j += 1;
+        }
+
+        if pos < start {
+            chunks.push(MergeChunk::Resolved(
+                base_lines[pos..start].iter().map(|s| s.to_string()).collect(),
+            ));
+        }
+
+        let group = &all[i..j];
+        let local: Vec<&Change> = group.iter().filter(|(l, _)| *l).map(|(_, c)| c).collect();
+        let remote: Vec<&Change> = group.iter().filter(|(l, _)| !*l).map(|(_, c)| c).collect();
+        let local_text = apply_changes(&base_lines, start, end, &local);
+        let remote_text = apply_changes(&base_lines, start, end, &remote);
+        if local.is_empty() || remote.is_empty() || local_text == remote_text {
+            let text = if local.is_empty() { remote_text } else { local_text };
+            chunks.push(MergeChunk::Resolved(text));
+        } else {
+            chunks.push(MergeChunk::Conflict {
+                base: base_lines[start..end].iter().map(|s| s.to_string()).collect(),
+                local: local_text,
+                remote: remote_text,
+                choice: None,
+            });
+        }
+
+        pos = end;
+        i = j;
+    }
+    if pos < base_lines.len() {
+        chunks.push(MergeChunk::Resolved(
+            base_lines[pos..].iter().map(|s| s.to_string()).collect(),
+        ));
+    }
+    chunks
+}
+
+pub(crate) fn conflict_count(chunks: &[MergeChunk]) -> usize {
+    chunks
+        .iter()
+        .filter(|c| matches!(c, MergeChunk::Conflict { .. }))
+        .count()
+}
+
+pub(crate) fn unresolved_count(chunks: &[MergeChunk]) -> usize {
+    chunks
+        .iter()
+        .filter(|c| matches!(c, MergeChunk::Conflict { choice: None, .. }))
+        .count()
+}
+
+/// Merged text, or `None` while a conflict has no choice yet.
+pub(crate) fn merge_result(chunks: &[MergeChunk]) -> Option<String> {
+    let mut out = String::new();
+    let mut push = |lines: &[String]| {
+        for line in lines {
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(line);
+        }
+    };
+    for chunk in chunks {
+        match chunk {
+            MergeChunk::Resolved(lines) => push(lines),
+            MergeChunk::Conflict { local, remote, choice, .. } => match choice {
+                None => return None,
+                Some(ConflictChoice::Local) => push(local),
+                Some(ConflictChoice::Remote) => push(remote),
+                Some(ConflictChoice::Both) => {
+                    push(local);
+                    push(remote);
+                }
+            },
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "a\nb\nc\nd\ne\n";
+
+    fn lines(text: &str) -> Vec<String> {
+        text.split_inclusive('\n').map(str::to_string).collect()
+    }
+
+    fn choose(chunks: &mut [MergeChunk], pick: ConflictChoice) {
+        for chunk in chunks {
+            if let MergeChunk::Conflict { choice, .. } = chunk {
+                *choice = Some(pick);
+            }
+        }
+    }
+
+    #[test]
+    fn unchanged_merges_to_base() {
+        let chunks = merge3(BASE, BASE, BASE);
+        assert_eq!(chunks, [MergeChunk::Resolved(lines(BASE))]);
+        assert_eq!(merge_result(&chunks).as_deref(), Some(BASE));
+    }
+
+    #[test]
+    fn edits_on_different_lines_merge_cleanly() {
+        let local = "A\nb\nc\nd\ne\n";
+        let remote = "a\nb\nc\nd\nE\nf\n";
+        let chunks = merge3(BASE, local, remote);
+        assert_eq!(conflict_count(&chunks), 0);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("A\nb\nc\nd\nE\nf\n"));
+    }
+
+    #[test]
+    fn identical_edits_are_not_a_conflict() {
+        let both = "a\nB\nc\nd\ne\n";
+        let chunks = merge3(BASE, both, both);
+        assert_eq!(conflict_count(&chunks), 0);
+        assert_eq!(merge_result(&chunks).as_deref(), Some(both));
+    }
+
+    #[test]
+    fn same_line_edited_on_both_sides_conflicts() {
+        let mut chunks = merge3(BASE, "a\nb\nLOCAL\nd\ne\n", "a\nb\nREMOTE\nd\ne\n");
+        assert_eq!(conflict_count(&chunks), 1);
+        assert_eq!(
+            chunks[1],
+            MergeChunk::Conflict {
+                base: lines("c\n"),
+                local: lines("LOCAL\n"),
+                remote: lines("REMOTE\n"),
+                choice: None,
+            }
+        );
+        assert_eq!(unresolved_count(&chunks), 1);
+        assert_eq!(merge_result(&chunks), None);
+
+        choose(&mut chunks, ConflictChoice::Remote);
+        assert_eq!(unresolved_count(&chunks), 0);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("a\nb\nREMOTE\nd\ne\n"));
+        choose(&mut chunks, ConflictChoice::Both);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("a\nb\nLOCAL\nREMOTE\nd\ne\n"));
+    }
+
+    #[test]
+    fn adjacent_edits_form_one_conflict() {
+        // Local changes b, remote deletes b and c: the overlapping region conflicts as a whole.
+        let mut chunks = merge3(BASE, "a\nB\nc\nd\ne\n", "a\nd\ne\n");
+        assert_eq!(conflict_count(&chunks), 1);
+        choose(&mut chunks, ConflictChoice::Local);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("a\nB\nc\nd\ne\n"));
+    }
+
+    #[test]
+    fn inserts_at_the_same_place_conflict() {
+        let mut chunks = merge3("a\nb\n", "a\nlocal\nb\n", "a\nremote\nb\n");
+        assert_eq!(conflict_count(&chunks), 1);
+        choose(&mut chunks, ConflictChoice::Both);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("a\nlocal\nremote\nb\n"));
+    }
+
+    #[test]
+    fn missing_final_newline_is_kept_apart() {
+        let chunks = merge3("a\nb", "a\nb", "a\nb\nc");
+        assert_eq!(conflict_count(&chunks), 0);
+        assert_eq!(merge_result(&chunks).as_deref(), Some("a\nb\nc"));
+    }
+}
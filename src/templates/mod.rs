@@ -0,0 +1,29 @@
+//! Overlay template helpers that do not depend on the UI.
+
+mod assets;
+mod bundle;
+mod diff;
+mod find;
+mod highlight;
+mod lint;
+mod merge;
+mod params;
+mod search;
+mod sources;
+mod starters;
+mod version_label;
+mod workspace;
+
+pub(crate) use assets::*;
+pub(crate) use bundle::*;
+pub(crate) use diff::*;
+pub(crate) use find::*;
+pub(crate) use highlight::*;
+pub(crate) use lint::*;
+pub(crate) use merge::*;
+pub(crate) use params::*;
+pub(crate) use search::*;
+pub(crate) use sources::*;
+pub(crate) use starters::*;
+pub(crate) use version_label::*;
+pub(crate) use workspace::*;
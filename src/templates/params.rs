@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `id` of the `<script type="application/json">` block in `index.html` that declares
+/// the template's parameters.
+pub(crate) const PARAMS_SCRIPT_ID: &str = "twitchdesk-params";
+
+/// Query parameter the preview URLs already use; templates can't declare it.
+const RESERVED_PARAM: &str = "mock";
+
+/// Inserted by "Add parameters" in the editor.
+const EXAMPLE_MANIFEST: &str = r##"<script type="application/json" id="twitchdesk-params">
+{
+  "params": [
+    { "name": "accent", "type": "color", "label": "Accent color", "default": "#7c5cff" },
+    { "name": "title", "type": "text", "label": "Title", "default": "Follower goal" },
+    { "name": "goal", "type": "number", "label": "Goal", "default": 100, "min": 1, "step": 1 },
+    { "name": "font", "type": "select", "label": "Font", "options": ["Inter", "Roboto", "Comic Neue"] },
+    { "name": "show_avatar", "type": "boolean", "label": "Show avatar", "default": true }
+  ]
+}
+</script>
+"##;
+
+/// Parameters a template declares in its manifest.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct ParamSchema {
+    pub params: Vec<TemplateParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct TemplateParam {
+    /// Query parameter name the template reads.
+    pub name: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub default: Option<Value>,
+    #[serde(flatten)]
+    pub kind: ParamKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ParamKind {
+    Text,
+    Number {
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+        #[serde(default)]
+        step: Option<f64>,
+    },
+    Boolean,
+    Color,
+    Select {
+        options: Vec<String>,
+    },
+}
+
+impl TemplateParam {
+    pub(crate) fn label(&self) -> &str {
+        if self.label.trim().is_empty() {
+            &self.name
+        } else {
+            &self.label
+        }
+    }
+
+    /// The manifest default as a query value, or the type's own default.
+    pub(crate) fn default_value(&self) -> String {
+        match &self.default {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Bool(b)) => b.to_string(),
+            Some(Value::Number(n)) => n.as_f64().map(format_number).unwrap_or_else(|| n.to_string()),
+            Some(Value::Null) | None => match &self.kind {
+                ParamKind::Text => String::new(),
+                ParamKind::Number { min, .. } => format_number(min.unwrap_or(0.0).max(0.0)),
+                ParamKind::Boolean => "false".to_string(),
+                ParamKind::Color => "#ffffff".to_string(),
+                ParamKind::Select { options } => options.first().cloned().unwrap_or_default(),
+            },
+            Some(other) => other.to_string(),
+        }
+    }
+
+    pub(crate) fn is_valid(&self, value: &str) -> bool {
+        match &self.kind {
+            ParamKind::Text => true,
+            ParamKind::Number { min, max, .. } => value.trim().parse::<f64>().is_ok_and(|n| {
+                n.is_finite() && min.is_none_or(|min| n >= min) && max.is_none_or(|max| n <= max)
+            }),
+            ParamKind::Boolean => matches!(value, "true" | "false"),
+            ParamKind::Color => parse_hex_color(value).is_some(),
+            ParamKind::Select { options } => options.iter().any(|o| o == value),
+        }
+    }
+
+    /// `value` if it still fits the schema, otherwise the default.
+    pub(crate) fn resolve(&self, value: Option<&String>) -> String {
+        value
+            .filter(|v| self.is_valid(v))
+            .cloned()
+            .unwrap_or_else(|| self.default_value())
+    }
+}
+
+impl ParamSchema {
+    fn validate(&self) -> Result<()> {
+        let mut seen = HashSet::new();
+        for p in &self.params {
+            let valid_name = !p.name.is_empty()
+                && p.name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid_name {
+                anyhow::bail!("parameter name {:?} may only use letters, digits, _ and -", p.name);
+            }
+            if p.name == RESERVED_PARAM {
+                anyhow::bail!("parameter name {RESERVED_PARAM:?} is reserved for mock previews");
+            }
+            if !seen.insert(p.name.as_str()) {
+                anyhow::bail!("parameter {:?} is declared twice", p.name);
+            }
+            if let ParamKind::Select { options } = &p.kind {
+                if options.is_empty() {
+                    anyhow::bail!("select parameter {:?} has no options", p.name);
+                }
+            }
+            if p.default.is_some() && !p.is_valid(&p.default_value()) {
+                anyhow::bail!("default of parameter {:?} does not match its type", p.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Every parameter with its stored value (or default), in manifest order.
+    pub(crate) fn resolve(&self, values: &BTreeMap<String, String>) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .map(|p| (p.name.clone(), p.resolve(values.get(&p.name))))
+            .collect()
+    }
+}
+
+/// The manifest declared in `index_html`, if it has one.
+pub(crate) fn read_param_schema(index_html: &str) -> Result<Option<ParamSchema>> {
+    let lower = index_html.to_ascii_lowercase();
+    let Some(id_at) = [
+        format!("id=\"{PARAMS_SCRIPT_ID}\""),
+        format!("id='{PARAMS_SCRIPT_ID}'"),
+    ]
+    .iter()
+    .find_map(|needle| lower.find(needle.as_str())) else {
+        return Ok(None);
+    };
+    let body_start = lower[id_at..]
+        .find('>')
+        .map(|i| id_at + i + 1)
+        .context("parameter manifest: unterminated <script> tag")?;
+    let body_end = lower[body_start..]
+        .find("</script")
+        .map(|i| body_start + i)
+        .context("parameter manifest: missing </script>")?;
+    let schema: ParamSchema = serde_json::from_str(&index_html[body_start..body_end])
+        .context("parameter manifest is not valid JSON")?;
+    schema.validate().context("parameter manifest")?;
+    Ok(Some(schema))
+}
+
+/// `index_html` with an example manifest added before `</head>` (or at the top).
+pub(crate) fn with_example_param_manifest(index_html: &str) -> String {
+    let mut html = index_html.to_string();
+    match html.to_ascii_lowercase().find("</head>") {
+        Some(idx) => html.insert_str(idx, EXAMPLE_MANIFEST),
+        None => html.insert_str(0, EXAMPLE_MANIFEST),
+    }
+    html
+}
+
+/// Query string for a preview URL: the parameters, then `mock=true` if requested.
+pub(crate) fn preview_query(params: &[(String, String)], mock: bool) -> String {
+    let mut pairs: Vec<String> = params
+        .iter()
+        .map(|(k, v)| format!("{}={}", urlencoding::encode(k), urlencoding::encode(v)))
+        .collect();
+    if mock {
+        pairs.push(format!("{RESERVED_PARAM}=true"));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("?{}", pairs.join("&"))
+    }
+}
+
+/// `#rgb` or `#rrggbb`.
+pub(crate) fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => {
+            let mut rgb = [0u8; 3];
+            for (i, c) in hex.chars().enumerate() {
+                let v = c.to_digit(16)? as u8;
+                rgb[i] = v * 17;
+            }
+            Some(rgb)
+        }
+        6 => Some([
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        ]),
+        _ => None,
+    }
+}
+
+pub(crate) fn hex_color(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// Whole numbers without a trailing `.0`.
+pub(crate) fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        n.to_string()
+    }
+}
@@ -0,0 +1,80 @@
+use std::cmp::Ordering;
+
+use crate::models::{TemplateListItem, TemplateVersionSummary};
+
+use super::{compare_version_labels, TemplateSources};
+
+/// Order of the template list.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum TemplateSort {
+    #[default]
+    Name,
+    /// Most recently updated first.
+    Updated,
+}
+
+impl TemplateSort {
+    pub(crate) const ALL: [TemplateSort; 2] = [TemplateSort::Name, TemplateSort::Updated];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            TemplateSort::Name => "Name",
+            TemplateSort::Updated => "Last updated",
+        }
+    }
+
+    pub(crate) fn compare(self, a: &TemplateListItem, b: &TemplateListItem) -> Ordering {
+        let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.id.cmp(&b.id));
+        match self {
+            TemplateSort::Name => by_name(),
+            TemplateSort::Updated => b.updated_at.cmp(&a.updated_at).then_with(by_name),
+        }
+    }
+}
+
+/// Tags from a comma separated field: trimmed, without empties or case-insensitive
+/// duplicates, sorted.
+pub(crate) fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags.sort_by_key(|t| t.to_lowercase());
+    tags
+}
+
+pub(crate) fn has_tag(template: &TemplateListItem, tag: &str) -> bool {
+    template.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Whether `needle` (already lowercase) occurs in any of the three files, ignoring case.
+pub(crate) fn sources_contain(sources: &TemplateSources, needle: &str) -> bool {
+    [&sources.index_html, &sources.style_css, &sources.overlay_js]
+        .iter()
+        .any(|text| text.to_lowercase().contains(needle))
+}
+
+/// Whether the latest version (highest label) is not the live one. `contents` looks up
+/// the saved sources of a version; when both are known a latest version with the same
+/// contents as the published one doesn't count. Templates that were never published
+/// count as long as they have a version.
+pub(crate) fn has_unpublished_changes<'a>(
+    versions: &[TemplateVersionSummary],
+    contents: impl Fn(&TemplateVersionSummary) -> Option<&'a TemplateSources>,
+) -> bool {
+    let Some(latest) = versions.iter().max_by(|a, b| compare_version_labels(&a.version, &b.version)) else {
+        return false;
+    };
+    let Some(published) = versions.iter().find(|v| v.is_published) else {
+        return true;
+    };
+    if latest.version == published.version {
+        return false;
+    }
+    match (contents(latest), contents(published)) {
+        (Some(a), Some(b)) => a != b,
+        _ => true,
+    }
+}
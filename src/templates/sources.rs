@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{TemplateVersionResponse, TemplateVersionUpdateRequest};
+
+/// The three files that make up a template version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum TemplateFile {
+    Html,
+    Css,
+    Js,
+}
+
+impl TemplateFile {
+    pub(crate) const ALL: [TemplateFile; 3] = [TemplateFile::Html, TemplateFile::Css, TemplateFile::Js];
+
+    /// File name inside a version folder / bundle.
+    pub(crate) fn file_name(self) -> &'static str {
+        match self {
+            TemplateFile::Html => "index.html",
+            TemplateFile::Css => "style.css",
+            TemplateFile::Js => "overlay.js",
+        }
+    }
+}
+
+/// Contents of a template version, independent of where they came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct TemplateSources {
+    pub index_html: String,
+    pub style_css: String,
+    pub overlay_js: String,
+}
+
+impl TemplateSources {
+    pub(crate) fn get(&self, file: TemplateFile) -> &str {
+        match file {
+            TemplateFile::Html => &self.index_html,
+            TemplateFile::Css => &self.style_css,
+            TemplateFile::Js => &self.overlay_js,
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, file: TemplateFile) -> &mut String {
+        match file {
+            TemplateFile::Html => &mut self.index_html,
+            TemplateFile::Css => &mut self.style_css,
+            TemplateFile::Js => &mut self.overlay_js,
+        }
+    }
+
+    pub(crate) fn to_update_request(&self) -> TemplateVersionUpdateRequest {
+        TemplateVersionUpdateRequest {
+            index_html: self.index_html.clone(),
+            style_css: self.style_css.clone(),
+            overlay_js: self.overlay_js.clone(),
+        }
+    }
+}
+
+impl From<&TemplateVersionResponse> for TemplateSources {
+    fn from(v: &TemplateVersionResponse) -> Self {
+        Self {
+            index_html: v.index_html.clone(),
+            style_css: v.style_css.clone(),
+            overlay_js: v.overlay_js.clone(),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::{read_template_bundle, TemplateFile, TemplateSources, BUNDLE_EXTENSION};
+
+/// Optional sidecar in a team starter folder.
+const STARTER_META_FILE: &str = "starter.json";
+
+/// A template "New from starter" can create.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StarterTemplate {
+    /// Stable key (used for thumbnails and selection).
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub sources: TemplateSources,
+    /// Folder or bundle a team starter was read from; `None` for built-in ones.
+    pub path: Option<PathBuf>,
+}
+
+impl StarterTemplate {
+    /// Changes whenever the sources do; thumbnails are cached under it.
+    pub(crate) fn content_key(&self) -> String {
+        let mut h = DefaultHasher::new();
+        self.sources.hash(&mut h);
+        format!("{}-{:016x}", self.id, h.finish())
+    }
+}
+
+macro_rules! builtin_starter {
+    ($id:literal, $name:literal, $description:literal) => {
+        StarterTemplate {
+            id: concat!("builtin:", $id).to_string(),
+            name: $name.to_string(),
+            description: $description.to_string(),
+            sources: TemplateSources {
+                index_html: include_str!(concat!("../../assets/starters/", $id, "/index.html")).to_string(),
+                style_css: include_str!(concat!("../../assets/starters/", $id, "/style.css")).to_string(),
+                overlay_js: include_str!(concat!("../../assets/starters/", $id, "/overlay.js")).to_string(),
+            },
+            path: None,
+        }
+    };
+}
+
+/// Starters shipped in the binary.
+pub(crate) fn builtin_starters() -> Vec<StarterTemplate> {
+    vec![
+        builtin_starter!(
+            "alert-box",
+            "Alert box",
+            "Queued follow, sub, resub, cheer and raid alerts with a configurable accent and duration."
+        ),
+        builtin_starter!(
+            "chat-overlay",
+            "Chat overlay",
+            "The last few chat messages, newest at the bottom."
+        ),
+        builtin_starter!(
+            "follower-goal",
+            "Follower goal bar",
+            "Progress bar towards a follower goal that fills up on each follow."
+        ),
+        builtin_starter!(
+            "now-live-banner",
+            "Now live banner",
+            "Lower-third banner with the channel name and a pulsing LIVE badge."
+        ),
+        builtin_starter!(
+            "ai-alert-text",
+            "AI alert text box",
+            "Types out AI alert text one alert at a time."
+        ),
+    ]
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StarterMeta {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Team starters in `dir`: sub-folders with an `index.html` (plus optional `style.css`,
+/// `overlay.js` and `starter.json` with a name and description) and template bundles,
+/// which contribute their published version (or their last one). Entries that can't be
+/// read are returned as errors next to the starters that could.
+pub(crate) fn load_team_starters(dir: &Path) -> Result<(Vec<StarterTemplate>, Vec<String>)> {
+    let entries = fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+
+    let mut starters = vec![];
+    let mut errors = vec![];
+    for path in paths {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let starter = if path.is_dir() && path.join(TemplateFile::Html.file_name()).is_file() {
+            read_starter_dir(&path)
+        } else if path.is_file() && file_name.ends_with(&format!(".{BUNDLE_EXTENSION}")) {
+            read_starter_bundle(&path)
+        } else {
+            continue;
+        };
+        match starter {
+            Ok(s) => starters.push(s),
+            Err(e) => errors.push(format!("{file_name}: {e:#}")),
+        }
+    }
+    Ok((starters, errors))
+}
+
+fn read_starter_dir(dir: &Path) -> Result<StarterTemplate> {
+    let mut sources = TemplateSources::default();
+    for file in TemplateFile::ALL {
+        let path = dir.join(file.file_name());
+        if path.exists() {
+            *sources.get_mut(file) =
+                fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        }
+    }
+    let meta = match fs::read_to_string(dir.join(STARTER_META_FILE)) {
+        Ok(raw) => serde_json::from_str::<StarterMeta>(&raw).context("parse starter.json")?,
+        Err(_) => StarterMeta::default(),
+    };
+    let folder = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(StarterTemplate {
+        id: format!("team:{folder}"),
+        name: if meta.name.trim().is_empty() { folder } else { meta.name },
+        description: meta.description,
+        sources,
+        path: Some(dir.to_path_buf()),
+    })
+}
+
+fn read_starter_bundle(path: &Path) -> Result<StarterTemplate> {
+    let bundle = read_template_bundle(path)?;
+    let version = bundle
+        .versions
+        .iter()
+        .find(|v| v.is_published)
+        .or(bundle.versions.last())
+        .context("bundle has no versions")?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(StarterTemplate {
+        id: format!("team:{file_name}"),
+        description: format!("Version {} from {file_name}", version.version),
+        name: bundle.name.clone(),
+        sources: version.sources.clone(),
+        path: Some(path.to_path_buf()),
+    })
+}
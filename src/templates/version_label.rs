@@ -0,0 +1,230 @@
+use std::cmp::Ordering;
+
+use anyhow::Result;
+use semver::Version;
+
+use crate::models::TemplateVersionSummary;
+
+/// A version label the app understands: `3`, `1.4.0` or `v1.4.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum VersionLabel {
+    Integer(u64),
+    Semver(Version),
+}
+
+impl VersionLabel {
+    pub(crate) fn parse(label: &str) -> Option<Self> {
+        let label = label.trim();
+        let bare = label.strip_prefix(['v', 'V']).unwrap_or(label);
+        if !bare.is_empty() && bare.bytes().all(|b| b.is_ascii_digit()) {
+            return bare.parse().ok().map(VersionLabel::Integer);
+        }
+        Version::parse(bare).ok().map(VersionLabel::Semver)
+    }
+
+    /// Integers order like `n.0.0`.
+    fn as_semver(&self) -> Version {
+        match self {
+            VersionLabel::Integer(n) => Version::new(*n, 0, 0),
+            VersionLabel::Semver(v) => v.clone(),
+        }
+    }
+}
+
+/// Order of two version labels: by number for integer and semver labels, with labels the
+/// app doesn't understand before them (alphabetically).
+pub(crate) fn compare_version_labels(a: &str, b: &str) -> Ordering {
+    match (VersionLabel::parse(a), VersionLabel::parse(b)) {
+        (Some(x), Some(y)) => x.as_semver().cmp(&y.as_semver()).then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Newest version first.
+pub(crate) fn sort_versions(versions: &mut [TemplateVersionSummary]) {
+    versions.sort_by(|a, b| compare_version_labels(&b.version, &a.version));
+}
+
+/// How "Create from current" picks the next label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VersionBump {
+    /// Integer labels: `3` → `4`.
+    Next,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl VersionBump {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            VersionBump::Next => "Next",
+            VersionBump::Patch => "Patch",
+            VersionBump::Minor => "Minor",
+            VersionBump::Major => "Major",
+        }
+    }
+}
+
+/// Labels following the highest of `existing`: the next integer for integer labels,
+/// patch/minor/major bumps for semver ones (keeping a `v` prefix), minus any that would
+/// overflow. `1` for a template without versions; nothing if no label can be understood.
+pub(crate) fn suggest_next_versions<'a>(existing: impl IntoIterator<Item = &'a str>) -> Vec<(VersionBump, String)> {
+    let existing: Vec<&str> = existing.into_iter().collect();
+    if existing.is_empty() {
+        return vec![(VersionBump::Next, "1".to_string())];
+    }
+    let Some(highest) = existing
+        .iter()
+        .copied()
+        .filter(|l| VersionLabel::parse(l).is_some())
+        .max_by(|a, b| compare_version_labels(a, b))
+    else {
+        return vec![];
+    };
+    let prefix = if highest.trim().starts_with(['v', 'V']) { &highest.trim()[..1] } else { "" };
+    match VersionLabel::parse(highest) {
+        Some(VersionLabel::Integer(n)) => n
+            .checked_add(1)
+            .map(|next| (VersionBump::Next, format!("{prefix}{next}")))
+            .into_iter()
+            .collect(),
+        Some(VersionLabel::Semver(v)) => {
+            // A pre-release bumps to its release: 1.2.0-beta.1 → 1.2.0.
+            let patch = if v.pre.is_empty() {
+                v.patch.checked_add(1).map(|p| Version::new(v.major, v.minor, p))
+            } else {
+                Some(Version::new(v.major, v.minor, v.patch))
+            };
+            [
+                (VersionBump::Patch, patch),
+                (VersionBump::Minor, v.minor.checked_add(1).map(|m| Version::new(v.major, m, 0))),
+                (VersionBump::Major, v.major.checked_add(1).map(|m| Version::new(m, 0, 0))),
+            ]
+            .into_iter()
+            // A component at u64::MAX has no next value.
+            .filter_map(|(bump, v)| Some((bump, format!("{prefix}{}", v?))))
+            .collect()
+        }
+        None => vec![],
+    }
+}
+
+/// Check a label for a new version: it must be an integer or semver label and must not
+/// match an existing one (`2`, `v2` and `2.0.0` count as the same version).
+pub(crate) fn check_new_version_label<'a>(label: &str, existing: impl IntoIterator<Item = &'a str>) -> Result<()> {
+    let label = label.trim();
+    if label.is_empty() {
+        anyhow::bail!("Missing new version");
+    }
+    let Some(parsed) = VersionLabel::parse(label) else {
+        anyhow::bail!("\"{label}\" is not a version label; use a number (3) or semver (1.4.0)");
+    };
+    let parsed = parsed.as_semver();
+    for other in existing {
+        let same = other.trim() == label
+            || VersionLabel::parse(other).is_some_and(|o| o.as_semver() == parsed);
+        if same {
+            anyhow::bail!("Version {label} already exists (as {})", other.trim());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(version: &str) -> TemplateVersionSummary {
+        serde_json::from_value(serde_json::json!({
+            "version": version,
+            "is_published": false,
+            "updated_at": "",
+        }))
+        .unwrap()
+    }
+
+    fn suggested(existing: &[&str]) -> Vec<String> {
+        suggest_next_versions(existing.iter().copied())
+            .into_iter()
+            .map(|(_, label)| label)
+            .collect()
+    }
+
+    #[test]
+    fn parses_integer_and_semver_labels() {
+        assert_eq!(VersionLabel::parse("3"), Some(VersionLabel::Integer(3)));
+        assert_eq!(VersionLabel::parse(" v12 "), Some(VersionLabel::Integer(12)));
+        assert_eq!(VersionLabel::parse("V1.4.0"), Some(VersionLabel::Semver(Version::new(1, 4, 0))));
+        assert!(matches!(VersionLabel::parse("1.0.0-beta.1"), Some(VersionLabel::Semver(_))));
+        for label in ["", "v", "1.4", "draft", "v-1", "1.0.0.0"] {
+            assert_eq!(VersionLabel::parse(label), None, "{label}");
+        }
+    }
+
+    #[test]
+    fn orders_numerically_with_unknown_labels_first() {
+        let mut versions: Vec<_> = ["10", "draft", "2", "1.5.0", "v3", "2.0.0-rc.1", "alpha"]
+            .into_iter()
+            .map(summary)
+            .collect();
+        sort_versions(&mut versions);
+        let order: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(order, ["10", "v3", "2", "2.0.0-rc.1", "1.5.0", "draft", "alpha"]);
+    }
+
+    #[test]
+    fn equal_versions_order_by_label() {
+        // `2`, `v2` and `2.0.0` are the same version; the order is still total.
+        assert_eq!(compare_version_labels("2", "2.0.0"), "2".cmp("2.0.0"));
+        assert_eq!(compare_version_labels("v2", "2"), "v2".cmp("2"));
+        assert_eq!(compare_version_labels("2", "2"), Ordering::Equal);
+    }
+
+    #[test]
+    fn suggests_the_next_label() {
+        assert_eq!(suggested(&[]), ["1"]);
+        assert_eq!(suggested(&["1", "3", "2"]), ["4"]);
+        assert_eq!(suggested(&["v9", "v10"]), ["v11"]);
+        assert_eq!(suggested(&["1.2.3", "1.10.0"]), ["1.10.1", "1.11.0", "2.0.0"]);
+        assert_eq!(suggested(&["v1.2.0"]), ["v1.2.1", "v1.3.0", "v2.0.0"]);
+        assert_eq!(suggested(&["1.2.0-beta.1"]), ["1.2.0", "1.3.0", "2.0.0"]);
+        // The highest label decides, even across kinds.
+        assert_eq!(suggested(&["3", "2.5.0"]), ["4"]);
+        assert_eq!(suggested(&["draft", "2"]), ["3"]);
+        assert!(suggested(&["draft", "final"]).is_empty());
+    }
+
+    #[test]
+    fn skips_suggestions_that_overflow() {
+        assert!(suggested(&["18446744073709551615"]).is_empty());
+        assert_eq!(suggested(&["1.0.18446744073709551615"]), ["1.1.0", "2.0.0"]);
+        assert_eq!(suggested(&["18446744073709551615.0.0"]), ["18446744073709551615.0.1", "18446744073709551615.1.0"]);
+    }
+
+    #[test]
+    fn suggestion_kinds() {
+        let kinds: Vec<VersionBump> = suggest_next_versions(["1.0.0"]).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(kinds, [VersionBump::Patch, VersionBump::Minor, VersionBump::Major]);
+        assert_eq!(suggest_next_versions(["1"])[0].0, VersionBump::Next);
+    }
+
+    #[test]
+    fn rejects_colliding_labels() {
+        for (label, existing) in [("2", "v2"), ("v2", "2"), ("2.0.0", "2"), ("2", "2.0.0"), ("V1.0.0", "v1.0.0")] {
+            let err = check_new_version_label(label, [existing]).unwrap_err();
+            assert_eq!(err.to_string(), format!("Version {label} already exists (as {existing})"));
+        }
+        assert!(check_new_version_label("2.0.1", ["2", "v2.0.0"]).is_ok());
+        assert!(check_new_version_label("2.0.0-rc.1", ["2"]).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_labels() {
+        assert_eq!(check_new_version_label("  ", []).unwrap_err().to_string(), "Missing new version");
+        let err = check_new_version_label("final", []).unwrap_err().to_string();
+        assert!(err.starts_with("\"final\" is not a version label"), "{err}");
+    }
+}
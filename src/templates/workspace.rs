@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::write_file_atomic;
+
+use super::{TemplateFile, TemplateSources};
+
+/// Sidecar written into each version folder so it can be traced back to its remote version.
+const WORKSPACE_META_FILE: &str = ".twitchdesk.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WorkspaceMeta {
+    pub template_id: String,
+    pub template_name: String,
+    pub version: String,
+    /// `updated_at` of the server copy the files were last synced with.
+    pub remote_updated_at: String,
+}
+
+/// Size + mtime of one file; cheap to poll for external edits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+pub(crate) type FileStamps = [Option<FileStamp>; 3];
+
+/// `<root>/<template>/<version>`, with both names made safe to use as folder names.
+pub(crate) fn workspace_version_dir(root: &Path, template_name: &str, version: &str) -> PathBuf {
+    root.join(safe_dir_name(template_name))
+        .join(safe_dir_name(version))
+}
+
+pub(crate) fn safe_dir_name(name: &str) -> String {
+    let cleaned: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.').trim_end_matches(['.', ' ']);
+    if cleaned.is_empty() {
+        "_".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+pub(crate) fn write_workspace_version(
+    dir: &Path,
+    sources: &TemplateSources,
+    meta: &WorkspaceMeta,
+) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    for file in TemplateFile::ALL {
+        write_file_atomic(&dir.join(file.file_name()), sources.get(file).as_bytes())?;
+    }
+    write_workspace_meta(dir, meta)
+}
+
+pub(crate) fn write_workspace_meta(dir: &Path, meta: &WorkspaceMeta) -> Result<()> {
+    let raw = serde_json::to_string_pretty(meta)?;
+    write_file_atomic(&dir.join(WORKSPACE_META_FILE), raw.as_bytes())
+}
+
+pub(crate) fn read_workspace_version(dir: &Path) -> Result<TemplateSources> {
+    let mut sources = TemplateSources::default();
+    for file in TemplateFile::ALL {
+        let path = dir.join(file.file_name());
+        *sources.get_mut(file) =
+            fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    }
+    Ok(sources)
+}
+
+/// `true` if the folder already contains at least one template file.
+pub(crate) fn workspace_version_exists(dir: &Path) -> bool {
+    TemplateFile::ALL
+        .iter()
+        .any(|f| dir.join(f.file_name()).exists())
+}
+
+pub(crate) fn workspace_stamps(dir: &Path) -> FileStamps {
+    TemplateFile::ALL.map(|f| {
+        fs::metadata(dir.join(f.file_name()))
+            .ok()
+            .map(|m| FileStamp {
+                len: m.len(),
+                modified: m.modified().ok(),
+            })
+    })
+}
+
+pub(crate) fn read_workspace_meta(dir: &Path) -> Option<WorkspaceMeta> {
+    let raw = fs::read_to_string(dir.join(WORKSPACE_META_FILE)).ok()?;
+    serde_json::from_str(&raw).ok()
+}